@@ -24,10 +24,19 @@ fn main() -> Result<(), eframe::Error> {
         ..Default::default()
     };
 
+    // The workbook file to open, e.g. when the `.w` files are associated with the app.
+    let path = std::env::args_os().nth(1).map(std::path::PathBuf::from);
+
     eframe::run_native(
         "OpenCall",
         options,
-        Box::new(|cc| Box::new(OpenCall::new(cc))),
+        Box::new(|cc| {
+            let mut app = OpenCall::new(cc);
+            if let Some(path) = path {
+                app.open(path);
+            }
+            Box::new(app)
+        }),
     )
 }
 
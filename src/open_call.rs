@@ -1,20 +1,21 @@
 /* egui_demo_app egui.rs v0.25.0 | MIT License | https://github.com/emilk/egui */
 // https://github.com/emilk/egui/blob/master/crates/egui_demo_app/src/wrap_app.rs
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(target_arch = "wasm32")]
 use core::any::Any;
 
 use crate::guide::GuideReader;
-use crate::workbook::app::Workbook;
+use crate::workbook::app::{Workbook, WorkbookPreferences};
 
 pub const OPENCALL_VERSION: &str = "0.8.2";
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
 enum Anchor {
     // Applications in the top bar
     GuideApp,
+    #[default]
     WorkbookApp,
 }
 
@@ -41,12 +42,6 @@ impl From<Anchor> for egui::WidgetText {
     }
 }
 
-impl Default for Anchor {
-    fn default() -> Self {
-        Self::WorkbookApp
-    }
-}
-
 // The state that we persist (serialize).
 #[derive(Default)]
 pub struct State {
@@ -55,33 +50,152 @@ pub struct State {
     selected_anchor: Anchor,
 }
 
-#[derive(Default)]
+// The settings of the user, kept with the preferences between the sessions.
+#[derive(Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
 pub struct Settings {
     pub customer: CustomerAccount,
 }
 
-#[derive(Deserialize, Default)]
+#[derive(Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
 pub struct CustomerAccount {
     pub email: String,
     pub id: String,
+    // The author of new projects, snapshots and exports.
+    pub name: String,
+    pub institution: String,
+    // The code of the default language of the documents, e.g. "en".
+    pub language: String,
+}
+
+// The languages of the documents offered in the preferences.
+const LANGUAGES: [(&str, &str); 9] = [
+    ("", "Not set"),
+    ("en", "English"),
+    ("de", "Deutsch"),
+    ("es", "Español"),
+    ("fr", "Français"),
+    ("it", "Italiano"),
+    ("nl", "Nederlands"),
+    ("pl", "Polski"),
+    ("pt", "Português"),
+];
+
+impl Settings {
+    // Edit the settings, `true` if changed.
+    fn edit(&mut self, ui: &mut egui::Ui) -> bool {
+        let customer = &mut self.customer;
+        let mut changed = false;
+        egui::Grid::new("preferences_author")
+            .num_columns(2)
+            .spacing([12., 6.])
+            .show(ui, |ui| {
+                ui.label("Name:");
+                changed |= ui.text_edit_singleline(&mut customer.name).changed();
+                ui.end_row();
+
+                ui.label("Email:");
+                changed |= ui.text_edit_singleline(&mut customer.email).changed();
+                ui.end_row();
+
+                ui.label("Institution:");
+                changed |= ui.text_edit_singleline(&mut customer.institution).changed();
+                ui.end_row();
+
+                ui.label("Language:");
+                let selected = LANGUAGES
+                    .iter()
+                    .find(|(code, _)| *code == customer.language)
+                    .map_or(customer.language.as_str(), |(_, name)| name);
+                egui::ComboBox::from_id_source("preferences_language")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (code, name) in LANGUAGES {
+                            changed |= ui
+                                .selectable_value(&mut customer.language, code.to_string(), name)
+                                .changed();
+                        }
+                    });
+                ui.end_row();
+            });
+        changed
+    }
+}
+
+// The preferences kept between the sessions.
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+struct Preferences {
+    selected_anchor: Anchor,
+    dark_mode: bool,
+    zoom_factor: f32,
+    workbook: WorkbookPreferences,
+    settings: Settings,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            selected_anchor: Anchor::default(),
+            dark_mode: true,
+            // Variable by the user with `Cmd +/-` and sets to 1.0 with `Cmd 0`.
+            zoom_factor: 1.2,
+            workbook: WorkbookPreferences::default(),
+            settings: Settings::default(),
+        }
+    }
 }
 
 pub struct OpenCall {
     pub state: State,
+    // The context to read the zoom factor and the visuals when saving the preferences.
+    ctx: egui::Context,
+    settings: Settings,
+    settings_open: bool,
 }
 
 impl OpenCall {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        // Set the zoom factor of the UI when the app starts.
-        // Variable by the user with `Cmd +/-` and sets to 1.0 with `Cmd 0`.
-        cc.egui_ctx.set_zoom_factor(1.2);
+        let preferences: Preferences = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+
+        // Set the zoom factor and the visuals of the UI when the app starts.
+        cc.egui_ctx.set_zoom_factor(preferences.zoom_factor);
+        cc.egui_ctx.set_visuals(if preferences.dark_mode {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        });
+
+        // The settings are given to the workbook before the first project is made.
+        let mut state = State {
+            selected_anchor: preferences.selected_anchor,
+            workbook: Workbook::new(preferences.settings.clone()),
+            ..Default::default()
+        };
+        state.workbook.set_preferences(preferences.workbook);
 
         Self {
-            state: State::default(),
+            state,
+            ctx: cc.egui_ctx.clone(),
+            settings: preferences.settings,
+            settings_open: false,
         }
     }
 }
 
+impl OpenCall {
+    // Open the workbook file, e.g. given on the command line.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(&mut self, path: std::path::PathBuf) {
+        self.state.workbook.open_path(path);
+        self.state.selected_anchor = Anchor::WorkbookApp;
+    }
+}
+
 impl eframe::App for OpenCall {
     fn clear_color(&self, visuals: &egui::Visuals) -> [f32; 4] {
         visuals.panel_fill.to_normalized_gamma_f32()
@@ -102,6 +216,11 @@ impl eframe::App for OpenCall {
             ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(!fullscreen));
         }
 
+        // Show the workbook when it opens the file dropped or asks about the changes not saved.
+        if self.state.workbook.track_file(ctx) {
+            self.state.selected_anchor = Anchor::WorkbookApp;
+        }
+
         egui::TopBottomPanel::top("wrap_app_top_bar").show(ctx, |ui| {
             ui.horizontal_wrapped(|ui| {
                 ui.visuals_mut().button_frame = false;
@@ -110,6 +229,18 @@ impl eframe::App for OpenCall {
         });
 
         self.show_selected_app(ctx, frame);
+        self.show_settings(ctx);
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let preferences = Preferences {
+            selected_anchor: self.state.selected_anchor,
+            dark_mode: self.ctx.style().visuals.dark_mode,
+            zoom_factor: self.ctx.zoom_factor(),
+            workbook: self.state.workbook.preferences(),
+            settings: self.settings.clone(),
+        };
+        eframe::set_value(storage, eframe::APP_KEY, &preferences);
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -164,7 +295,37 @@ impl OpenCall {
 
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             egui::widgets::global_dark_light_mode_switch(ui);
+            if ui
+                .button("⚙")
+                .on_hover_text("Preferences: the author of new projects, snapshots and exports")
+                .clicked()
+            {
+                self.settings_open = !self.settings_open;
+            }
             egui::warn_if_debug_build(ui);
         });
     }
+
+    fn show_settings(&mut self, ctx: &egui::Context) {
+        let mut open = self.settings_open;
+        egui::Window::new("Preferences")
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(
+                        "The author of new projects, snapshots and exports. \
+                        The projects made before keep their owner.",
+                    )
+                    .small()
+                    .weak(),
+                );
+                ui.add_space(6.);
+                if self.settings.edit(ui) {
+                    self.state.workbook.settings = self.settings.clone();
+                }
+            });
+        self.settings_open = open;
+    }
 }
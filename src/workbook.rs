@@ -1,11 +1,25 @@
 pub mod app;
 
 mod chapter;
+mod compare;
 mod editor;
+mod exporter;
+mod file;
+mod identity;
+mod import;
 mod legacy_formats;
+mod merge;
 mod note;
+mod notifications;
+mod profiles;
 mod project;
+mod reference_formats;
 mod renderer;
+mod restore;
+mod search;
 mod sections;
+mod snapshots;
+mod snippets;
 mod store;
+mod tabs;
 mod visuals;
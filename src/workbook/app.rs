@@ -1,17 +1,45 @@
-use egui::ScrollArea;
+use chrono::{DateTime, Local};
+use egui::{RichText, ScrollArea};
+use serde::{Deserialize, Serialize};
 use std::future;
+use std::path::PathBuf;
 use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
 
-use super::project::Project;
+use super::compare::Comparison;
+use super::file::{SaveResult, Unsaved, WorkbookFile, SAVE_AS_SHORTCUT, SAVE_SHORTCUT};
+use super::identity::Identity;
+use super::import::Importing;
+use super::legacy_formats::conversion::read_project;
+use super::merge::Merging;
+use super::notifications::{Notification, Notifications};
+use super::profiles::Profiles;
+use super::project::{Project, ProjectOwner};
+use super::reference_formats::citation_style::CitationStyle;
+use super::reference_formats::citations::Citations;
+use super::reference_formats::exchange::{encode_references, ReferenceFormat, ReferenceList};
 use super::renderer::Renderer;
-use super::store::v_b0004::Store;
+use super::restore::Restoring;
+use super::search::{Search, SEARCH_SHORTCUT};
+use super::sections::references::{share_pdf_sender, PickedPdf};
+use super::snapshots::{AutoSnapshots, Snapshot};
+use super::snippets::Snippets;
+use super::store::v_b0005::{Store, DATA_FORMAT_VERSION, FORMAT_GUARD};
+use super::tabs::{Clipboard, Copying, Tab};
+use crate::open_call::Settings;
 
 // The version of data format used for saving workbook on disk
 // for further opening stored files by the desktop app.
 pub const DATA_FORMAT_STORAGE: &str = crate::workbook::store::save::DATA_FORMAT_VERSION;
 
+// The snapshots are not saved in the workbook file.
+const SNAPSHOTS_HINT: &str = "The snapshots are kept until the app is closed, \
+    they are not saved in the workbook file";
+
 pub struct Workbook {
-    pub stored_projects: Vec<Project>,
+    pub stored_projects: Vec<Snapshot>,
+    pub auto_snapshots: AutoSnapshots,
+    pub last_auto_snapshot: DateTime<Local>,
 
     pub project: Project,
 
@@ -22,13 +50,56 @@ pub struct Workbook {
     pub edit_section_titles: bool,
 
     pub windows: Windows,
+    pub notifications: Notifications,
+    // The snippets of the user, kept with the preferences.
+    pub snippets: Snippets,
+    // The profiles of the partners, kept with the preferences.
+    pub profiles: Profiles,
+    // The settings of the user, the owner of new projects and the author
+    // of snapshots and exports.
+    pub settings: Settings,
 
-    pub file_channel: (Sender<Vec<u8>>, Receiver<Vec<u8>>),
+    pub file_channel: (Sender<PickedFile>, Receiver<PickedFile>),
+    // The PDF files chosen for the reading notes of the references.
+    pub pdf_channel: (Sender<PickedPdf>, Receiver<PickedPdf>),
     pub file_buffer: Vec<u8>,
     pub import_state: ImportState,
+
+    // The file of the project, `None` until saved.
+    pub file: Option<WorkbookFile>,
+    // The file being opened.
+    pub opened_file: Option<WorkbookFile>,
+    pub opened_channel: (Sender<WorkbookFile>, Receiver<WorkbookFile>),
+    pub save_channel: (Sender<SaveResult>, Receiver<SaveResult>),
+    // The fingerprint of the project saved or opened.
+    pub saved: u64,
+    pub modified: bool,
+    // The citations of the project resolved for the preview, and the fingerprint
+    // of the project they are resolved for, see `Workbook::cite()`.
+    pub citations: Arc<Citations>,
+    pub cited: u64,
+    // The changes are checked after the input, see `Workbook::check_modified()`.
+    pub check_pending: bool,
+    pub checked_at: f64,
+    // The action waiting for the project to be saved.
+    pub after_save: Option<Unsaved>,
+    pub close_confirmed: bool,
+    // The title of the window shown.
+    pub title: String,
+    // The workbook files opened or saved lately, the latest first.
+    pub recent_files: Vec<PathBuf>,
+    // The file dropped onto the window to be opened.
+    pub dropped_file: Option<egui::DroppedFile>,
+
+    // The workbooks of the other tabs, `None` for the active one.
+    pub tabs: Vec<Option<Tab>>,
+    pub active_tab: usize,
+    // The identifier of the active tab.
+    pub tab: Identity,
+    pub clipboard: Clipboard,
 }
 
-#[derive(PartialEq)]
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq)]
 pub enum PanelSide {
     Left,
     Right,
@@ -38,12 +109,34 @@ pub enum PanelSide {
 pub struct Windows {
     pub about: crate::about::AboutOpenCall,
     pub about_open: bool,
+    pub search: Search,
+    pub search_open: bool,
+    pub compare: Comparison,
+    pub compare_open: bool,
+    pub merge: Merging,
+    pub merge_open: bool,
+    pub import: Importing,
+    pub import_open: bool,
+    pub restore: Restoring,
+    pub restore_open: bool,
+    pub copy: Copying,
+    pub copy_open: bool,
+    pub snippets_open: bool,
+    pub snippets_filter: String,
+    pub profiles_open: bool,
+    pub profiles_filter: String,
+    // The action asking what to do with the changes not saved.
+    pub unsaved: Option<Unsaved>,
+    // The error of saving the workbook.
+    pub save_failed: Option<String>,
 }
 
 impl Default for Workbook {
     fn default() -> Self {
-        Self {
+        let mut workbook = Self {
             stored_projects: Vec::new(),
+            auto_snapshots: AutoSnapshots::default(),
+            last_auto_snapshot: Local::now(),
             project: Project::default(),
             show_preview: false,
             show_controls: true,
@@ -51,50 +144,265 @@ impl Default for Workbook {
             edit_section_titles: false,
 
             windows: Windows::default(),
+            notifications: Notifications::default(),
+            snippets: Snippets::default(),
+            profiles: Profiles::default(),
+            settings: Settings::default(),
 
             file_channel: channel(),
+            pdf_channel: channel(),
             file_buffer: Vec::new(),
             import_state: ImportState::Free,
+
+            file: None,
+            opened_file: None,
+            opened_channel: channel(),
+            save_channel: channel(),
+            saved: 0,
+            modified: false,
+            citations: Arc::default(),
+            cited: 0,
+            check_pending: false,
+            checked_at: f64::NEG_INFINITY,
+            after_save: None,
+            close_confirmed: false,
+            title: String::new(),
+            recent_files: Vec::new(),
+            dropped_file: None,
+
+            tabs: vec![None],
+            active_tab: 0,
+            tab: Identity::new(),
+            clipboard: Clipboard::default(),
+        };
+        workbook.mark_saved(None);
+        workbook
+    }
+}
+
+impl Workbook {
+    // The workbook with the new project of the user.
+    pub fn new(settings: Settings) -> Self {
+        let mut workbook = Self {
+            project: Project::new(ProjectOwner::from(&settings.customer)),
+            settings,
+            ..Default::default()
+        };
+        workbook.mark_saved(None);
+        workbook
+    }
+
+    // The owner of new projects and the author of snapshots.
+    pub fn owner(&self) -> ProjectOwner {
+        ProjectOwner::from(&self.settings.customer)
+    }
+}
+
+// The preferences of the workbook kept between the sessions.
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct WorkbookPreferences {
+    pub show_preview: bool,
+    pub show_controls: bool,
+    pub show_side_panel: PanelSide,
+    pub recent_files: Vec<PathBuf>,
+    pub snippets: Snippets,
+    pub profiles: Profiles,
+    pub auto_snapshots: AutoSnapshots,
+}
+
+impl Default for WorkbookPreferences {
+    // The same as of the `Workbook::default()`.
+    fn default() -> Self {
+        Self {
+            show_preview: false,
+            show_controls: true,
+            show_side_panel: PanelSide::Left,
+            recent_files: Vec::new(),
+            snippets: Snippets::default(),
+            profiles: Profiles::default(),
+            auto_snapshots: AutoSnapshots::default(),
         }
     }
 }
 
+impl Workbook {
+    pub fn preferences(&self) -> WorkbookPreferences {
+        WorkbookPreferences {
+            show_preview: self.show_preview,
+            show_controls: self.show_controls,
+            show_side_panel: self.show_side_panel,
+            recent_files: self.recent_files.clone(),
+            snippets: self.snippets.clone(),
+            profiles: self.profiles.clone(),
+            auto_snapshots: self.auto_snapshots.clone(),
+        }
+    }
+
+    pub fn set_preferences(&mut self, preferences: WorkbookPreferences) {
+        self.show_preview = preferences.show_preview;
+        self.show_controls = preferences.show_controls;
+        self.show_side_panel = preferences.show_side_panel;
+        self.recent_files = preferences.recent_files;
+        self.snippets = preferences.snippets;
+        self.profiles = preferences.profiles;
+        self.auto_snapshots = preferences.auto_snapshots;
+    }
+}
+
 impl Store {
     pub fn encode_bincode(&self) -> Vec<u8> {
         let bytes: Vec<u8> = bincode::serialize(&self).unwrap();
         bytes
     }
 
+    // The workbook in the current format, the other formats are converted.
     pub fn decode_bincode(encoded: &[u8]) -> Result<Self, bincode::Error> {
-        bincode::deserialize(encoded)
+        let store: Self = bincode::deserialize(encoded)?;
+        if store.format != DATA_FORMAT_VERSION || store.guard != FORMAT_GUARD {
+            return Err(Box::new(bincode::ErrorKind::Custom(format!(
+                "unknown format {}",
+                store.format
+            ))));
+        }
+        Ok(store)
     }
 }
 
-#[derive(PartialEq)]
+// The contents of the file picked with the import it is read for.
+pub type PickedFile = (ImportState, Vec<u8>);
+
+// The import the file picked is read for. The state is sent along with
+// the contents, so a dialog cancelled leaves no import pending.
+#[derive(Clone, Copy, PartialEq)]
 pub enum ImportState {
     Free,
     Busy,
+    // Importing references from a reference manager file into the list.
+    References(ReferenceList),
+    // Adding a reference from the metadata of a PDF file to the list.
+    PdfReference(ReferenceList),
+    // Merging another copy of the workbook into the project.
+    Merge,
+    // Importing the sections of another workbook into the project.
+    Sections,
+    // Adding the snippets of a library shared as a file.
+    Snippets,
 }
 
 impl eframe::App for Workbook {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Save/open workbook
-        if let Ok(encoded) = self.file_channel.1.try_recv() {
+        if let Ok(file) = self.opened_channel.1.try_recv() {
+            self.opened_file = Some(file);
+        }
+        if let Ok((import_state, encoded)) = self.file_channel.1.try_recv() {
+            self.import_state = import_state;
             self.file_buffer = encoded;
         }
+        if let Ok((id, path)) = self.pdf_channel.1.try_recv() {
+            if let Some(reference) = self.project.bibliography.get_mut(id) {
+                reference.reading.file = path;
+            }
+        }
+        share_pdf_sender(ctx, self.pdf_channel.0.clone());
 
         if !self.file_buffer.is_empty() && self.import_state != ImportState::Free {
-            if self.import_state == ImportState::Busy {
+            let open_tab = self
+                .opened_file
+                .as_ref()
+                .and_then(|file| file.path.as_deref())
+                .and_then(|path| self.file_tab(path));
+            if let Some(i) = open_tab.filter(|_| self.import_state == ImportState::Busy) {
+                // The file open already is shown in its tab.
+                self.opened_file = None;
+                self.switch_tab(i);
+            } else if self.import_state == ImportState::Busy {
+                // The workbook is opened in a new tab unless the active one is blank.
+                let previous = self.active_tab;
+                if !self.is_blank() {
+                    self.new_tab();
+                }
                 match Store::decode_bincode(&self.file_buffer) {
                     Ok(work) => {
                         self.project = work.into(); // data format version is in the storage struct
+                        self.windows.search.forget_undo();
+                        let file = self.opened_file.take();
+                        self.mark_saved(file);
+                        self.notifications
+                            .push(Notification::info("Opened", self.file_name()));
                     }
                     Err(e) => {
+                        let before = self.project.fingerprint();
                         self.convert_or_err(e);
+                        let file = self.opened_file.take();
+                        // The project converted from an earlier format is saved
+                        // in the current one with the dialog, not over the old file.
+                        if self.project.fingerprint() != before {
+                            self.file = file.map(|file| WorkbookFile { path: None, ..file });
+                            self.modified = true;
+                        } else if self.active_tab != previous {
+                            self.close_tab();
+                            self.switch_tab(previous);
+                        }
+                    }
+                }
+            }
+            if let ImportState::References(list) = self.import_state {
+                self.import_references(list);
+                self.import_state = ImportState::Free;
+            }
+            if let ImportState::PdfReference(list) = self.import_state {
+                self.import_pdf_reference(list);
+                self.import_state = ImportState::Free;
+            }
+            if self.import_state == ImportState::Merge {
+                match read_project(&self.file_buffer) {
+                    Ok(other) => {
+                        self.windows.merge = Merging::with(other);
+                        self.windows.merge_open = true;
+                    }
+                    Err(e) => {
+                        self.notifications.push(Notification::error(
+                            "Merge Error",
+                            format!("The workbook cannot be merged: {}", e),
+                        ));
                     }
                 }
+                self.import_state = ImportState::Free;
+            }
+            if self.import_state == ImportState::Snippets {
+                self.import_snippets();
+                self.import_state = ImportState::Free;
+            }
+            if self.import_state == ImportState::Sections {
+                match read_project(&self.file_buffer) {
+                    Ok(other) => {
+                        self.windows.import = Importing::with(other);
+                        self.windows.import_open = true;
+                    }
+                    Err(e) => {
+                        self.notifications.push(Notification::error(
+                            "Import Error",
+                            format!("The workbook cannot be read: {}", e),
+                        ));
+                    }
+                }
+                self.import_state = ImportState::Free;
             }
             self.file_buffer.clear();
+            // The project may be changed without any input, e.g. the references imported.
+            self.update_modified();
+        }
+
+        // The actions discarding the changes not saved and saving.
+        let mut request = None;
+        let mut save = None;
+        let mut snapshot = false;
+        if ctx.input_mut(|input| input.consume_shortcut(&SAVE_AS_SHORTCUT)) {
+            save = Some(true);
+        } else if ctx.input_mut(|input| input.consume_shortcut(&SAVE_SHORTCUT)) {
+            save = Some(false);
         }
 
         let show_preview = &mut self.show_preview;
@@ -107,69 +415,161 @@ impl eframe::App for Workbook {
                     ui.menu_button("File", |ui| {
                         if ui.button("New").clicked() {
                             ui.close_menu();
-                            self.project.resolution = 1;
-                            *show_preview = false;
-                            self.project = Project::default();
+                            request = Some(Unsaved::New);
                         }
 
                         if ui.button("Open…").clicked() {
                             ui.close_menu();
-                            let sender = self.file_channel.0.clone();
-                            let task = rfd::AsyncFileDialog::new()
-                                .add_filter("Workbook Files", &["w"])
-                                .add_filter("All Files", &["*"])
-                                .pick_file();
-                            execute(async move {
-                                let file = task.await;
-                                if let Some(file) = file {
-                                    let contents = file.read().await;
-                                    let _ = sender.send(contents);
+                            request = Some(Unsaved::Open);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        ui.add_enabled_ui(!self.recent_files.is_empty(), |ui| {
+                            ui.menu_button("Open Recent", |ui| {
+                                for (i, path) in self.recent_files.iter().enumerate() {
+                                    let name = path.file_name().unwrap_or_default();
+                                    if ui
+                                        .button(name.to_string_lossy())
+                                        .on_hover_text(path.display().to_string())
+                                        .clicked()
+                                    {
+                                        ui.close_menu();
+                                        request = Some(Unsaved::OpenRecent(i));
+                                    }
+                                }
+                                ui.separator();
+                                if ui.button("Clear Recent").clicked() {
+                                    ui.close_menu();
+                                    self.recent_files.clear();
                                 }
                             });
-                            self.import_state = ImportState::Busy;
+                        });
+
+                        if ui
+                            .button("Merge with…")
+                            .on_hover_text(
+                                "Merge the notes, programmes, participants and references \
+                                of a copy of this workbook edited by a partner",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            pick_workbook_file(
+                                self.file_channel.0.clone(),
+                                ImportState::Merge,
+                                None,
+                            );
+                        }
+
+                        if ui
+                            .button("Import from workbook…")
+                            .on_hover_text(
+                                "Append the notes of the chosen sections, the programmes, \
+                                participants and references of another workbook, \
+                                e.g. of an earlier proposal",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            pick_workbook_file(
+                                self.file_channel.0.clone(),
+                                ImportState::Sections,
+                                None,
+                            );
                         }
 
                         ui.separator();
 
-                        if ui.button("Make a snapshot").clicked() {
-                            use chrono::{Datelike, Timelike};
-                            let timestamp = chrono::offset::Local::now().naive_local();
-                            // Record the revision by naming it according to the timestamp.
-                            let record = format!(
-                                "v{:02}{:02}{:02} {:}-{:02}-{:02}",
-                                timestamp.hour(),
-                                timestamp.minute(),
-                                timestamp.second(),
-                                timestamp.year(),
-                                timestamp.month(),
-                                timestamp.day()
-                            );
-                            let mut proj = self.project.clone();
-                            proj.record = record;
-                            self.stored_projects.append(&mut vec![proj]);
+                        if ui
+                            .button("Make a snapshot")
+                            .on_hover_text(SNAPSHOTS_HINT)
+                            .clicked()
+                        {
+                            snapshot = true;
                         }
 
                         ui.menu_button("Snapshots", |ui| {
+                            ui.menu_button("Automatic Snapshots", |ui| {
+                                self.auto_snapshots.edit(ui);
+                            });
                             egui::scroll_area::ScrollArea::vertical().show(ui, |ui| {
+                                ui.label(RichText::new(SNAPSHOTS_HINT).small().weak());
                                 if self.stored_projects.is_empty() {
                                     ui.label("…no snapshots found");
                                     return;
                                 }
 
+                                if ui
+                                    .button("Compare…")
+                                    .on_hover_text(
+                                        "Show the texts added, removed and changed \
+                                        between two snapshots or the current project",
+                                    )
+                                    .clicked()
+                                {
+                                    ui.close_menu();
+                                    // The latest snapshot with the current project.
+                                    self.windows.compare =
+                                        Comparison::with_current(self.stored_projects.len() - 1);
+                                    self.windows.compare_open = true;
+                                }
+                                if ui
+                                    .button("Restore…")
+                                    .on_hover_text(
+                                        "Restore the chosen sections or notes of a snapshot, \
+                                        the rest of the current project is kept",
+                                    )
+                                    .clicked()
+                                {
+                                    ui.close_menu();
+                                    self.windows.restore =
+                                        Restoring::with(self.stored_projects.len() - 1);
+                                    self.windows.restore_open = true;
+                                }
+                                ui.separator();
+
                                 ui.spacing_mut().item_spacing.x = 3.;
                                 let mut proj_to_delete = None;
                                 for i in (0..self.stored_projects.len()).rev() {
                                     ui.horizontal(|ui| {
-                                        if ui.button("↺").clicked() {
-                                            self.project = self.stored_projects[i].clone();
+                                        if ui
+                                            .button("↺")
+                                            .on_hover_text(
+                                                "Restore the whole project, \
+                                                the current one is kept as a snapshot",
+                                            )
+                                            .clicked()
+                                        {
+                                            request = Some(Unsaved::Restore(i));
                                             ui.close_menu();
                                         }
+                                        let snapshot = &mut self.stored_projects[i];
                                         ui.add(
                                             egui::TextEdit::singleline(
-                                                &mut self.stored_projects[i].record,
+                                                &mut snapshot.project.record,
                                             )
                                             .desired_width(120.),
-                                        );
+                                        )
+                                        .on_hover_text(snapshot.details());
+                                        let marker = if !snapshot.tag.is_empty() {
+                                            "🏷"
+                                        } else if snapshot.automatic {
+                                            "⏱"
+                                        } else {
+                                            "…"
+                                        };
+                                        ui.menu_button(marker, |ui| snapshot.edit_details(ui))
+                                            .response
+                                            .on_hover_text("Comment and tag");
+                                        if ui
+                                            .button("⇄")
+                                            .on_hover_text("Compare with the current project")
+                                            .clicked()
+                                        {
+                                            ui.close_menu();
+                                            self.windows.compare = Comparison::with_current(i);
+                                            self.windows.compare_open = true;
+                                        }
                                         // ⬆
                                         if ui.button("⬆").clicked()
                                             && i + 1 < self.stored_projects.len()
@@ -189,25 +589,212 @@ impl eframe::App for Workbook {
 
                         ui.separator();
 
-                        if ui.button("Save…").clicked() {
-                            ui.close_menu();
-                            let task = rfd::AsyncFileDialog::new()
-                                .add_filter("Workbook Files", &["w"])
-                                .add_filter("All Files", &["*"])
-                                .set_file_name(format!("{}.w", self.project.working_name.title))
-                                .save_file();
-                            let work: Store = self.project.clone().into();
-                            let contents = work.encode_bincode();
-                            execute(async move {
-                                let file = task.await;
-                                if let Some(file) = file {
-                                    _ = file.write(&contents).await;
+                        ui.menu_button("References", |ui| {
+                            for list in ReferenceList::ALL {
+                                if ui
+                                    .button(format!("Import into {}…", list.name()))
+                                    .on_hover_text(
+                                        "Append references exported from Zotero, Mendeley, \
+                                        EndNote, etc. in RIS or CSL-JSON format",
+                                    )
+                                    .clicked()
+                                {
+                                    ui.close_menu();
+                                    pick_references_file(
+                                        self.file_channel.0.clone(),
+                                        ImportState::References(list),
+                                    );
                                 }
-                            });
+                            }
+
+                            ui.separator();
+
+                            for list in ReferenceList::ALL {
+                                if ui
+                                    .button(format!("Add to {} from PDF…", list.name()))
+                                    .on_hover_text(
+                                        "Fill in the title, authors, year and DOI \
+                                        from the metadata embedded in a PDF file",
+                                    )
+                                    .clicked()
+                                {
+                                    ui.close_menu();
+                                    pick_pdf_file(
+                                        self.file_channel.0.clone(),
+                                        ImportState::PdfReference(list),
+                                    );
+                                }
+                            }
+
+                            ui.separator();
+
+                            for list in ReferenceList::ALL {
+                                ui.menu_button(format!("Export {}", list.name()), |ui| {
+                                    for format in [ReferenceFormat::Ris, ReferenceFormat::CslJson] {
+                                        if ui
+                                            .button(format!("{}…", format.name()))
+                                            .on_hover_text(list.export_hint())
+                                            .clicked()
+                                        {
+                                            ui.close_menu();
+                                            save_file(
+                                                self.notifications.sender(),
+                                                encode_references(
+                                                    &self.project.references(list),
+                                                    format,
+                                                ),
+                                                format!(
+                                                    "{} references.{}",
+                                                    self.project.working_name.title,
+                                                    format.extension()
+                                                ),
+                                                (format.name(), format.extension()),
+                                            );
+                                        }
+                                    }
+                                });
+                            }
+                        });
+
+                        ui.separator();
+
+                        if ui
+                            .add(
+                                egui::Button::new("Save")
+                                    .shortcut_text(ui.ctx().format_shortcut(&SAVE_SHORTCUT)),
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            save = Some(false);
+                        }
+
+                        if ui
+                            .add(
+                                egui::Button::new("Save As…")
+                                    .shortcut_text(ui.ctx().format_shortcut(&SAVE_AS_SHORTCUT)),
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            save = Some(true);
+                        }
+
+                        if ui.button("Close").clicked() {
+                            ui.close_menu();
+                            request = Some(Unsaved::CloseTab);
+                        }
+
+                        ui.separator();
+
+                        if ui
+                            .button("Export as Markdown…")
+                            .on_hover_text(
+                                "Export the document as it is shown in the preview, \
+                                with the references in the chosen citation style",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            for (warning, keys) in self.citations.warnings() {
+                                self.notifications
+                                    .push(Notification::warning(&warning, keys));
+                            }
+                            save_file(
+                                self.notifications.sender(),
+                                self.project
+                                    .export_markdown(&self.settings.customer)
+                                    .into_bytes(),
+                                format!("{}.md", self.project.working_name.title),
+                                ("Markdown", "md"),
+                            );
+                        }
+                    });
+
+                    ui.menu_button("Edit", |ui| {
+                        if ui
+                            .button("Copy…")
+                            .on_hover_text(
+                                "Copy the notes, participants and references \
+                                to paste them into the workbook of another tab",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.windows.copy_open = true;
+                        }
+                        let paste = match self.clipboard.len() {
+                            0 => "Paste".to_string(),
+                            1 => "Paste 1 item".to_string(),
+                            n => format!("Paste {} items", n),
+                        };
+                        if ui
+                            .add_enabled(!self.clipboard.is_empty(), egui::Button::new(paste))
+                            .on_hover_text(
+                                "Append the items copied, the notes to the lists \
+                                of the same sections",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.clipboard.paste(&mut self.project);
                         }
                     });
 
                     ui.menu_button("View", |ui| {
+                        if ui
+                            .add(
+                                egui::Button::new("Search…")
+                                    .shortcut_text(ui.ctx().format_shortcut(&SEARCH_SHORTCUT)),
+                            )
+                            .on_hover_text(
+                                "Search and replace in the notes, titles, participants, programmes \
+                                and references of the project",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.windows.search_open = true;
+                        }
+
+                        if ui
+                            .button(format!("Messages ({})…", self.notifications.log.len()))
+                            .on_hover_text(
+                                "The results of opening, saving, importing and exporting, \
+                                and the warnings",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.notifications.log_open = true;
+                        }
+
+                        if ui
+                            .button("Snippet Library…")
+                            .on_hover_text(
+                                "The texts used in many workbooks, e.g. the description \
+                                of the institution, to insert them into the notes",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.windows.snippets_open = true;
+                        }
+
+                        if ui
+                            .button("Participant Profiles…")
+                            .on_hover_text(
+                                "The partners kept apart from the workbooks \
+                                to add them to the projects",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.windows.profiles_open = true;
+                        }
+
+                        ui.separator();
+
                         if ui.checkbox(show_preview, "Preview").changed() {
                             ui.close_menu()
                         };
@@ -231,6 +818,23 @@ impl eframe::App for Workbook {
                         {
                             // ui.close_menu()
                         };
+
+                        ui.separator();
+
+                        ui.menu_button("Citation Style", |ui| {
+                            for style in CitationStyle::ALL {
+                                if ui
+                                    .radio_value(
+                                        &mut self.project.citation_style,
+                                        style,
+                                        style.name(),
+                                    )
+                                    .clicked()
+                                {
+                                    ui.close_menu();
+                                }
+                            }
+                        });
                     });
 
                     ui.menu_button("About", |ui| {
@@ -256,8 +860,22 @@ impl eframe::App for Workbook {
                 });
             });
 
+        if let Some(save_as) = save {
+            self.save(save_as);
+        }
+        if snapshot {
+            self.make_snapshot("");
+        }
+        if let Some(action) = request {
+            self.request(action, ctx);
+        }
+
+        self.show_tabs(ctx);
+        self.snippets.share(ctx);
         self.editor_gui(ctx);
         self.show_windows(ctx);
+        self.auto_snapshot_timer(ctx);
+        self.check_modified(ctx);
     }
 }
 
@@ -358,15 +976,105 @@ impl Workbook {
     // Show open windows.
     fn show_windows(&mut self, ctx: &egui::Context) {
         self.windows.about.show(ctx, &mut self.windows.about_open);
+        self.show_search(ctx);
+        self.show_comparison(ctx);
+        self.show_merge(ctx);
+        self.show_restore(ctx);
+        self.show_copy(ctx);
+        self.show_import(ctx);
+        self.show_snippets(ctx);
+        self.show_profiles(ctx);
+        self.show_unsaved(ctx);
+        self.show_save_failed(ctx);
+        self.show_notifications(ctx);
     }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn execute<F: future::Future<Output = ()> + Send + 'static>(f: F) {
+pub(super) fn execute<F: future::Future<Output = ()> + Send + 'static>(f: F) {
     std::thread::spawn(move || futures::executor::block_on(f));
 }
 
 #[cfg(target_arch = "wasm32")]
-fn execute<F: future::Future<Output = ()> + 'static>(f: F) {
+pub(super) fn execute<F: future::Future<Output = ()> + 'static>(f: F) {
     wasm_bindgen_futures::spawn_local(f);
 }
+
+// Pick the workbook file for the `import`, its name and path are sent
+// before the contents to the `file_sender` if any.
+pub(super) fn pick_workbook_file(
+    sender: Sender<PickedFile>,
+    import: ImportState,
+    file_sender: Option<Sender<WorkbookFile>>,
+) {
+    let task = rfd::AsyncFileDialog::new()
+        .add_filter("Workbook Files", &["w"])
+        .add_filter("All Files", &["*"])
+        .pick_file();
+    execute(async move {
+        let file = task.await;
+        if let Some(file) = file {
+            if let Some(file_sender) = file_sender {
+                let _ = file_sender.send(WorkbookFile::from(&file));
+            }
+            let contents = file.read().await;
+            let _ = sender.send((import, contents));
+        }
+    });
+}
+
+fn pick_references_file(sender: Sender<PickedFile>, import: ImportState) {
+    let task = rfd::AsyncFileDialog::new()
+        .add_filter("Reference Files", &["ris", "json"])
+        .add_filter("All Files", &["*"])
+        .pick_file();
+    execute(async move {
+        let file = task.await;
+        if let Some(file) = file {
+            let contents = file.read().await;
+            let _ = sender.send((import, contents));
+        }
+    });
+}
+
+fn pick_pdf_file(sender: Sender<PickedFile>, import: ImportState) {
+    let task = rfd::AsyncFileDialog::new()
+        .add_filter("PDF Files", &["pdf"])
+        .add_filter("All Files", &["*"])
+        .pick_file();
+    execute(async move {
+        let file = task.await;
+        if let Some(file) = file {
+            let contents = file.read().await;
+            let _ = sender.send((import, contents));
+        }
+    });
+}
+
+// Save the contents with the file filter `(name, extension)`.
+// The result is sent to the `notifier`.
+pub(super) fn save_file(
+    notifier: Sender<Notification>,
+    contents: Vec<u8>,
+    file_name: String,
+    filter: (&str, &str),
+) {
+    let task = rfd::AsyncFileDialog::new()
+        .add_filter(filter.0, &[filter.1])
+        .add_filter("All Files", &["*"])
+        .set_file_name(file_name)
+        .save_file();
+    execute(async move {
+        let file = task.await;
+        if let Some(file) = file {
+            let notification = match file.write(&contents).await {
+                Ok(()) => Notification::success("Exported", file.file_name()),
+                Err(e) => Notification::error(
+                    "Export Error",
+                    format!("“{}” cannot be written: {}", file.file_name(), e),
+                ),
+            };
+            let _ = notifier.send(notification);
+        }
+    });
+}
@@ -0,0 +1,492 @@
+// Comparison of two versions of the project, i.e. two snapshots or a snapshot
+// and the current project: the texts added, removed and changed in each section,
+// with the word-level differences of the changed texts.
+
+use egui::text::{LayoutJob, TextFormat};
+use egui::{Color32, Context, RichText, TextStyle, Ui};
+
+use super::app::Workbook;
+use super::chapter::Variety;
+use super::project::Project;
+
+const ADDED_COLOR: Color32 = Color32::from_rgb(0x3a, 0xa0, 0x55);
+
+// The limit of the alignment table, the longer texts are compared as a whole.
+const ALIGNMENT_LIMIT: usize = 4_000_000;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Version {
+    Current,
+    // The index in the `stored_projects`.
+    Snapshot(usize),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Words {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Added { path: String, text: String },
+    Removed { path: String, text: String },
+    Changed { path: String, words: Vec<Words> },
+}
+
+pub struct SectionChanges {
+    pub title: String,
+    pub changes: Vec<Change>,
+}
+
+// The text field of a section.
+struct Field {
+    // The path without the number of the note, e.g. "Idea › Problem".
+    list: String,
+    path: String,
+    text: String,
+}
+
+impl PartialEq for Field {
+    fn eq(&self, other: &Self) -> bool {
+        self.list == other.list && self.text == other.text
+    }
+}
+
+// The non-empty text fields grouped by the sections.
+fn sections(project: &Project) -> Vec<(Variety, Vec<Field>)> {
+    let mut sections: Vec<(Variety, Vec<Field>)> = Vec::new();
+    project.visit_texts(&mut |location, text| {
+        if text.trim().is_empty() {
+            return;
+        }
+        let list = match location.path.rsplit_once(" › Note ") {
+            Some((list, number)) if number.chars().all(|c| c.is_ascii_digit()) => list,
+            _ => location.path.as_str(),
+        };
+        let field = Field {
+            list: list.to_string(),
+            path: location.path.clone(),
+            text: text.clone(),
+        };
+        match sections
+            .iter_mut()
+            .find(|(section, _)| *section == location.section)
+        {
+            Some((_, fields)) => fields.push(field),
+            None => sections.push((location.section.clone(), vec![field])),
+        }
+    });
+    sections
+}
+
+enum Edit {
+    // The index in `old`.
+    Same(usize),
+    Removed(usize),
+    Added(usize),
+}
+
+// The shortest edit script turning `old` into `new`.
+fn align<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    if old.len() * new.len() > ALIGNMENT_LIMIT {
+        return (0..old.len())
+            .map(Edit::Removed)
+            .chain((0..new.len()).map(Edit::Added))
+            .collect();
+    }
+
+    // The lengths of the common subsequences of the suffixes.
+    let width = new.len() + 1;
+    let mut common = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i * width + j] = if old[i] == new[j] {
+                common[(i + 1) * width + j + 1] + 1
+            } else {
+                common[(i + 1) * width + j].max(common[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push(Edit::Same(i));
+            i += 1;
+            j += 1;
+        } else if j == new.len()
+            || i < old.len() && common[(i + 1) * width + j] >= common[i * width + j + 1]
+        {
+            edits.push(Edit::Removed(i));
+            i += 1;
+        } else {
+            edits.push(Edit::Added(j));
+            j += 1;
+        }
+    }
+    edits
+}
+
+// The words and the spaces between them.
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut space = None;
+    for (i, c) in text.char_indices() {
+        if space.is_some_and(|space| space != c.is_whitespace()) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        space = Some(c.is_whitespace());
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+pub fn diff_words(old: &str, new: &str) -> Vec<Words> {
+    let old = tokens(old);
+    let new = tokens(new);
+    let mut words: Vec<Words> = Vec::new();
+    for edit in align(&old, &new) {
+        let (token, next) = match edit {
+            Edit::Same(i) => (old[i], Words::Same(String::new())),
+            Edit::Removed(i) => (old[i], Words::Removed(String::new())),
+            Edit::Added(j) => (new[j], Words::Added(String::new())),
+        };
+        // The adjacent tokens of the same kind are joined.
+        match (words.last_mut(), &next) {
+            (Some(Words::Same(text)), Words::Same(_))
+            | (Some(Words::Added(text)), Words::Added(_))
+            | (Some(Words::Removed(text)), Words::Removed(_)) => text.push_str(token),
+            _ => words.push(match next {
+                Words::Same(_) => Words::Same(token.to_string()),
+                Words::Added(_) => Words::Added(token.to_string()),
+                Words::Removed(_) => Words::Removed(token.to_string()),
+            }),
+        }
+    }
+    words
+}
+
+// The changes of the texts section by section.
+pub fn compare(old: &Project, new: &Project) -> Vec<SectionChanges> {
+    let mut old_sections = sections(old);
+    let mut changes = Vec::new();
+    for (section, new_fields) in sections(new) {
+        let old_fields = match old_sections.iter().position(|(old, _)| *old == section) {
+            Some(i) => old_sections.remove(i).1,
+            None => Vec::new(),
+        };
+        let title = new_fields[0].path.split(" › ").next().unwrap_or_default();
+        changes.push(SectionChanges {
+            title: title.to_string(),
+            changes: compare_fields(&old_fields, &new_fields),
+        });
+    }
+    // The sections with the texts removed entirely.
+    for (_, old_fields) in old_sections {
+        let title = old_fields[0].path.split(" › ").next().unwrap_or_default();
+        changes.push(SectionChanges {
+            title: title.to_string(),
+            changes: compare_fields(&old_fields, &[]),
+        });
+    }
+    changes.retain(|section| !section.changes.is_empty());
+    changes
+}
+
+fn compare_fields(old: &[Field], new: &[Field]) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let mut removed: Vec<&Field> = Vec::new();
+    let mut added: Vec<&Field> = Vec::new();
+    let edits = align(old, new);
+    for (n, edit) in edits.iter().enumerate() {
+        match edit {
+            Edit::Removed(i) => removed.push(&old[*i]),
+            Edit::Added(j) => added.push(&new[*j]),
+            Edit::Same(_) => {}
+        }
+        // The removed and added texts of the same list in a row are paired
+        // as the changed ones if they are similar.
+        if matches!(edit, Edit::Same(_)) || n + 1 == edits.len() {
+            let mut pairs: Vec<Option<&Field>> = vec![None; added.len()];
+            for field in removed.drain(..) {
+                let best = (0..added.len())
+                    .filter(|&j| pairs[j].is_none() && added[j].list == field.list)
+                    .map(|j| (j, similarity(&field.text, &added[j].text)))
+                    .filter(|(_, similarity)| *similarity >= 0.5)
+                    .max_by(|a, b| a.1.total_cmp(&b.1));
+                match best {
+                    Some((j, _)) => pairs[j] = Some(field),
+                    None => changes.push(Change::Removed {
+                        path: field.path.clone(),
+                        text: field.text.clone(),
+                    }),
+                }
+            }
+            for (field, previous) in added.drain(..).zip(pairs) {
+                changes.push(match previous {
+                    Some(previous) => Change::Changed {
+                        path: field.path.clone(),
+                        words: diff_words(&previous.text, &field.text),
+                    },
+                    None => Change::Added {
+                        path: field.path.clone(),
+                        text: field.text.clone(),
+                    },
+                });
+            }
+        }
+    }
+    changes
+}
+
+// The share of the words of the shorter text found in the other one.
+fn similarity(old: &str, new: &str) -> f32 {
+    let old: Vec<&str> = old.split_whitespace().collect();
+    let new: Vec<&str> = new.split_whitespace().collect();
+    let shorter = old.len().min(new.len());
+    if shorter == 0 {
+        return 0.0;
+    }
+    let shared = old.iter().filter(|word| new.contains(word)).count();
+    shared.min(shorter) as f32 / shorter as f32
+}
+
+// The state of the comparison window.
+pub struct Comparison {
+    pub old: Version,
+    pub new: Version,
+    sections: Vec<SectionChanges>,
+    // The changes are to be compared again.
+    stale: bool,
+}
+
+impl Default for Comparison {
+    fn default() -> Self {
+        Self {
+            old: Version::Snapshot(0),
+            new: Version::Current,
+            sections: Vec::new(),
+            stale: true,
+        }
+    }
+}
+
+impl Comparison {
+    // Compare the snapshot with the current project.
+    pub fn with_current(snapshot: usize) -> Self {
+        Comparison {
+            old: Version::Snapshot(snapshot),
+            ..Default::default()
+        }
+    }
+}
+
+impl Workbook {
+    fn version(&self, version: Version) -> &Project {
+        match version {
+            Version::Snapshot(i) if i < self.stored_projects.len() => {
+                &self.stored_projects[i].project
+            }
+            _ => &self.project,
+        }
+    }
+
+    pub fn version_name(&self, version: Version) -> String {
+        match version {
+            Version::Snapshot(i) if i < self.stored_projects.len() => {
+                self.stored_projects[i].name(i)
+            }
+            _ => "Current project".to_string(),
+        }
+    }
+
+    pub fn show_comparison(&mut self, ctx: &Context) {
+        if !self.windows.compare_open {
+            return;
+        }
+
+        let mut comparison = std::mem::take(&mut self.windows.compare);
+        if comparison.stale {
+            comparison.sections =
+                compare(self.version(comparison.old), self.version(comparison.new));
+            comparison.stale = false;
+        }
+
+        let mut versions = vec![Version::Current];
+        versions.extend((0..self.stored_projects.len()).rev().map(Version::Snapshot));
+        let names: Vec<String> = versions
+            .iter()
+            .map(|version| self.version_name(*version))
+            .collect();
+
+        let mut open = self.windows.compare_open;
+        egui::Window::new("Compare")
+            .open(&mut open)
+            .default_width(520.)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for (label, selected) in
+                        [("From:", &mut comparison.old), ("To:", &mut comparison.new)]
+                    {
+                        ui.label(label);
+                        let name = self.version_name(*selected);
+                        egui::ComboBox::from_id_source(label)
+                            .selected_text(name)
+                            .show_ui(ui, |ui| {
+                                for (version, name) in versions.iter().zip(names.iter()) {
+                                    if ui.selectable_value(selected, *version, name).clicked() {
+                                        comparison.stale = true;
+                                    }
+                                }
+                            });
+                    }
+                    if ui.button("⇄").on_hover_text("Swap the versions").clicked() {
+                        std::mem::swap(&mut comparison.old, &mut comparison.new);
+                        comparison.stale = true;
+                    }
+                    if ui
+                        .button("Refresh")
+                        .on_hover_text("Compare the current project again")
+                        .clicked()
+                    {
+                        comparison.stale = true;
+                    }
+                });
+                ui.separator();
+
+                if comparison.sections.is_empty() {
+                    ui.label("No differences in the texts.");
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for section in &comparison.sections {
+                            egui::CollapsingHeader::new(format!(
+                                "{} ({})",
+                                section.title,
+                                section.changes.len()
+                            ))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for change in &section.changes {
+                                    preview_change(ui, change);
+                                    ui.add_space(6.);
+                                }
+                            });
+                        }
+                    });
+            });
+
+        self.windows.compare_open = open;
+        self.windows.compare = comparison;
+    }
+}
+
+fn preview_change(ui: &mut Ui, change: &Change) {
+    let removed_color = ui.visuals().error_fg_color;
+    let text_color = ui.visuals().text_color();
+    let font_id = TextStyle::Body.resolve(ui.style());
+    match change {
+        Change::Added { path, text } => {
+            ui.label(
+                RichText::new(format!("+ {}", path))
+                    .small()
+                    .color(ADDED_COLOR),
+            );
+            ui.label(RichText::new(text).color(ADDED_COLOR));
+        }
+        Change::Removed { path, text } => {
+            ui.label(
+                RichText::new(format!("− {}", path))
+                    .small()
+                    .color(removed_color),
+            );
+            ui.label(RichText::new(text).strikethrough().color(removed_color));
+        }
+        Change::Changed { path, words } => {
+            ui.label(RichText::new(format!("~ {}", path)).small().weak());
+            let mut job = LayoutJob::default();
+            for part in words {
+                let (text, format) = match part {
+                    Words::Same(text) => (text, TextFormat::simple(font_id.clone(), text_color)),
+                    Words::Added(text) => (
+                        text,
+                        TextFormat {
+                            underline: egui::Stroke::new(1.0, ADDED_COLOR),
+                            ..TextFormat::simple(font_id.clone(), ADDED_COLOR)
+                        },
+                    ),
+                    Words::Removed(text) => (
+                        text,
+                        TextFormat {
+                            strikethrough: egui::Stroke::new(1.0, removed_color),
+                            ..TextFormat::simple(font_id.clone(), removed_color)
+                        },
+                    ),
+                };
+                job.append(text, 0.0, format);
+            }
+            ui.label(job);
+        }
+    }
+}
+
+// Test that the notes are compared section by section and word by word.
+#[test]
+fn compare_projects() {
+    use super::note::Note;
+
+    let note = |text: &str| Note {
+        note: text.to_string(),
+        ..Note::new()
+    };
+    let mut old = Project::default();
+    old.idea.problem.notes = vec![note("The pilot study works."), note("Unchanged.")];
+    old.scope.tasks.notes = vec![note("Task to be removed.")];
+    let mut new = Project::default();
+    new.idea.problem.notes = vec![
+        note("New first note."),
+        note("The large pilot study works well."),
+        note("Unchanged."),
+    ];
+
+    let sections = compare(&old, &new);
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].title, "Idea");
+    assert_eq!(
+        sections[0].changes,
+        vec![
+            Change::Added {
+                path: "Idea › Problem › Note 1".to_string(),
+                text: "New first note.".to_string()
+            },
+            Change::Changed {
+                path: "Idea › Problem › Note 2".to_string(),
+                words: vec![
+                    Words::Same("The ".to_string()),
+                    Words::Added("large ".to_string()),
+                    Words::Same("pilot study ".to_string()),
+                    Words::Removed("works.".to_string()),
+                    Words::Added("works well.".to_string()),
+                ]
+            },
+        ]
+    );
+    assert_eq!(
+        sections[1].changes,
+        vec![Change::Removed {
+            path: "Project Scope: Objectives and Planned Activities › Tasks".to_string(),
+            text: "Task to be removed.".to_string()
+        }]
+    );
+    assert!(compare(&new, &new).is_empty());
+}
@@ -2,6 +2,7 @@ use egui::Ui;
 
 use super::app::Workbook;
 use super::chapter::Variety;
+use super::sections::costs::Summary;
 
 pub const SPACE_INTERNAL_EDITOR: f32 = 10.0;
 pub const SPACE_SECTIONS_EDITOR: f32 = 14.0;
@@ -30,6 +31,7 @@ impl Workbook {
                             ui,
                             self.edit_section_titles,
                             self.project.resolution,
+                            &mut self.project.bibliography,
                         );
                     }
                     Variety::Timeline => {
@@ -59,13 +61,18 @@ impl Workbook {
                             ui,
                             self.edit_section_titles,
                             self.project.resolution,
+                            self.project.timeline.duration_years,
                         );
                     }
                     Variety::Scripting => {
                         self.project.scripting.edit(ui, self.edit_section_titles);
                     }
                     Variety::Literature => {
-                        self.project.literature.edit(ui, self.edit_section_titles);
+                        self.project.literature.edit(
+                            ui,
+                            self.edit_section_titles,
+                            &mut self.project.bibliography,
+                        );
                     }
                     Variety::Outcomes => {
                         self.project.outcomes.edit(
@@ -78,10 +85,15 @@ impl Workbook {
                         self.project.resources.edit(ui, self.edit_section_titles);
                     }
                     Variety::Budget => {
+                        let summary = Summary::new(
+                            &self.project.team.participants,
+                            self.project.timeline.duration_years,
+                        );
                         self.project.budget.edit(
                             ui,
                             self.edit_section_titles,
                             self.project.resolution,
+                            &summary,
                         );
                     }
                     Variety::Attachments => {
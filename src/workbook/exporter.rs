@@ -0,0 +1,281 @@
+use super::chapter::Variety;
+use super::project::Project;
+use super::reference_formats::citation_style::Fragment;
+use super::reference_formats::citations::Citations;
+use super::sections::costs::Summary;
+use super::sections::cv::CvLength;
+use crate::open_call::CustomerAccount;
+
+// The document exported in Markdown format.
+// Note: The sections are exported the same way as they are previewed,
+//       i.e. only the visible sections and notes up to the level of details.
+pub struct Document {
+    pub text: String,
+    pub resolution: usize,
+    pub citations: Citations,
+    // The length of the CVs of the participants required by the funder.
+    pub cv_length: CvLength,
+    // The costs of the participants added up for the Budget section.
+    pub costs: Summary,
+    // Only the citations are collected, the text is not written.
+    draft: bool,
+}
+
+pub trait Exporter {
+    fn export(&self, document: &mut Document);
+}
+
+impl Document {
+    pub fn new(project: &Project) -> Self {
+        let mut draft = Self {
+            text: String::new(),
+            resolution: project.resolution,
+            citations: Citations::new(project),
+            cv_length: project.team.cv_length,
+            costs: project.costs(),
+            draft: true,
+        };
+        // The references are numbered in the order of the first citation,
+        // so the citations are collected before the document is written.
+        project.export_sections(&mut draft);
+
+        Self {
+            draft: false,
+            ..draft
+        }
+    }
+
+    // The metadata of the document for Pandoc, if the author is set in the preferences.
+    pub fn front_matter(&mut self, title: &str, author: &CustomerAccount) {
+        let fields = [
+            ("title", title.trim()),
+            ("author", author.name.trim()),
+            ("institute", author.institution.trim()),
+            ("lang", author.language.trim()),
+        ];
+        if fields[1..].iter().all(|(_, value)| value.is_empty()) {
+            return;
+        }
+        self.text.push_str("---\n");
+        for (field, value) in fields {
+            if !value.is_empty() {
+                let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+                self.text.push_str(&format!("{}: \"{}\"\n", field, value));
+            }
+        }
+        self.text.push_str("---\n\n");
+    }
+
+    // The title of the document.
+    pub fn title(&mut self, text: &str) {
+        self.block(&format!("# {}", text.trim()));
+    }
+
+    // The title of a section.
+    pub fn heading(&mut self, text: &str) {
+        self.block(&format!("## {}", text.trim()));
+    }
+
+    // The title of a subsection, e.g. the name of participant.
+    pub fn subheading(&mut self, text: &str) {
+        self.block(&format!("### {}", text.trim()));
+    }
+
+    pub fn paragraph(&mut self, text: &str) {
+        if self.draft {
+            self.citations.cite(text);
+        } else if !text.trim().is_empty() {
+            let text = self.citations.resolve(text.trim());
+            // Keep the line breaks of notes.
+            self.block(&text.replace('\n', "  \n"));
+        }
+    }
+
+    // The items of a list, one per line.
+    pub fn list(&mut self, items: &[String]) {
+        let items: Vec<String> = items
+            .iter()
+            .filter(|item| !item.trim().is_empty())
+            .map(|item| format!("- {}", item.trim()))
+            .collect();
+        if !items.is_empty() {
+            self.block(&items.join("\n"));
+        }
+    }
+
+    // A table with the header in the first row.
+    pub fn table(&mut self, rows: &[Vec<String>]) {
+        let Some((header, rows)) = rows.split_first() else {
+            return;
+        };
+        let line = |cells: &[String]| {
+            let cells: Vec<String> = cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+            format!("| {} |", cells.join(" | "))
+        };
+        let mut lines = vec![line(header), line(&vec!["---".to_string(); header.len()])];
+        lines.extend(rows.iter().map(|row| line(row)));
+        self.block(&lines.join("\n"));
+    }
+
+    pub fn link(&mut self, url: &str) {
+        if !url.is_empty() {
+            self.block(&format!("<{}>", url));
+        }
+    }
+
+    // A formatted entry, e.g. of the bibliography, after the optional label.
+    pub fn entry(&mut self, label: &str, fragments: &[Fragment]) {
+        let mut text = String::new();
+        if !label.is_empty() {
+            text.push_str(label);
+            text.push(' ');
+        }
+        for fragment in fragments {
+            match fragment {
+                Fragment::Text(part) => text.push_str(part),
+                Fragment::Emphasis(part) => text.push_str(&format!("*{}*", part)),
+                Fragment::Link(url) => text.push_str(&format!("<{}>", url)),
+            }
+        }
+        self.block(&text);
+    }
+
+    fn block(&mut self, text: &str) {
+        if self.draft {
+            return;
+        }
+        self.text.push_str(text);
+        self.text.push_str("\n\n");
+    }
+}
+
+impl Project {
+    // The project in Markdown format, the sections in the order of the preview,
+    // written by the `author`.
+    pub fn export_markdown(&self, author: &CustomerAccount) -> String {
+        let mut document = Document::new(self);
+        document.front_matter(&self.working_name.title, author);
+        self.export_sections(&mut document);
+
+        // The bibliography of the references cited in the notes.
+        let style = document.citations.style;
+        let cited: Vec<(String, Vec<Fragment>)> = document
+            .citations
+            .cited()
+            .into_iter()
+            .map(|(number, reference)| (style.label(number), style.format(reference)))
+            .collect();
+        if !cited.is_empty() {
+            document.heading("Bibliography");
+            for (label, fragments) in cited {
+                document.entry(&label, &fragments);
+            }
+        }
+
+        document.text
+    }
+
+    // The costs of the participants by category and year.
+    pub fn costs(&self) -> Summary {
+        Summary::new(&self.team.participants, self.timeline.duration_years)
+    }
+
+    // The citations of the project resolved in the order of the export.
+    pub fn citations(&self) -> Citations {
+        Document::new(self).citations
+    }
+
+    fn export_sections(&self, document: &mut Document) {
+        for section in &self.index_list {
+            if section.tier > self.resolution {
+                continue;
+            }
+            match section.variety {
+                Variety::WorkingName => self.working_name.export(document),
+                Variety::Funding => self.funding.export(document),
+                Variety::Idea => self.idea.export(document),
+                Variety::Timeline => self.timeline.export(document),
+                Variety::Scope => self.scope.export(document),
+                Variety::PrelimResults => self.prelim_results.export(document),
+                Variety::Methodology => self.methodology.export(document),
+                Variety::Team => self.team.export(document),
+                Variety::Scripting => self.scripting.export(document),
+                Variety::Literature => self.literature.export(document),
+                Variety::Outcomes => self.outcomes.export(document),
+                Variety::Resources => self.resources.export(document),
+                Variety::Budget => self.budget.export(document),
+                _ => {}
+            }
+        }
+    }
+}
+
+// Test that the visible sections are exported with the references in the citation style.
+#[test]
+fn markdown_export() {
+    use super::note::{Note, Notes};
+    use super::reference_formats::citation_style::CitationStyle;
+    use super::sections::references::{Reference, ReferenceMetadata};
+
+    let mut project = Project {
+        resolution: 4,
+        citation_style: CitationStyle::Ieee,
+        ..Default::default()
+    };
+    project.working_name.title = "Test project".to_string();
+    project.working_name.visible = true;
+    project.literature.visible = true;
+    project.literature.literature_survey = Notes {
+        notes: vec![
+            Note {
+                note: "First line\nsecond line".to_string(),
+                visible: true,
+                ..Default::default()
+            },
+            Note {
+                note: "Hidden note".to_string(),
+                visible: false,
+                ..Default::default()
+            },
+        ],
+    };
+    project.bibliography.references = vec![Reference {
+        title: "A study of examples".to_string(),
+        visible: true,
+        metadata: ReferenceMetadata {
+            authors: "Doe, Ann".to_string(),
+            year: "2021".to_string(),
+            container: "Journal of Examples".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }];
+    project.budget.visible = false;
+
+    let text = project.export_markdown(&CustomerAccount::default());
+    assert!(text.starts_with("# Test project\n\n"));
+    assert!(text.contains("## Literature Survey\n\nFirst line  \nsecond line\n\n"));
+    assert!(text.contains("[1] A. Doe, “A study of examples,” *Journal of Examples*, 2021.\n\n"));
+    assert!(!text.contains("Hidden note"));
+    assert!(!text.contains(&format!("## {}", project.budget.title)));
+}
+
+// Test that the author set in the preferences is written as the front matter.
+#[test]
+fn front_matter() {
+    let project = Project::default();
+    let mut document = Document::new(&project);
+    document.front_matter("Title", &CustomerAccount::default());
+    assert!(document.text.is_empty());
+
+    let author = CustomerAccount {
+        name: "Jane \"JD\" Doe".to_string(),
+        language: "en".to_string(),
+        ..Default::default()
+    };
+    document.front_matter("Title", &author);
+    assert_eq!(
+        document.text,
+        "---\ntitle: \"Title\"\nauthor: \"Jane \\\"JD\\\" Doe\"\nlang: \"en\"\n---\n\n"
+    );
+}
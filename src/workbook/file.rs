@@ -0,0 +1,704 @@
+// The file the workbook is saved in, the changes not saved yet
+// and the prompt before they are discarded.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use egui::{Context, Key, KeyboardShortcut, Modifiers, ViewportCommand};
+
+use super::app::{execute, pick_workbook_file, ImportState, Workbook};
+use super::identity::Identity;
+use super::notifications::Notification;
+use super::project::Project;
+use super::snapshots::{auto_snapshot, Snapshot};
+use super::store::v_b0005::Store;
+
+pub const SAVE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::S);
+pub const SAVE_AS_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::S);
+
+// The number of the recent files listed.
+const RECENT_FILES: usize = 10;
+
+// The seconds between the checks for the changes while typing.
+const CHECK_INTERVAL: f64 = 0.5;
+
+#[derive(Clone)]
+pub struct WorkbookFile {
+    pub name: String,
+    // The path is known on native only, the web app always saves with the dialog.
+    pub path: Option<PathBuf>,
+}
+
+impl From<&rfd::FileHandle> for WorkbookFile {
+    fn from(file: &rfd::FileHandle) -> Self {
+        WorkbookFile {
+            name: file.file_name(),
+            #[cfg(not(target_arch = "wasm32"))]
+            path: Some(file.path().to_path_buf()),
+            #[cfg(target_arch = "wasm32")]
+            path: None,
+        }
+    }
+}
+
+// The name of the file shown in the title and the tabs.
+pub fn file_name(file: Option<&WorkbookFile>) -> String {
+    match file {
+        Some(file) => file.name.clone(),
+        None => "Untitled".to_string(),
+    }
+}
+
+// The result of saving the workbook of the tab sent back to the UI thread.
+pub enum SaveResult {
+    // The file and the fingerprint of the project saved,
+    // and the project for the snapshot on saving if it's made.
+    Saved(Identity, WorkbookFile, u64, Option<Box<Project>>),
+    // The name of the file and the error.
+    Failed(Identity, String, String),
+    Cancelled,
+}
+
+// The actions which may discard the changes not saved.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Unsaved {
+    New,
+    Open,
+    // Open the file of the `recent_files`.
+    OpenRecent(usize),
+    // Open the file dropped onto the window.
+    OpenDropped,
+    // Restore the whole project from the snapshot.
+    Restore(usize),
+    CloseTab,
+    // Close the application, the tabs are closed one by one.
+    Close,
+}
+
+impl Unsaved {
+    // The workbooks are opened in new tabs, the changes of the open ones are kept.
+    fn discards(&self) -> bool {
+        matches!(
+            self,
+            Unsaved::Restore(_) | Unsaved::CloseTab | Unsaved::Close
+        )
+    }
+}
+
+// The fingerprint of the project as it is stored.
+fn fingerprint(contents: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn encode(project: &Project) -> Vec<u8> {
+    let work: Store = project.clone().into();
+    work.encode_bincode()
+}
+
+// Write the file next to the workbook file first and put it in place
+// of the workbook file, so the workbook is never left half written.
+fn write_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+    let result =
+        std::fs::write(&temporary, contents).and_then(|()| std::fs::rename(&temporary, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+    }
+    result
+}
+
+impl Project {
+    pub fn fingerprint(&self) -> u64 {
+        fingerprint(&encode(self))
+    }
+}
+
+impl Workbook {
+    pub fn file_name(&self) -> String {
+        file_name(self.file.as_ref())
+    }
+
+    // Put the file first in the list of recent files.
+    pub fn remember_file(&mut self, file: &WorkbookFile) {
+        if let Some(path) = &file.path {
+            self.recent_files.retain(|recent| recent != path);
+            self.recent_files.insert(0, path.clone());
+            self.recent_files.truncate(RECENT_FILES);
+        }
+    }
+
+    // The project is the same as in the file, e.g. just opened or saved.
+    pub fn mark_saved(&mut self, file: Option<WorkbookFile>) {
+        if let Some(file) = &file {
+            self.remember_file(file);
+        }
+        self.file = file;
+        self.saved = self.project.fingerprint();
+        self.modified = false;
+        self.cite(self.saved);
+    }
+
+    // The citations are resolved again only after the project is changed,
+    // not the whole project is exported for the preview on every frame.
+    fn cite(&mut self, fingerprint: u64) {
+        if fingerprint != self.cited {
+            self.citations = Arc::new(self.project.citations());
+            self.cited = fingerprint;
+        }
+    }
+
+    pub fn update_modified(&mut self) {
+        let fingerprint = self.project.fingerprint();
+        self.modified = fingerprint != self.saved;
+        self.cite(fingerprint);
+    }
+
+    // Check for the changes after the input, i.e. not on every frame:
+    // the whole project is encoded for the fingerprint, so it's taken
+    // at most once in `CHECK_INTERVAL` while typing, and after the input.
+    pub fn check_modified(&mut self, ctx: &Context) {
+        let (input, time) = ctx.input(|i| {
+            let input = i.pointer.any_down()
+                || i.events.iter().any(|event| {
+                    !matches!(
+                        event,
+                        egui::Event::PointerMoved(_) | egui::Event::MouseMoved(_)
+                    )
+                });
+            (input, i.time)
+        });
+        self.check_pending |= input;
+        if !self.check_pending {
+            return;
+        }
+        let wait = self.checked_at + CHECK_INTERVAL - time;
+        if wait > 0. {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(wait));
+            return;
+        }
+        self.checked_at = time;
+        let cited = self.cited;
+        self.check_now();
+        if self.cited != cited {
+            // The preview is drawn before the change.
+            ctx.request_repaint();
+        }
+    }
+
+    // The changes waiting for the check are checked before they may be discarded.
+    fn check_now(&mut self) {
+        if self.check_pending {
+            self.check_pending = false;
+            self.update_modified();
+        }
+    }
+
+    // Save to the same file on native, otherwise choose the file.
+    pub fn save(&mut self, save_as: bool) {
+        let contents = encode(&self.project);
+        let saved = fingerprint(&contents);
+        // The snapshot is made after the project is written.
+        let snapshot = self
+            .auto_snapshots
+            .on_save
+            .then(|| Box::new(self.project.clone()));
+        let tab = self.tab;
+        let sender = self.save_channel.0.clone();
+
+        // The path is never known on the web.
+        let file = self
+            .file
+            .clone()
+            .filter(|file| !save_as && file.path.is_some());
+        if let Some(file) = file {
+            let path = file.path.clone().unwrap_or_default();
+            execute(async move {
+                let result = match write_file(&path, &contents) {
+                    Ok(()) => SaveResult::Saved(tab, file, saved, snapshot),
+                    Err(e) => SaveResult::Failed(tab, file.name, e.to_string()),
+                };
+                let _ = sender.send(result);
+            });
+            return;
+        }
+
+        let file_name = match &self.file {
+            Some(file) => file.name.clone(),
+            None => format!("{}.w", self.project.working_name.title),
+        };
+        let task = rfd::AsyncFileDialog::new()
+            .add_filter("Workbook Files", &["w"])
+            .add_filter("All Files", &["*"])
+            .set_file_name(file_name)
+            .save_file();
+        execute(async move {
+            let result = match task.await {
+                Some(file) => match file.write(&contents).await {
+                    Ok(()) => SaveResult::Saved(tab, WorkbookFile::from(&file), saved, snapshot),
+                    Err(e) => SaveResult::Failed(tab, file.file_name(), e.to_string()),
+                },
+                None => SaveResult::Cancelled,
+            };
+            let _ = sender.send(result);
+        });
+    }
+
+    // Open the workbook file read in the same way as with the dialog.
+    fn open_contents(&mut self, file: WorkbookFile, contents: Vec<u8>) {
+        self.opened_file = Some(file);
+        self.file_buffer = contents;
+        self.import_state = ImportState::Busy;
+    }
+
+    // Open the workbook file of the path, `false` if it cannot be read.
+    pub fn open_path(&mut self, path: PathBuf) -> bool {
+        let path = std::fs::canonicalize(&path).unwrap_or(path);
+        match std::fs::read(&path) {
+            Ok(contents) => {
+                let name = path.file_name().unwrap_or_default();
+                let file = WorkbookFile {
+                    name: name.to_string_lossy().to_string(),
+                    path: Some(path),
+                };
+                self.open_contents(file, contents);
+                true
+            }
+            Err(e) => {
+                self.notifications.push(Notification::error(
+                    "Open Error",
+                    format!("“{}” cannot be opened: {}", path.display(), e),
+                ));
+                false
+            }
+        }
+    }
+
+    // Open the workbook file dropped onto the window, natively or in the browser.
+    // `true` if a file is dropped.
+    fn open_dropped(&mut self, ctx: &Context) -> bool {
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        if dropped.is_empty() {
+            return false;
+        }
+        let is_workbook = |file: &egui::DroppedFile| {
+            let name = match &file.path {
+                Some(path) => path.to_string_lossy().to_string(),
+                None => file.name.clone(),
+            };
+            name.to_lowercase().ends_with(".w")
+        };
+        match dropped.into_iter().find(is_workbook) {
+            Some(file) => {
+                self.dropped_file = Some(file);
+                self.request(Unsaved::OpenDropped, ctx);
+            }
+            None => {
+                self.notifications.push(Notification::warning(
+                    "Not Opened",
+                    "Only the workbook files (.w) can be opened.",
+                ));
+            }
+        }
+        true
+    }
+
+    // Show that the workbook file can be dropped.
+    fn preview_dropping(&self, ctx: &Context) {
+        if ctx.input(|i| i.raw.hovered_files.is_empty()) {
+            return;
+        }
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("workbook_drop"),
+        ));
+        let rect = ctx.screen_rect();
+        painter.rect_filled(rect, 0., egui::Color32::from_black_alpha(160));
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            "Drop the workbook file (.w) to open it",
+            egui::TextStyle::Heading.resolve(&ctx.style()),
+            egui::Color32::WHITE,
+        );
+    }
+
+    // Do the action at once or ask what to do with the changes first.
+    pub fn request(&mut self, action: Unsaved, ctx: &Context) {
+        self.check_now();
+        if self.modified && action.discards() {
+            self.windows.unsaved = Some(action);
+        } else {
+            self.proceed(action, ctx);
+        }
+    }
+
+    fn proceed(&mut self, action: Unsaved, ctx: &Context) {
+        match action {
+            Unsaved::New => {
+                if !self.is_blank() {
+                    self.new_tab();
+                }
+            }
+            Unsaved::Open => {
+                pick_workbook_file(
+                    self.file_channel.0.clone(),
+                    ImportState::Busy,
+                    Some(self.opened_channel.0.clone()),
+                );
+            }
+            Unsaved::OpenRecent(i) if i < self.recent_files.len() => {
+                let path = self.recent_files[i].clone();
+                if !self.open_path(path) {
+                    self.recent_files.remove(i);
+                }
+            }
+            Unsaved::OpenRecent(_) => {}
+            Unsaved::OpenDropped => {
+                if let Some(dropped) = self.dropped_file.take() {
+                    match (dropped.bytes, dropped.path) {
+                        (Some(bytes), path) => self.open_contents(
+                            WorkbookFile {
+                                name: dropped.name,
+                                path,
+                            },
+                            bytes.to_vec(),
+                        ),
+                        (None, Some(path)) => {
+                            self.open_path(path);
+                        }
+                        (None, None) => {}
+                    }
+                }
+            }
+            Unsaved::Restore(i) if i < self.stored_projects.len() => {
+                // The project is taken before the safety snapshot is made,
+                // the retention rules may delete the automatic snapshots.
+                let restored = self.stored_projects[i].project.clone();
+                self.make_snapshot("Before restore");
+                self.project = restored;
+                self.windows.search.forget_undo();
+                self.update_modified();
+            }
+            Unsaved::Restore(_) => {}
+            Unsaved::CloseTab => self.close_tab(),
+            Unsaved::Close => {
+                self.close_tab();
+                // Ask about the changes of the next tab.
+                match self.modified_tab() {
+                    Some(i) => {
+                        self.switch_tab(i);
+                        self.windows.unsaved = Some(Unsaved::Close);
+                    }
+                    None => {
+                        self.close_confirmed = true;
+                        ctx.send_viewport_cmd(ViewportCommand::Close);
+                    }
+                }
+            }
+        }
+    }
+
+    // Keep track of the file and the window title, ask before closing
+    // with the changes not saved. Called on every frame, whichever
+    // application is shown; `true` if the workbook needs the attention,
+    // i.e. opens the file dropped, asks about the changes or the error of saving.
+    pub fn track_file(&mut self, ctx: &Context) -> bool {
+        self.preview_dropping(ctx);
+        let dropped = self.open_dropped(ctx);
+
+        // The action waiting for the active project is given up unless it's saved,
+        // the saving of the other tabs leaves it waiting.
+        if let Ok(result) = self.save_channel.1.try_recv() {
+            match result {
+                SaveResult::Saved(tab, file, saved, snapshot) => {
+                    self.notifications
+                        .push(Notification::success("Saved", file.name.clone()));
+                    self.remember_file(&file);
+                    let rules = self.auto_snapshots.clone();
+                    let author = self.owner();
+                    let snapshot = |stored_projects: &mut Vec<Snapshot>| {
+                        if let Some(project) = snapshot {
+                            auto_snapshot(stored_projects, &project, &rules, "Saved", author);
+                        }
+                    };
+                    if tab == self.tab {
+                        snapshot(&mut self.stored_projects);
+                        self.windows.save_failed = None;
+                        self.file = Some(file);
+                        self.saved = saved;
+                        self.modified = self.project.fingerprint() != saved;
+                        if let Some(action) = self.after_save.take() {
+                            self.proceed(action, ctx);
+                        }
+                    } else if let Some(tab) = self.other_tab_mut(tab) {
+                        snapshot(&mut tab.stored_projects);
+                        tab.file = Some(file);
+                        tab.saved = saved;
+                        tab.modified = tab.project.fingerprint() != saved;
+                    }
+                }
+                // The project stays modified, its tab is shown with the error.
+                SaveResult::Failed(tab, name, error) => {
+                    if tab == self.tab {
+                        self.after_save = None;
+                    }
+                    self.select_tab(tab);
+                    let error = format!("The workbook cannot be saved to “{}”: {}", name, error);
+                    self.notifications
+                        .push(Notification::error("Save Failed", error.clone()));
+                    self.windows.save_failed = Some(error);
+                }
+                SaveResult::Cancelled => self.after_save = None,
+            }
+        }
+
+        let title = format!(
+            "{}{} – OpenCall",
+            if self.modified { "• " } else { "" },
+            self.file_name()
+        );
+        if title != self.title {
+            ctx.send_viewport_cmd(ViewportCommand::Title(title.clone()));
+            self.title = title;
+        }
+
+        if ctx.input(|i| i.viewport().close_requested()) && !self.close_confirmed {
+            self.check_now();
+            if let Some(i) = self.modified_tab() {
+                ctx.send_viewport_cmd(ViewportCommand::CancelClose);
+                self.switch_tab(i);
+                self.windows.unsaved = Some(Unsaved::Close);
+            }
+        }
+        dropped || self.windows.unsaved.is_some() || self.windows.save_failed.is_some()
+    }
+
+    pub fn show_unsaved(&mut self, ctx: &Context) {
+        let Some(action) = self.windows.unsaved else {
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new("Unsaved Changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0., 0.])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Do you want to save the changes to “{}”?",
+                    self.file_name()
+                ));
+                ui.label("The changes are lost if they are not saved.");
+                ui.add_space(6.);
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Don't Save").clicked() {
+                        choice = Some(false);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.windows.unsaved = None;
+                    }
+                });
+            });
+        match choice {
+            Some(true) => {
+                self.windows.unsaved = None;
+                // The action is done when the project is saved.
+                self.after_save = Some(action);
+                self.save(false);
+            }
+            Some(false) => {
+                self.windows.unsaved = None;
+                self.proceed(action, ctx);
+            }
+            None => {}
+        }
+    }
+}
+
+impl Workbook {
+    // The error of saving, the project can be saved again or elsewhere.
+    pub fn show_save_failed(&mut self, ctx: &Context) {
+        let Some(error) = &self.windows.save_failed else {
+            return;
+        };
+        let mut retry = None;
+        let mut open = true;
+        egui::Window::new("Save Failed")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0., 0.])
+            .show(ctx, |ui| {
+                ui.label(error);
+                ui.label("The changes are kept in the workbook, they are not saved yet.");
+                ui.add_space(6.);
+                ui.horizontal(|ui| {
+                    if ui.button("Retry").clicked() {
+                        retry = Some(false);
+                    }
+                    if ui.button("Save As…").clicked() {
+                        retry = Some(true);
+                    }
+                });
+            });
+        if !open {
+            self.windows.save_failed = None;
+        }
+        if let Some(save_as) = retry {
+            self.windows.save_failed = None;
+            self.save(save_as);
+        }
+    }
+}
+
+// Test that the changes are told apart from the project saved.
+#[test]
+fn modified_since_saved() {
+    let mut workbook = Workbook::default();
+    assert_eq!(workbook.project.fingerprint(), workbook.saved);
+    assert_eq!(workbook.file_name(), "Untitled");
+
+    workbook.project.idea.problem.notes.push(super::note::Note {
+        note: "Problem".to_string(),
+        ..super::note::Note::new()
+    });
+    assert_ne!(workbook.project.fingerprint(), workbook.saved);
+
+    workbook.mark_saved(Some(WorkbookFile {
+        name: "Project.w".to_string(),
+        path: None,
+    }));
+    assert_eq!(workbook.project.fingerprint(), workbook.saved);
+    assert_eq!(workbook.file_name(), "Project.w");
+
+    // The same texts in another note are a change too.
+    workbook.project.idea.problem.notes[0].id = super::identity::Identity::new();
+    assert_ne!(workbook.project.fingerprint(), workbook.saved);
+}
+
+// Test that the citations are resolved again only after the project is changed.
+#[test]
+fn citations_of_changes() {
+    use super::sections::references::Reference;
+
+    let mut workbook = Workbook::default();
+    let citations = workbook.citations.clone();
+    workbook.update_modified();
+    assert!(Arc::ptr_eq(&citations, &workbook.citations));
+
+    workbook.project.bibliography.references.push(Reference {
+        title: "Paper".to_string(),
+        visible: true,
+        ..Default::default()
+    });
+    workbook.update_modified();
+    assert!(workbook.modified);
+    assert!(!Arc::ptr_eq(&citations, &workbook.citations));
+    assert_eq!(workbook.cited, workbook.project.fingerprint());
+}
+
+// Test that the recent files are listed the latest first without repeats.
+#[test]
+fn recent_files() {
+    let mut workbook = Workbook::default();
+    let file = |name: &str| WorkbookFile {
+        name: name.to_string(),
+        path: Some(PathBuf::from(format!("/tmp/{}", name))),
+    };
+    for i in 0..RECENT_FILES + 2 {
+        workbook.remember_file(&file(&format!("{}.w", i)));
+    }
+    workbook.remember_file(&file("5.w"));
+    workbook.remember_file(&WorkbookFile {
+        name: "Web.w".to_string(),
+        path: None,
+    });
+
+    assert_eq!(workbook.recent_files.len(), RECENT_FILES);
+    assert_eq!(workbook.recent_files[0], PathBuf::from("/tmp/5.w"));
+    assert_eq!(workbook.recent_files[1], PathBuf::from("/tmp/11.w"));
+    assert!(!workbook.recent_files[1..].contains(&PathBuf::from("/tmp/5.w")));
+}
+
+// Test that the workbook file of a path is read for opening, or the error is reported.
+#[test]
+fn open_path() {
+    let path = std::env::temp_dir().join(format!("open_path_{}.w", std::process::id()));
+    let contents = encode(&Project::default());
+    std::fs::write(&path, &contents).unwrap();
+
+    let mut workbook = Workbook::default();
+    assert!(workbook.open_path(path.clone()));
+    assert!(workbook.file_buffer == contents);
+    assert!(workbook.import_state == ImportState::Busy);
+    let opened = workbook.opened_file.clone().unwrap();
+    assert_eq!(opened.name, path.file_name().unwrap().to_string_lossy());
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(!workbook.open_path(path));
+    assert_eq!(workbook.notifications.log.len(), 1);
+}
+
+// Test that the workbook file is replaced as a whole, and that the snapshot
+// on saving is made only after the project is written.
+#[test]
+fn save_file() {
+    let path = std::env::temp_dir().join(format!("save_file_{}.w", std::process::id()));
+    write_file(&path, b"first").unwrap();
+    write_file(&path, b"second").unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"second");
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    assert!(!PathBuf::from(temporary).exists());
+    std::fs::remove_file(&path).unwrap();
+    assert!(write_file(&path.join("missing.w"), b"third").is_err());
+
+    let ctx = Context::default();
+    let mut workbook = Workbook::default();
+    workbook.auto_snapshots.on_save = true;
+    let sender = workbook.save_channel.0.clone();
+    let file = WorkbookFile {
+        name: "Project.w".to_string(),
+        path: None,
+    };
+    sender
+        .send(SaveResult::Failed(
+            workbook.tab,
+            file.name.clone(),
+            "denied".to_string(),
+        ))
+        .unwrap();
+    workbook.track_file(&ctx);
+    assert!(workbook.stored_projects.is_empty());
+
+    // The action waiting for the active project is kept while another tab is saved.
+    workbook.after_save = Some(Unsaved::New);
+    sender
+        .send(SaveResult::Saved(Identity::new(), file.clone(), 0, None))
+        .unwrap();
+    workbook.track_file(&ctx);
+    assert!(workbook.after_save.is_some());
+    workbook.after_save = None;
+
+    let project = Box::new(workbook.project.clone());
+    let saved = project.fingerprint();
+    sender
+        .send(SaveResult::Saved(workbook.tab, file, saved, Some(project)))
+        .unwrap();
+    workbook.track_file(&ctx);
+    assert_eq!(workbook.stored_projects.len(), 1);
+    assert!(workbook.stored_projects[0]
+        .project
+        .record
+        .starts_with("Saved "));
+    assert_eq!(workbook.file_name(), "Project.w");
+}
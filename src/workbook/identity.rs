@@ -0,0 +1,241 @@
+// Stable identifiers of the notes, programmes, participants and references,
+// so that the copies of the same workbook edited apart can be merged item by item.
+
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::collections::HashSet;
+use std::hash::{BuildHasher, BuildHasherDefault};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::note::Notes;
+use super::project::Project;
+use super::sections::team::Participant;
+
+// The number of identifiers made in this session.
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Identity(u64);
+
+// Every new item gets its own identifier.
+impl Default for Identity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Identity {
+    // The time and the sequence number are hashed with the keys
+    // seeded randomly for each session, so the identifiers made
+    // by different partners don't collide for practical purposes.
+    pub fn new() -> Self {
+        let time = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        Identity(RandomState::new().hash_one((time, count)))
+    }
+
+    // The identifier of a field of the project, the same in every session,
+    // e.g. to choose the side of the field in a merge conflict.
+    pub fn of_field(path: &str) -> Self {
+        Identity(BuildHasherDefault::<DefaultHasher>::default().hash_one(path))
+    }
+}
+
+// The visit of the lists of notes of the sections, read-only or to change them,
+// so that both visit the same lists under the same names.
+macro_rules! visit_section_notes {
+    ($method:ident $(, $mutability:ident)?) => {
+        pub fn $method(
+            &$($mutability)? self,
+            visit: &mut dyn FnMut(&'static str, &$($mutability)? Notes),
+        ) {
+            visit(
+                "Project Title › Alternative titles",
+                &$($mutability)? self.working_name.options,
+            );
+
+            let idea = &$($mutability)? self.idea;
+            visit("Idea › Problem", &$($mutability)? idea.problem);
+            visit("Idea › Hypothesis", &$($mutability)? idea.hypothesis);
+            visit("Idea › Abstract", &$($mutability)? idea.summary);
+            visit("Idea › Description", &$($mutability)? idea.description);
+
+            let scope = &$($mutability)? self.scope;
+            visit("Scope › Suggested tasks", &$($mutability)? scope.suggested_tasks);
+            visit("Scope › Objectives", &$($mutability)? scope.objectives);
+            visit("Scope › Activities", &$($mutability)? scope.activities);
+            visit("Scope › Work plan", &$($mutability)? scope.work_plan);
+            visit("Scope › Tasks", &$($mutability)? scope.tasks);
+
+            visit("Timeline › Milestones", &$($mutability)? self.timeline.milestones);
+            visit("Scripting › Details", &$($mutability)? self.scripting.scripting);
+
+            let team = &$($mutability)? self.team;
+            visit("Team › Proposed partners", &$($mutability)? team.proposed_partners);
+            visit("Team › Project leader", &$($mutability)? team.project_leader);
+            visit("Team › Industrial partners", &$($mutability)? team.industrial_partners);
+            visit("Team › Cooperation", &$($mutability)? team.proponents);
+
+            visit(
+                "Preliminary Results",
+                &$($mutability)? self.prelim_results.prelim_results,
+            );
+            visit("Methodology", &$($mutability)? self.methodology.methodology);
+
+            let outcomes = &$($mutability)? self.outcomes;
+            visit("Outcomes › Expected results", &$($mutability)? outcomes.results);
+            visit("Outcomes › Impact", &$($mutability)? outcomes.impact);
+            visit("Outcomes › Dissemination", &$($mutability)? outcomes.propagation);
+
+            visit(
+                "Literature Survey",
+                &$($mutability)? self.literature.literature_survey,
+            );
+
+            visit("Resources › Existing", &$($mutability)? self.resources.existing);
+            visit("Resources › Additional", &$($mutability)? self.resources.further);
+
+            let budget = &$($mutability)? self.budget;
+            visit("Budget › Personnel", &$($mutability)? budget.personnel);
+            visit("Budget › Facilities", &$($mutability)? budget.facilities);
+            visit("Budget › Materials", &$($mutability)? budget.materials);
+            visit("Budget › Workshops", &$($mutability)? budget.workshops);
+            visit("Budget › Overheads", &$($mutability)? budget.overheads);
+            visit("Budget › Miscellaneous", &$($mutability)? budget.misc);
+
+            let attachments = &$($mutability)? self.attachments;
+            visit("Attachments › Tables", &$($mutability)? attachments.tables);
+            visit("Attachments › Figures", &$($mutability)? attachments.figures);
+            visit("Attachments › Other", &$($mutability)? attachments.other);
+        }
+    };
+}
+
+impl Project {
+    // Visit the lists of notes of the sections in the order of the storage,
+    // the lists of the programmes, participants and references are not visited.
+    visit_section_notes!(visit_section_notes);
+    visit_section_notes!(visit_section_notes_mut, mut);
+
+    // Visit all the lists of notes in the order of the storage.
+    pub fn visit_notes(&mut self, visit: &mut dyn FnMut(&mut Notes)) {
+        self.visit_section_notes_mut(&mut |_, notes| visit(notes));
+        for programme in self.funding.funding_options.iter_mut() {
+            visit(&mut programme.annotation);
+        }
+        for participant in self.team.participants.iter_mut() {
+            for (_, notes) in participant.notes_mut() {
+                visit(notes);
+            }
+        }
+        for reference in self.bibliography.references.iter_mut() {
+            visit(&mut reference.source_details);
+        }
+    }
+
+    // Give new identifiers to the items which have the same identifiers
+    // as the items before them, e.g. copied or read from an earlier format.
+    pub fn identify(&mut self) {
+        let mut known = HashSet::new();
+        let mut check = |id: &mut Identity| {
+            if !known.insert(*id) {
+                *id = Identity::new();
+                known.insert(*id);
+            }
+        };
+        for programme in self.funding.funding_options.iter_mut() {
+            check(&mut programme.id);
+        }
+        for participant in self.team.participants.iter_mut() {
+            check(&mut participant.id);
+        }
+        for reference in self.bibliography.references.iter_mut() {
+            check(&mut reference.id);
+        }
+        self.visit_notes(&mut |notes| {
+            for note in notes.notes.iter_mut() {
+                check(&mut note.id);
+            }
+        });
+    }
+}
+
+impl Participant {
+    // New identifiers for the copy of the participant and its notes.
+    pub fn renew_identities(&mut self) {
+        self.id = Identity::new();
+        for (_, notes) in self.notes_mut() {
+            for note in notes.notes.iter_mut() {
+                note.id = Identity::new();
+            }
+        }
+    }
+}
+
+// Test that the identifiers are kept in the storage and repeated ones are replaced.
+#[test]
+fn identities_in_store() {
+    use crate::workbook::note::Note;
+    use crate::workbook::sections::funding::Programme;
+    use crate::workbook::sections::references::Reference;
+    use crate::workbook::store::v_b0005::Store;
+
+    let note = |text: &str| Note {
+        note: text.to_string(),
+        ..Note::new()
+    };
+    let mut project = Project::default();
+    project.idea.problem.notes = vec![note("Problem"), note("Gap")];
+    project.funding.funding_options.push(Programme {
+        annotation: Notes {
+            notes: vec![note("Call")],
+        },
+        ..Default::default()
+    });
+    project.team.participants.push(Participant {
+        name: "Jane Doe".to_string(),
+        cv: Notes {
+            notes: vec![note("CV")],
+        },
+        ..Default::default()
+    });
+    project.bibliography.references.push(Reference {
+        title: "Paper".to_string(),
+        ..Default::default()
+    });
+
+    // Both visits go through the same lists under the same names.
+    let mut names = Vec::new();
+    project.visit_section_notes(&mut |name, notes| names.push((name, notes.notes.len())));
+    let mut names_mut = Vec::new();
+    project.visit_section_notes_mut(&mut |name, notes| names_mut.push((name, notes.notes.len())));
+    assert_eq!(names, names_mut);
+    assert!(names.contains(&("Idea › Problem", 2)));
+
+    let identities = |project: &mut Project| {
+        let mut ids = vec![
+            project.funding.funding_options[0].id,
+            project.team.participants[0].id,
+            project.bibliography.references[0].id,
+        ];
+        project.visit_notes(&mut |notes| ids.extend(notes.notes.iter().map(|note| note.id)));
+        ids
+    };
+
+    let store: Store = project.clone().into();
+    let mut restored: Project = store.into();
+    let expected = identities(&mut project);
+    assert_eq!(expected.len(), 7);
+    assert_eq!(identities(&mut restored), expected);
+
+    // A copied note gets a new identifier.
+    let copy = project.idea.problem.notes[0].clone();
+    project.idea.hypothesis.notes.push(copy);
+    project.identify();
+    let ids = identities(&mut project);
+    assert_eq!(ids[..5], expected[..5]);
+    assert!(!expected.contains(&ids[5]));
+    assert_eq!(ids[6..], expected[5..]);
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+}
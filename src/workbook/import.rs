@@ -0,0 +1,270 @@
+// Importing the chosen sections, programmes, participants and references
+// of another workbook, e.g. of an earlier proposal. The notes are appended
+// to the lists of the current project, nothing is overwritten.
+
+use std::collections::HashSet;
+
+use egui::{Context, RichText};
+
+use super::app::Workbook;
+use super::chapter::Variety;
+use super::identity::Identity;
+use super::notifications::Notification;
+use super::project::Project;
+use super::tabs::{choose, Clipboard};
+
+// The state of the "Import from Workbook" window.
+#[derive(Default)]
+pub struct Importing {
+    // The workbook the items are imported from.
+    pub other: Option<Project>,
+    pub sections: Vec<Variety>,
+    // The programmes, participants and references.
+    pub chosen: HashSet<Identity>,
+}
+
+impl Importing {
+    pub fn with(other: Project) -> Self {
+        Importing {
+            other: Some(other),
+            sections: Vec::new(),
+            chosen: HashSet::new(),
+        }
+    }
+
+    // The chosen items with the notes of the chosen sections.
+    fn clipboard(&self, other: &Project) -> Clipboard {
+        let mut chosen = self.chosen.clone();
+        other.visit_section_notes(&mut |name, notes| {
+            if section_of(name).is_some_and(|section| self.sections.contains(&section)) {
+                chosen.extend(notes.notes.iter().map(|note| note.id));
+            }
+        });
+        Clipboard::copy(other, &chosen)
+    }
+}
+
+// The section of the list of notes named in `Project::visit_section_notes()`.
+fn section_of(name: &str) -> Option<Variety> {
+    let section = match name.split(" › ").next().unwrap_or_default() {
+        "Project Title" => Variety::WorkingName,
+        "Idea" => Variety::Idea,
+        "Scope" => Variety::Scope,
+        "Timeline" => Variety::Timeline,
+        "Scripting" => Variety::Scripting,
+        "Team" => Variety::Team,
+        "Preliminary Results" => Variety::PrelimResults,
+        "Methodology" => Variety::Methodology,
+        "Outcomes" => Variety::Outcomes,
+        "Literature Survey" => Variety::Literature,
+        "Resources" => Variety::Resources,
+        "Budget" => Variety::Budget,
+        "Attachments" => Variety::Attachments,
+        _ => return None,
+    };
+    Some(section)
+}
+
+// The number of notes of each section.
+fn count_notes(project: &Project) -> Vec<(Variety, usize)> {
+    let mut counts: Vec<(Variety, usize)> = Vec::new();
+    project.visit_section_notes(&mut |name, notes| {
+        let Some(section) = section_of(name) else {
+            return;
+        };
+        match counts.iter_mut().find(|(other, _)| *other == section) {
+            Some((_, count)) => *count += notes.notes.len(),
+            None => counts.push((section, notes.notes.len())),
+        }
+    });
+    counts
+}
+
+impl Workbook {
+    pub fn show_import(&mut self, ctx: &Context) {
+        if !self.windows.import_open {
+            return;
+        }
+        let mut importing = std::mem::take(&mut self.windows.import);
+        let Some(other) = importing.other.take() else {
+            self.windows.import_open = false;
+            return;
+        };
+
+        let mut open = self.windows.import_open;
+        let mut apply = false;
+        egui::Window::new("Import from Workbook")
+            .open(&mut open)
+            .default_width(420.)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(format!(
+                        "Choose the parts of “{}” to import into “{}”. \
+                        The notes are appended to the same sections.",
+                        other.working_name.title,
+                        self.file_name()
+                    ))
+                    .small()
+                    .weak(),
+                );
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .max_height(420.)
+                    .show(ui, |ui| {
+                        egui::CollapsingHeader::new("Sections")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                let counts = count_notes(&other);
+                                for segment in &other.index_list {
+                                    let section = &segment.variety;
+                                    let count = counts
+                                        .iter()
+                                        .find(|(other, _)| other == section)
+                                        .map_or(0, |(_, count)| *count);
+                                    if count == 0 {
+                                        continue;
+                                    }
+                                    let mut selected = importing.sections.contains(section);
+                                    let title = format!(
+                                        "{} ({} notes)",
+                                        other.section_title(section),
+                                        count
+                                    );
+                                    if ui.checkbox(&mut selected, title).changed() {
+                                        if selected {
+                                            importing.sections.push(section.clone());
+                                        } else {
+                                            importing.sections.retain(|other| other != section);
+                                        }
+                                    }
+                                }
+                            });
+                        egui::CollapsingHeader::new("Programmes")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for programme in other.funding.funding_options.iter() {
+                                    choose(
+                                        ui,
+                                        &mut importing.chosen,
+                                        programme.id,
+                                        &programme.title,
+                                    );
+                                }
+                            });
+                        egui::CollapsingHeader::new("Participants")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for participant in other.team.participants.iter() {
+                                    choose(
+                                        ui,
+                                        &mut importing.chosen,
+                                        participant.id,
+                                        &participant.name,
+                                    );
+                                }
+                            });
+                        egui::CollapsingHeader::new("References")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for reference in other.bibliography.references.iter() {
+                                    choose(
+                                        ui,
+                                        &mut importing.chosen,
+                                        reference.id,
+                                        &reference.title,
+                                    );
+                                }
+                            });
+                    });
+                ui.separator();
+
+                let chosen = !importing.sections.is_empty() || !importing.chosen.is_empty();
+                if ui
+                    .add_enabled(chosen, egui::Button::new("Import"))
+                    .on_hover_text(
+                        "Append the notes of the chosen sections and the chosen items \
+                        to the current project",
+                    )
+                    .clicked()
+                {
+                    apply = true;
+                }
+            });
+
+        if apply {
+            let clipboard = importing.clipboard(&other);
+            clipboard.paste(&mut self.project);
+            self.notifications.push(Notification::success(
+                "Imported",
+                format!(
+                    "{} items of “{}”",
+                    clipboard.len(),
+                    other.working_name.title
+                ),
+            ));
+            open = false;
+        }
+        importing.other = Some(other);
+        self.windows.import_open = open;
+        self.windows.import = importing;
+    }
+}
+
+// Test that the notes of the chosen sections and the chosen items
+// are appended to the project.
+#[test]
+fn import_from_workbook() {
+    use super::note::Note;
+    use super::sections::funding::Programme;
+    use super::sections::team::Participant;
+
+    let note = |text: &str| Note {
+        note: text.to_string(),
+        ..Note::new()
+    };
+    let mut other = Project::default();
+    other.team.proposed_partners.notes = vec![note("University of Oslo")];
+    other.resources.existing.notes = vec![note("Cluster")];
+    other.resources.further.notes = vec![note("Microscope")];
+    other.methodology.methodology.notes = vec![note("Survey")];
+    other.funding.funding_options.push(Programme {
+        title: "Horizon Europe".to_string(),
+        ..Default::default()
+    });
+    other.team.participants.push(Participant {
+        name: "Jane Doe".to_string(),
+        ..Default::default()
+    });
+
+    let mut project = Project::default();
+    project.resources.existing.notes = vec![note("Laboratory")];
+
+    let mut importing = Importing::with(other.clone());
+    importing.sections = vec![Variety::Resources, Variety::Team];
+    importing.chosen.insert(other.team.participants[0].id);
+    assert!(count_notes(&other).contains(&(Variety::Resources, 2)));
+    importing.clipboard(&other).paste(&mut project);
+
+    let texts = |notes: &super::note::Notes| -> Vec<String> {
+        notes.notes.iter().map(|note| note.note.clone()).collect()
+    };
+    assert_eq!(
+        texts(&project.resources.existing),
+        ["Laboratory", "Cluster"]
+    );
+    assert_eq!(texts(&project.resources.further), ["Microscope"]);
+    assert_eq!(
+        texts(&project.team.proposed_partners),
+        ["University of Oslo"]
+    );
+    assert!(project.methodology.methodology.notes.is_empty());
+    assert!(project.funding.funding_options.is_empty());
+    assert_eq!(project.team.participants[0].name, "Jane Doe");
+    assert_ne!(
+        project.team.participants[0].id,
+        other.team.participants[0].id
+    );
+}
@@ -2,3 +2,4 @@ pub mod conversion;
 
 mod v_b0002;
 mod v_b0003;
+mod v_b0004;
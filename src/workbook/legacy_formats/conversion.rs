@@ -1,6 +1,11 @@
 use crate::workbook::app::Workbook;
 use crate::workbook::legacy_formats::v_b0002::WorkVersionB0002;
 use crate::workbook::legacy_formats::v_b0003::WorkVersionB0003;
+use crate::workbook::legacy_formats::v_b0004::WorkVersionB0004;
+use crate::workbook::notifications::Notification;
+use crate::workbook::project::Project;
+use crate::workbook::snapshots::Snapshot;
+use crate::workbook::store::v_b0005::Store;
 
 #[derive(Debug)]
 enum Format {
@@ -11,88 +16,125 @@ enum Format {
 
     // Format version 'B0003' - see struct `WorkVersionB0003`
     VersionB0003,
+
+    // Format version 'B0004' - see struct `WorkVersionB0004`
+    VersionB0004,
 }
 
 impl Workbook {
     pub fn convert_or_err(&mut self, e: Box<bincode::ErrorKind>) {
-        // Note: Assuming that the encoded format version is represented
-        // as "0.1.3" (or "B0001"),
-        // we read first 5 bytes of the file_buffer to get the version string.
-        let version_bytes = 5 * 8;
-        let read_version_bytes = self.file_buffer.get(..version_bytes).unwrap();
-
-        let version_read_result: Result<String, bincode::Error> =
-            bincode::deserialize(read_version_bytes);
-
-        let known_format: Format;
-        let file_format = match version_read_result {
-            Ok(v) => {
-                known_format = match v.as_str() {
-                    "B0002" => Format::VersionB0002,
-                    "B0003" => Format::VersionB0003,
-                    _ => Format::Unknown,
-                };
-                v
+        match convert(&self.file_buffer, e) {
+            Ok((project, stored_projects, file_format)) => {
+                self.project = project;
+                self.stored_projects = stored_projects;
+                self.windows.search.forget_undo();
+                self.notifications.push(converted(&file_format));
             }
-            Err(_err) => {
-                known_format = Format::Unknown;
-                "read error".to_string()
+            Err(err) => {
+                self.notifications
+                    .push(Notification::error("Parser Error", err));
             }
-        };
+        }
+    }
+}
 
-        match known_format {
-            Format::VersionB0002 => {
-                if let Ok(work) = WorkVersionB0002::decode_bincode(&self.file_buffer) {
-                    self.apply_format_b0002(work);
-                } else {
-                    let err = format!(
-                        "Detected version {}: \
-                    incompatible data format, \
-                    probably an old workbook version was used for saving this \
-                    project: {}",
-                        file_format, e
-                    );
-                    rfd::MessageDialog::new()
-                        .set_title("Parser Error")
-                        .set_description(err)
-                        .set_level(rfd::MessageLevel::Error)
-                        .show();
-                }
-            }
+// The project and the snapshots of the workbook file in an earlier format
+// with the name of the format, or the error if it cannot be converted.
+fn convert(
+    buffer: &[u8],
+    e: Box<bincode::ErrorKind>,
+) -> Result<(Project, Vec<Snapshot>, String), String> {
+    // Note: Assuming that the encoded format version is represented
+    // as "0.1.3" (or "B0001"),
+    // we read first 5 bytes of the file_buffer to get the version string.
+    let version_bytes = 5 * 8;
+    let read_version_bytes = buffer.get(..version_bytes).unwrap_or(buffer);
 
-            Format::VersionB0003 => {
-                if let Ok(work) = WorkVersionB0003::decode_bincode(&self.file_buffer) {
-                    self.apply_format_b0003(work);
-                } else {
-                    let err = format!(
-                        "Detected version {}: \
-                    incompatible data format, \
-                    probably an old workbook version was used for saving this \
-                    project: {}",
-                        file_format, e
-                    );
-                    rfd::MessageDialog::new()
-                        .set_title("Parser Error")
-                        .set_description(err)
-                        .set_level(rfd::MessageLevel::Error)
-                        .show();
-                }
-            }
+    let version_read_result: Result<String, bincode::Error> =
+        bincode::deserialize(read_version_bytes);
 
-            _ => {
-                let err = format!(
-                    "Detected version {}: \
-                incompatible data format, \
-                probably an old workbook version was used for saving this \
-                project: {}",
-                    file_format, e
-                );
-                rfd::MessageDialog::new()
-                    .set_title("Parser Error")
-                    .set_description(err)
-                    .set_level(rfd::MessageLevel::Error)
-                    .show();
-            }
+    let known_format: Format;
+    let file_format = match version_read_result {
+        Ok(v) => {
+            known_format = match v.as_str() {
+                "B0002" => Format::VersionB0002,
+                "B0003" => Format::VersionB0003,
+                "B0004" => Format::VersionB0004,
+                _ => Format::Unknown,
+            };
+            v
+        }
+        Err(_err) => {
+            known_format = Format::Unknown;
+            "read error".to_string()
         }
+    };
+
+    let converted = match known_format {
+        Format::VersionB0002 => WorkVersionB0002::decode_bincode(buffer)
+            .ok()
+            .map(WorkVersionB0002::convert),
+        Format::VersionB0003 => WorkVersionB0003::decode_bincode(buffer)
+            .ok()
+            .map(WorkVersionB0003::convert),
+        Format::VersionB0004 => WorkVersionB0004::decode_bincode(buffer)
+            .ok()
+            .map(WorkVersionB0004::convert),
+        _ => None,
+    };
+    match converted {
+        Some((project, stored_projects)) => Ok((project, stored_projects, file_format)),
+        None => Err(format!(
+            "Detected version {}: \
+            incompatible data format, \
+            probably an old workbook version was used for saving this \
+            project: {}",
+            file_format, e
+        )),
     }
 }
+
+// The project of the workbook file in the current or an earlier format,
+// e.g. to import its sections.
+pub fn read_project(buffer: &[u8]) -> Result<Project, String> {
+    match Store::decode_bincode(buffer) {
+        Ok(work) => Ok(work.into()),
+        Err(e) => convert(buffer, e).map(|(project, _, _)| project),
+    }
+}
+
+// The notice of the workbook read from an earlier format.
+fn converted(file_format: &str) -> Notification {
+    Notification::info(
+        "Converted",
+        format!(
+            "The workbook is converted from the format {}, \
+            it is saved in the current format.",
+            file_format
+        ),
+    )
+}
+
+// Test that the format B0004 is converted, and that its readers
+// refuse the current format.
+#[test]
+fn format_b0004() {
+    let mut project = Project::default();
+    project.working_name.title = "Test project".to_string();
+    let store: Store = project.into();
+    let owner = (&store.owner.email, &store.owner.account);
+    let encoded = bincode::serialize(&(
+        "B0004",
+        owner,
+        &store.record,
+        store.resolution,
+        &store.chunks,
+    ))
+    .unwrap();
+
+    assert!(Store::decode_bincode(&encoded).is_err());
+    let converted = read_project(&encoded).unwrap();
+    assert_eq!(converted.working_name.title, "Test project".to_string());
+
+    assert!(WorkVersionB0004::decode_bincode(&store.encode_bincode()).is_err());
+}
@@ -1,6 +1,6 @@
 use serde::Deserialize;
 
-use crate::workbook::app::Workbook;
+use crate::workbook::identity::Identity;
 use crate::workbook::note::{Note, Notes};
 use crate::workbook::project::{Project, ProjectOwner};
 use crate::workbook::sections::attachments::Attachments;
@@ -10,12 +10,15 @@ use crate::workbook::sections::literature::Literature;
 use crate::workbook::sections::methodology::Methodology;
 use crate::workbook::sections::outcomes::Outcomes;
 use crate::workbook::sections::prelim_results::PrelimResults;
-use crate::workbook::sections::references::{Reference, References};
+use crate::workbook::sections::references::{
+    Bibliography, Reading, Reference, ReferenceMetadata, References,
+};
 use crate::workbook::sections::scope::Scope;
 use crate::workbook::sections::scripting::{Scripting, ScriptingOption};
 use crate::workbook::sections::team::{Participant, Team};
 use crate::workbook::sections::timeline::{Date, Timeline};
 use crate::workbook::sections::working_name::ProjectTitle;
+use crate::workbook::snapshots::Snapshot;
 
 // ############################################################################
 // ### Legacy data formats for conversion.
@@ -171,6 +174,7 @@ impl From<Partner0002> for Participant {
             resources: val.resources.into(),
             budget: val.budget.into(),
             visible: val.visible,
+            id: Identity::new(),
 
             ..Default::default()
         }
@@ -221,6 +225,9 @@ impl From<Reference0002> for Reference {
             hyperlink: val.hyperlink,
             source_details: val.source_details.into(),
             visible: val.visible,
+            metadata: ReferenceMetadata::default(),
+            reading: Reading::default(),
+            id: Identity::new(),
         }
     }
 }
@@ -251,6 +258,7 @@ impl From<Note0002> for Note {
             note: val.note,
             hint: val.hint,
             visible: val.visible,
+            id: Identity::new(),
         }
     }
 }
@@ -263,22 +271,39 @@ impl From<Notes0002> for Notes {
     }
 }
 
-impl Workbook {
-    pub fn apply_format_b0002(&mut self, work: WorkVersionB0002) {
-        let _old_version = work.0;
-        let old_project = work.1;
+impl WorkVersionB0002 {
+    // The project and the snapshots in the current format.
+    pub fn convert(self) -> (Project, Vec<Snapshot>) {
+        let _old_version = self.0;
+        let old_project = self.1;
 
         // Map ProjectVersion0002 to Project.
-        self.project = Project {
+        let project = Project {
             record: old_project.record,
 
             owner: ProjectOwner {
                 email: old_project.owner.email,
                 account: old_project.owner.account,
+                name: String::new(),
             },
 
             index_list: Project::default().index_list,
 
+            bibliography: Bibliography::merge(
+                old_project
+                    .survey
+                    .references
+                    .into_iter()
+                    .map(|x| x.into())
+                    .collect(),
+                old_project
+                    .references
+                    .references
+                    .into_iter()
+                    .map(|x| x.into())
+                    .collect(),
+            ),
+
             attachments: old_project.attachments.into(),
             funding: FundingOptions {
                 title: old_project.funding_program.title,
@@ -288,6 +313,7 @@ impl Workbook {
                     deadline: old_project.funding_program.deadline,
                     annotation: old_project.funding_program.annotation.into(),
                     visible: old_project.funding_program.visible,
+                    id: Identity::new(),
                 }],
                 visible: old_project.funding_program.visible,
             },
@@ -296,12 +322,6 @@ impl Workbook {
                 hypothesis: old_project.idea.idea.into(),
                 references: References {
                     title: old_project.references.title,
-                    references: old_project
-                        .references
-                        .references
-                        .into_iter()
-                        .map(|x| x.into())
-                        .collect(),
                     visible: old_project.references.visible,
                 },
                 visible: old_project.idea.visible,
@@ -311,12 +331,6 @@ impl Workbook {
             literature: Literature {
                 title: old_project.survey.title,
                 literature_survey: old_project.survey.literature_survey.into(),
-                references: old_project
-                    .survey
-                    .references
-                    .into_iter()
-                    .map(|x| x.into())
-                    .collect(),
                 visible: old_project.survey.visible,
             },
             methodology: Methodology {
@@ -379,116 +393,125 @@ impl Workbook {
             ..Default::default()
         };
 
-        self.stored_projects = Vec::new();
-        let old_stored_projects = work.2;
+        let mut stored_projects = Vec::new();
+        let old_stored_projects = self.2;
         for old_project in old_stored_projects {
-            self.stored_projects.push(Project {
-                record: old_project.record,
-                owner: ProjectOwner {
-                    email: old_project.owner.email,
-                    account: old_project.owner.account,
-                },
+            stored_projects.push(
+                Project {
+                    record: old_project.record,
+                    owner: ProjectOwner {
+                        email: old_project.owner.email,
+                        account: old_project.owner.account,
+                        name: String::new(),
+                    },
 
-                index_list: Project::default().index_list,
+                    index_list: Project::default().index_list,
 
-                attachments: old_project.attachments.into(),
-                funding: FundingOptions {
-                    title: old_project.funding_program.title,
-                    funding_options: vec![Programme {
-                        title: old_project.funding_program.name,
-                        hyperlink: old_project.funding_program.hyperlink,
-                        deadline: old_project.funding_program.deadline,
-                        annotation: old_project.funding_program.annotation.into(),
-                        visible: old_project.funding_program.visible,
-                    }],
-                    visible: old_project.funding_program.visible,
-                },
-                idea: Idea {
-                    title: old_project.idea.title,
-                    hypothesis: old_project.idea.idea.into(),
-                    references: References {
-                        title: old_project.references.title,
-                        references: old_project
+                    bibliography: Bibliography::merge(
+                        old_project
+                            .survey
+                            .references
+                            .into_iter()
+                            .map(|x| x.into())
+                            .collect(),
+                        old_project
                             .references
                             .references
                             .into_iter()
                             .map(|x| x.into())
                             .collect(),
-                        visible: old_project.references.visible,
+                    ),
+
+                    attachments: old_project.attachments.into(),
+                    funding: FundingOptions {
+                        title: old_project.funding_program.title,
+                        funding_options: vec![Programme {
+                            title: old_project.funding_program.name,
+                            hyperlink: old_project.funding_program.hyperlink,
+                            deadline: old_project.funding_program.deadline,
+                            annotation: old_project.funding_program.annotation.into(),
+                            visible: old_project.funding_program.visible,
+                            id: Identity::new(),
+                        }],
+                        visible: old_project.funding_program.visible,
+                    },
+                    idea: Idea {
+                        title: old_project.idea.title,
+                        hypothesis: old_project.idea.idea.into(),
+                        references: References {
+                            title: old_project.references.title,
+                            visible: old_project.references.visible,
+                        },
+                        visible: old_project.idea.visible,
+
+                        ..Default::default()
+                    },
+                    literature: Literature {
+                        title: old_project.survey.title,
+                        literature_survey: old_project.survey.literature_survey.into(),
+                        visible: old_project.survey.visible,
                     },
-                    visible: old_project.idea.visible,
+                    methodology: Methodology {
+                        title: old_project.offer.title,
+                        methodology: old_project.offer.offer.into(),
+                        visible: old_project.offer.visible,
+                    },
+                    outcomes: Outcomes {
+                        title: old_project.expected_results.title,
+                        results: old_project.expected_results.results.into(),
+                        visible: old_project.expected_results.visible,
 
-                    ..Default::default()
-                },
-                literature: Literature {
-                    title: old_project.survey.title,
-                    literature_survey: old_project.survey.literature_survey.into(),
-                    references: old_project
-                        .survey
-                        .references
-                        .into_iter()
-                        .map(|x| x.into())
-                        .collect(),
-                    visible: old_project.survey.visible,
-                },
-                methodology: Methodology {
-                    title: old_project.offer.title,
-                    methodology: old_project.offer.offer.into(),
-                    visible: old_project.offer.visible,
-                },
-                outcomes: Outcomes {
-                    title: old_project.expected_results.title,
-                    results: old_project.expected_results.results.into(),
-                    visible: old_project.expected_results.visible,
+                        ..Default::default()
+                    },
+                    prelim_results: PrelimResults {
+                        title: old_project.prelim_results.title,
+                        prelim_results: old_project.prelim_results.prelim_results.into(),
+                        visible: old_project.prelim_results.visible,
+                    },
+                    scope: Scope {
+                        title: old_project.contribution.title,
+                        activities: old_project.contribution.contribution.into(),
+                        visible: old_project.contribution.visible,
 
-                    ..Default::default()
-                },
-                prelim_results: PrelimResults {
-                    title: old_project.prelim_results.title,
-                    prelim_results: old_project.prelim_results.prelim_results.into(),
-                    visible: old_project.prelim_results.visible,
-                },
-                scope: Scope {
-                    title: old_project.contribution.title,
-                    activities: old_project.contribution.contribution.into(),
-                    visible: old_project.contribution.visible,
+                        ..Default::default()
+                    },
+                    scripting: Scripting {
+                        title: old_project.scripting.title,
+                        answer: old_project.scripting.answer.into(),
+                        scripting: old_project.scripting.scripting.into(),
+                        visible: old_project.scripting.visible,
+                    },
+                    team: Team {
+                        title: old_project.team.title,
+                        participants: old_project
+                            .team
+                            .partners
+                            .into_iter()
+                            .map(|x| x.into())
+                            .collect(),
+                        industrial_partners: old_project.team.industrial_partners.into(),
+                        proponents: old_project.team.proponents.into(),
+                        visible: old_project.team.visible,
 
-                    ..Default::default()
-                },
-                scripting: Scripting {
-                    title: old_project.scripting.title,
-                    answer: old_project.scripting.answer.into(),
-                    scripting: old_project.scripting.scripting.into(),
-                    visible: old_project.scripting.visible,
-                },
-                team: Team {
-                    title: old_project.team.title,
-                    participants: old_project
-                        .team
-                        .partners
-                        .into_iter()
-                        .map(|x| x.into())
-                        .collect(),
-                    industrial_partners: old_project.team.industrial_partners.into(),
-                    proponents: old_project.team.proponents.into(),
-                    visible: old_project.team.visible,
+                        ..Default::default()
+                    },
+                    timeline: Timeline {
+                        project_start: old_project.funding_program.project_start,
+                        duration_years: old_project.funding_program.duration_years,
 
-                    ..Default::default()
-                },
-                timeline: Timeline {
-                    project_start: old_project.funding_program.project_start,
-                    duration_years: old_project.funding_program.duration_years,
+                        ..Default::default()
+                    },
+                    working_name: ProjectTitle {
+                        title: old_project.working_name,
+                        visible: true,
+                        ..Default::default()
+                    },
 
                     ..Default::default()
-                },
-                working_name: ProjectTitle {
-                    title: old_project.working_name,
-                    visible: true,
-                    ..Default::default()
-                },
-
-                ..Default::default()
-            });
+                }
+                .into(),
+            );
         }
+        (project, stored_projects)
     }
 }
@@ -1,20 +1,22 @@
 use serde::Deserialize;
 
 use crate::workbook::{
-    app::Workbook,
     chapter::{Segment, Variety},
+    identity::Identity,
     note::{Note, Notes},
     project::{Project, ProjectOwner},
+    reference_formats::citation_style::CitationStyle,
     sections::{
         attachments::Attachments,
         budget::{Budget, SectionBudget},
+        cv::CvLength,
         funding::{FundingOptions, Programme},
         idea::{Idea, SectionIdea},
         literature::Literature,
         methodology::Methodology,
         outcomes::{Outcomes, SectionOutcomes},
         prelim_results::PrelimResults,
-        references::{Reference, References},
+        references::{Bibliography, Reading, Reference, ReferenceMetadata, References},
         resources::Resources,
         scope::{Scope, SectionScope},
         scripting::{Scripting, ScriptingOption},
@@ -22,6 +24,7 @@ use crate::workbook::{
         timeline::{Date, SectionTimeline, Timeline},
         working_name::ProjectTitle,
     },
+    snapshots::Snapshot,
 };
 
 #[derive(Deserialize)]
@@ -424,7 +427,18 @@ enum SectionTimeline0003 {
 }
 
 impl From<ProjectVersion0003> for Project {
-    fn from(val: ProjectVersion0003) -> Self {
+    fn from(mut val: ProjectVersion0003) -> Self {
+        let bibliography = Bibliography::merge(
+            std::mem::take(&mut val.literature.references)
+                .into_iter()
+                .map(|x| x.into())
+                .collect(),
+            std::mem::take(&mut val.idea.references.references)
+                .into_iter()
+                .map(|x| x.into())
+                .collect(),
+        );
+
         Project {
             record: val.record,
             owner: val.owner.into(),
@@ -433,6 +447,9 @@ impl From<ProjectVersion0003> for Project {
 
             resolution: val.resolution,
 
+            citation_style: CitationStyle::default(),
+            bibliography,
+
             attachments: val.attachments.into(),
             budget: val.budget.into(),
             funding: val.funding.into(),
@@ -465,9 +482,18 @@ impl From<Attachments0003> for Attachments {
 
 impl From<Budget0003> for Budget {
     fn from(val: Budget0003) -> Self {
+        let mut index_list: Vec<Segment> = val.index_list.into_iter().map(|x| x.into()).collect();
+        // The chapters introduced after the format.
+        index_list.insert(
+            0,
+            Segment {
+                variety: Variety::SectionBudget(SectionBudget::Costs),
+                tier: 2,
+            },
+        );
         Budget {
             title: val.title,
-            index_list: val.index_list.into_iter().map(|x| x.into()).collect(),
+            index_list,
             personnel: val.personnel.into(),
             facilities: val.facilities.into(),
             materials: val.materials.into(),
@@ -520,7 +546,6 @@ impl From<Literature0003> for Literature {
         Literature {
             title: val.title,
             literature_survey: val.literature_survey.into(),
-            references: val.references.into_iter().map(|x| x.into()).collect(),
             visible: val.visible,
         }
     }
@@ -542,6 +567,7 @@ impl From<Note0003> for Note {
             note: val.note,
             hint: val.hint,
             visible: val.visible,
+            id: Identity::new(),
         }
     }
 }
@@ -580,9 +606,12 @@ impl From<Participant0003> for Participant {
             contribution: val.contribution.into(),
             team: val.team.into(),
             cv: val.cv.into(),
+            cv_entries: Vec::new(),
             resources: val.resources.into(),
             budget: val.budget.into(),
+            costs: Vec::new(),
             visible: val.visible,
+            id: Identity::new(),
         }
     }
 }
@@ -605,6 +634,7 @@ impl From<Programme0003> for Programme {
             deadline: val.deadline.into(),
             annotation: val.annotation.into(),
             visible: val.visible,
+            id: Identity::new(),
         }
     }
 }
@@ -614,6 +644,7 @@ impl From<ProjectOwner0003> for ProjectOwner {
         ProjectOwner {
             email: val.email,
             account: val.account,
+            name: String::new(),
         }
     }
 }
@@ -635,6 +666,9 @@ impl From<Reference0003> for Reference {
             hyperlink: val.hyperlink,
             source_details: val.source_details.into(),
             visible: val.visible,
+            metadata: ReferenceMetadata::default(),
+            reading: Reading::default(),
+            id: Identity::new(),
         }
     }
 }
@@ -643,7 +677,6 @@ impl From<References0003> for References {
     fn from(val: References0003) -> Self {
         References {
             title: val.title,
-            references: val.references.into_iter().map(|x| x.into()).collect(),
             visible: val.visible,
         }
     }
@@ -697,14 +730,22 @@ impl From<Segment0003> for Segment {
 
 impl From<Team0003> for Team {
     fn from(val: Team0003) -> Self {
+        let mut index_list: Vec<Segment> = val.index_list.into_iter().map(|x| x.into()).collect();
+        // The chapters introduced after the format.
+        index_list.push(Segment {
+            variety: Variety::SectionTeam(SectionTeam::Effort),
+            tier: 3,
+        });
         Team {
             title: val.title,
-            index_list: val.index_list.into_iter().map(|x| x.into()).collect(),
+            index_list,
             proposed_partners: val.proposed_partners.into(),
             project_leader: val.project_leader.into(),
             industrial_partners: val.industrial_partners.into(),
             proponents: val.proponents.into(),
             participants: val.participants.into_iter().map(|x| x.into()).collect(),
+            cv_length: CvLength::default(),
+            work_packages: Vec::new(),
             visible: val.visible,
         }
     }
@@ -860,15 +901,20 @@ impl From<Variety0003> for Variety {
     }
 }
 
-impl Workbook {
-    pub fn apply_format_b0003(&mut self, work: WorkVersionB0003) {
-        let _old_version = work.0;
-        let old_project = work.1;
+impl WorkVersionB0003 {
+    // The project and the snapshots in the current format.
+    pub fn convert(self) -> (Project, Vec<Snapshot>) {
+        let _old_version = self.0;
+        let old_project = self.1;
 
         // Map ProjectVersion0003 to Project.
-        self.project = old_project.into();
-
-        let old_stored_projects = work.2;
-        self.stored_projects = old_stored_projects.into_iter().map(|x| x.into()).collect();
+        let project = old_project.into();
+
+        let old_stored_projects = self.2;
+        let stored_projects = old_stored_projects
+            .into_iter()
+            .map(|x| Project::from(x).into())
+            .collect();
+        (project, stored_projects)
     }
 }
@@ -0,0 +1,55 @@
+use serde::Deserialize;
+
+use crate::workbook::{
+    project::{Project, ProjectOwner},
+    snapshots::Snapshot,
+    store::v_b0005::{Chunk, Store},
+};
+
+// The format B0004 is the format B0005 without the guard and the extensions,
+// the chunks are the same.
+#[derive(Deserialize)]
+pub struct WorkVersionB0004 {
+    format: String,
+    owner: ProjectOwner0004,
+    record: String,
+    resolution: usize,
+    chunks: Vec<Chunk>,
+}
+
+impl WorkVersionB0004 {
+    pub fn decode_bincode(encoded: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(encoded)
+    }
+}
+
+#[derive(Deserialize)]
+struct ProjectOwner0004 {
+    email: String,
+    account: String,
+}
+
+impl From<ProjectOwner0004> for ProjectOwner {
+    fn from(val: ProjectOwner0004) -> Self {
+        ProjectOwner {
+            email: val.email,
+            account: val.account,
+            name: String::new(),
+        }
+    }
+}
+
+impl WorkVersionB0004 {
+    // The project in the current format, the format B0004 has no snapshots.
+    pub fn convert(self) -> (Project, Vec<Snapshot>) {
+        let _old_version = self.format;
+        let store = Store {
+            owner: self.owner.into(),
+            record: self.record,
+            resolution: self.resolution,
+            chunks: self.chunks,
+            ..Default::default()
+        };
+        (store.into(), Vec::new())
+    }
+}
@@ -0,0 +1,880 @@
+// Three-way merge of the copies of the workbook edited by the partners apart.
+// The notes, programmes, participants, references and work packages are matched
+// by their identifiers, the title, the timeline and the settings by the field:
+// the changes made in one copy only are taken automatically,
+// the items changed differently in both copies are the conflicts to resolve.
+// Without the common ancestor (e.g. a snapshot made before the copy was sent),
+// nothing is deleted and every item changed in the other copy is a conflict.
+
+use std::collections::HashMap;
+
+use egui::{Context, RichText, Ui};
+
+use super::app::Workbook;
+use super::compare::Version;
+use super::identity::Identity;
+use super::note::{Note, Notes};
+use super::project::Project;
+use super::sections::costs;
+use super::sections::cv::CvEntry;
+use super::sections::effort::WorkPackage;
+use super::sections::funding::Programme;
+use super::sections::references::{Bibliography, Reference};
+use super::sections::team::Participant;
+use super::sections::timeline::Date;
+
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum Side {
+    // The current project.
+    #[default]
+    This,
+    // The workbook merged into the current project.
+    Other,
+}
+
+// The item changed differently in the two copies, or changed in one copy
+// and deleted in the other.
+#[derive(Debug, PartialEq)]
+pub struct Conflict {
+    pub id: Identity,
+    pub path: String,
+    // The item in this workbook and in the other one, `None` if deleted.
+    pub this: Option<String>,
+    pub other: Option<String>,
+}
+
+pub struct Merged {
+    pub project: Project,
+    pub conflicts: Vec<Conflict>,
+    // The changes taken from the other workbook automatically.
+    pub taken: Vec<String>,
+}
+
+// The items matched by the identifiers.
+trait Item: Clone {
+    fn id(&self) -> Identity;
+    fn name(&self) -> String;
+    // The fields except the lists of notes are the same.
+    fn same(&self, other: &Self) -> bool;
+    // The fields except the lists of notes, as shown in the conflicts.
+    fn summary(&self) -> String;
+    fn notes_mut(&mut self) -> Vec<(&'static str, &mut Notes)>;
+}
+
+impl Item for Note {
+    fn id(&self) -> Identity {
+        self.id
+    }
+
+    fn name(&self) -> String {
+        const LENGTH: usize = 40;
+        let text = self.note.split_whitespace().collect::<Vec<_>>().join(" ");
+        match text.char_indices().nth(LENGTH) {
+            Some((end, _)) => format!("“{}…”", &text[..end]),
+            None if text.is_empty() => "Empty note".to_string(),
+            None => format!("“{}”", text),
+        }
+    }
+
+    fn same(&self, other: &Self) -> bool {
+        self.note == other.note && self.visible == other.visible
+    }
+
+    fn summary(&self) -> String {
+        if self.visible {
+            self.note.clone()
+        } else {
+            format!("{}\n(hidden)", self.note)
+        }
+    }
+
+    fn notes_mut(&mut self) -> Vec<(&'static str, &mut Notes)> {
+        Vec::new()
+    }
+}
+
+impl Item for Programme {
+    fn id(&self) -> Identity {
+        self.id
+    }
+
+    fn name(&self) -> String {
+        item_name(&self.title, "Programme")
+    }
+
+    fn same(&self, other: &Self) -> bool {
+        self.title == other.title
+            && self.hyperlink == other.hyperlink
+            && self.deadline == other.deadline
+            && self.visible == other.visible
+    }
+
+    fn summary(&self) -> String {
+        let deadline = &self.deadline;
+        summary(&[
+            ("", &self.title),
+            ("Link", &self.hyperlink),
+            (
+                "Deadline",
+                &format!(
+                    "{}-{:02}-{:02}",
+                    deadline.year, deadline.month, deadline.day
+                ),
+            ),
+        ])
+    }
+
+    fn notes_mut(&mut self) -> Vec<(&'static str, &mut Notes)> {
+        vec![("Annotation", &mut self.annotation)]
+    }
+}
+
+impl Item for Participant {
+    fn id(&self) -> Identity {
+        self.id
+    }
+
+    fn name(&self) -> String {
+        item_name(&self.name, "Participant")
+    }
+
+    fn same(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.role == other.role
+            && self.affiliation == other.affiliation
+            && self.hyperlink == other.hyperlink
+            && self.country == other.country
+            && self.expertise == other.expertise
+            && self.cv_entries == other.cv_entries
+            && self.costs == other.costs
+            && self.visible == other.visible
+    }
+
+    fn summary(&self) -> String {
+        let cv: Vec<String> = self.cv_entries.iter().map(CvEntry::line).collect();
+        let costs = if self.costs.is_empty() {
+            String::new()
+        } else {
+            costs::line(&self.costs)
+        };
+        summary(&[
+            ("", &self.name),
+            ("Role", &self.role),
+            ("Affiliation", &self.affiliation),
+            ("Web address", &self.hyperlink),
+            ("Country", &self.country),
+            ("Expertise", &self.expertise),
+            ("CV", &cv.join("; ")),
+            ("Costs", &costs),
+        ])
+    }
+
+    fn notes_mut(&mut self) -> Vec<(&'static str, &mut Notes)> {
+        Participant::notes_mut(self).into()
+    }
+}
+
+impl Item for Reference {
+    fn id(&self) -> Identity {
+        self.id
+    }
+
+    fn name(&self) -> String {
+        item_name(&self.title, "Reference")
+    }
+
+    fn same(&self, other: &Self) -> bool {
+        self.title == other.title
+            && self.hyperlink == other.hyperlink
+            && self.visible == other.visible
+            && self.metadata == other.metadata
+            && self.reading == other.reading
+    }
+
+    fn summary(&self) -> String {
+        let data = &self.metadata;
+        let reading = &self.reading;
+        summary(&[
+            ("", &self.title),
+            ("Authors", &data.authors),
+            ("Year", &data.year),
+            ("Published in", &data.container),
+            ("DOI", &data.doi),
+            ("Link", &self.hyperlink),
+            ("Status", reading.status.name()),
+            ("Tags", &reading.tags),
+            ("Annotation", &reading.annotation),
+        ])
+    }
+
+    fn notes_mut(&mut self) -> Vec<(&'static str, &mut Notes)> {
+        vec![("Source details", &mut self.source_details)]
+    }
+}
+
+impl Item for WorkPackage {
+    fn id(&self) -> Identity {
+        self.id
+    }
+
+    fn name(&self) -> String {
+        item_name(&self.title, "Work package")
+    }
+
+    fn same(&self, other: &Self) -> bool {
+        self.title == other.title && self.effort == other.effort
+    }
+
+    fn summary(&self) -> String {
+        let months: f32 = self.effort.iter().map(|effort| effort.months).sum();
+        summary(&[
+            ("", &self.title),
+            ("Person-months", &format!("{} in total", months)),
+        ])
+    }
+
+    fn notes_mut(&mut self) -> Vec<(&'static str, &mut Notes)> {
+        Vec::new()
+    }
+}
+
+fn item_name(title: &str, kind: &str) -> String {
+    if title.trim().is_empty() {
+        format!("Unnamed {}", kind.to_lowercase())
+    } else {
+        title.trim().to_string()
+    }
+}
+
+// The non-empty fields, one per line.
+fn summary(fields: &[(&str, &str)]) -> String {
+    fields
+        .iter()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(name, text)| {
+            if name.is_empty() {
+                text.to_string()
+            } else {
+                format!("{}: {}", name, text)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// The lists of notes of the item, or of the sections of the project.
+fn lists<T: Item>(item: &T) -> Vec<Notes> {
+    item.clone()
+        .notes_mut()
+        .into_iter()
+        .map(|(_, notes)| notes.clone())
+        .collect()
+}
+
+fn format_date(date: &Date) -> String {
+    format!("{}-{:02}-{:02}", date.year, date.month, date.day)
+}
+
+fn section_lists(project: &Project) -> Vec<Notes> {
+    let mut lists = Vec::new();
+    project.visit_section_notes(&mut |_, notes| lists.push(notes.clone()));
+    lists
+}
+
+// The item and its notes are not changed.
+fn unchanged<T: Item>(item: &T, original: &T) -> bool {
+    let same_notes = |a: &Notes, b: &Notes| {
+        a.notes.len() == b.notes.len()
+            && a.notes
+                .iter()
+                .zip(b.notes.iter())
+                .all(|(a, b)| a.id == b.id && a.same(b))
+    };
+    item.same(original)
+        && lists(item)
+            .iter()
+            .zip(lists(original).iter())
+            .all(|(a, b)| same_notes(a, b))
+}
+
+struct Merger<'a> {
+    choices: &'a HashMap<Identity, Side>,
+    conflicts: Vec<Conflict>,
+    taken: Vec<String>,
+}
+
+impl Merger<'_> {
+    // This workbook is chosen unless the other one is chosen for the conflict.
+    fn resolve(
+        &mut self,
+        id: Identity,
+        path: &str,
+        this: Option<String>,
+        other: Option<String>,
+    ) -> Side {
+        self.conflicts.push(Conflict {
+            id,
+            path: path.to_string(),
+            this,
+            other,
+        });
+        self.choices.get(&id).copied().unwrap_or_default()
+    }
+
+    fn merge_list<T: Item>(
+        &mut self,
+        path: &str,
+        original: Option<&[T]>,
+        this: &[T],
+        other: &[T],
+    ) -> Vec<T> {
+        let find = |list: &[T], id: Identity| list.iter().find(|item| item.id() == id).cloned();
+        let mut merged = Vec::new();
+        for item in this {
+            let path = format!("{} › {}", path, item.name());
+            let before = original.and_then(|original| find(original, item.id()));
+            match (find(other, item.id()), before) {
+                (Some(changed), before) => {
+                    merged.push(self.merge_item(&path, before.as_ref(), item, &changed));
+                }
+                // Deleted in the other workbook.
+                (None, Some(before)) => {
+                    if unchanged(item, &before) {
+                        self.taken.push(format!("Deleted: {}", path));
+                    } else if self.resolve(item.id(), &path, Some(item.summary()), None)
+                        == Side::This
+                    {
+                        merged.push(item.clone());
+                    }
+                }
+                // Added to this workbook.
+                (None, None) => merged.push(item.clone()),
+            }
+        }
+
+        for (k, item) in other.iter().enumerate() {
+            if find(this, item.id()).is_some() {
+                continue;
+            }
+            let path = format!("{} › {}", path, item.name());
+            let add = match original.and_then(|original| find(original, item.id())) {
+                None => {
+                    self.taken.push(format!("Added: {}", path));
+                    true
+                }
+                // Deleted in this workbook.
+                Some(before) if unchanged(item, &before) => false,
+                Some(_) => {
+                    self.resolve(item.id(), &path, None, Some(item.summary())) == Side::Other
+                }
+            };
+            if add {
+                // After the item it follows in the other workbook.
+                let position = other[..k]
+                    .iter()
+                    .rev()
+                    .find_map(|previous| {
+                        merged
+                            .iter()
+                            .position(|merged: &T| merged.id() == previous.id())
+                    })
+                    .map_or(0, |i| i + 1);
+                merged.insert(position, item.clone());
+            }
+        }
+        merged
+    }
+
+    // The field changed in one copy only is taken, the one changed
+    // in both copies differently is the conflict.
+    fn merge_field<T: Clone + PartialEq>(
+        &mut self,
+        path: &str,
+        original: Option<&T>,
+        this: &T,
+        other: &T,
+        show: impl Fn(&T) -> String,
+    ) -> T {
+        if this == other || original == Some(other) {
+            this.clone()
+        } else if original == Some(this) {
+            self.taken.push(format!("Changed: {}", path));
+            other.clone()
+        } else {
+            let id = Identity::of_field(path);
+            match self.resolve(id, path, Some(show(this)), Some(show(other))) {
+                Side::This => this.clone(),
+                Side::Other => other.clone(),
+            }
+        }
+    }
+
+    fn merge_item<T: Item>(&mut self, path: &str, original: Option<&T>, this: &T, other: &T) -> T {
+        let mut merged = if this.same(other) || original.is_some_and(|before| other.same(before)) {
+            this.clone()
+        } else if original.is_some_and(|before| this.same(before)) {
+            self.taken.push(format!("Changed: {}", path));
+            other.clone()
+        } else {
+            match self.resolve(this.id(), path, Some(this.summary()), Some(other.summary())) {
+                Side::This => this.clone(),
+                Side::Other => other.clone(),
+            }
+        };
+
+        // The notes of the item are merged one by one.
+        let originals = original.map(lists);
+        let (these, others) = (lists(this), lists(other));
+        for (i, (name, notes)) in merged.notes_mut().into_iter().enumerate() {
+            *notes = self.merge_notes(
+                &format!("{} › {}", path, name),
+                originals.as_ref().map(|lists| &lists[i]),
+                &these[i],
+                &others[i],
+            );
+        }
+        merged
+    }
+
+    fn merge_notes(
+        &mut self,
+        path: &str,
+        original: Option<&Notes>,
+        this: &Notes,
+        other: &Notes,
+    ) -> Notes {
+        Notes {
+            notes: self.merge_list(
+                path,
+                original.map(|notes| &notes.notes[..]),
+                &this.notes,
+                &other.notes,
+            ),
+        }
+    }
+
+    fn merge_bibliography(
+        &mut self,
+        original: Option<&Bibliography>,
+        this: &Bibliography,
+        other: &Bibliography,
+    ) -> Bibliography {
+        let references = self.merge_list(
+            "Literature Survey",
+            original.map(|bibliography| &bibliography.references[..]),
+            &this.references,
+            &other.references,
+        );
+
+        // A key reference selected or unselected in one copy only.
+        let (these, others) = (&this.key_references, &other.key_references);
+        let originals = original.map(|bibliography| &bibliography.key_references);
+        let selected = |id: &Identity| match &originals {
+            Some(originals) if these.contains(id) == originals.contains(id) => others.contains(id),
+            Some(_) => these.contains(id),
+            None => these.contains(id) || others.contains(id),
+        };
+        let mut key_references = Vec::new();
+        for id in these.iter().chain(others.iter()) {
+            let present = references.iter().any(|reference| reference.id == *id);
+            if present && selected(id) && !key_references.contains(id) {
+                key_references.push(*id);
+            }
+        }
+
+        Bibliography {
+            references,
+            key_references,
+        }
+    }
+}
+
+// Merge the other workbook into this one, the conflicts are resolved
+// with the `choices`. The titles, the order and the visibility of the sections
+// and the level of details are kept from this workbook.
+pub fn merge(
+    original: Option<&Project>,
+    this: &Project,
+    other: &Project,
+    choices: &HashMap<Identity, Side>,
+) -> Merged {
+    let mut merger = Merger {
+        choices,
+        conflicts: Vec::new(),
+        taken: Vec::new(),
+    };
+    let mut project = this.clone();
+
+    project.working_name.title = merger.merge_field(
+        "Project Title",
+        original.map(|project| &project.working_name.title),
+        &this.working_name.title,
+        &other.working_name.title,
+        String::clone,
+    );
+    project.timeline.project_start = merger.merge_field(
+        "Timeline › Start",
+        original.map(|project| &project.timeline.project_start),
+        &this.timeline.project_start,
+        &other.timeline.project_start,
+        format_date,
+    );
+    project.timeline.duration_years = merger.merge_field(
+        "Timeline › Duration",
+        original.map(|project| &project.timeline.duration_years),
+        &this.timeline.duration_years,
+        &other.timeline.duration_years,
+        |years| format!("{} years", years),
+    );
+    project.citation_style = merger.merge_field(
+        "Literature Survey › Citation style",
+        original.map(|project| &project.citation_style),
+        &this.citation_style,
+        &other.citation_style,
+        |style| style.name().to_string(),
+    );
+    project.team.cv_length = merger.merge_field(
+        "Team › CV length",
+        original.map(|project| &project.team.cv_length),
+        &this.team.cv_length,
+        &other.team.cv_length,
+        |length| length.name().to_string(),
+    );
+
+    let originals = original.map(section_lists);
+    let (these, others) = (section_lists(this), section_lists(other));
+    let mut i = 0;
+    project.visit_section_notes_mut(&mut |name, notes| {
+        *notes = merger.merge_notes(
+            name,
+            originals.as_ref().map(|lists| &lists[i]),
+            &these[i],
+            &others[i],
+        );
+        i += 1;
+    });
+
+    project.funding.funding_options = merger.merge_list(
+        "Funding",
+        original.map(|project| &project.funding.funding_options[..]),
+        &this.funding.funding_options,
+        &other.funding.funding_options,
+    );
+    project.team.participants = merger.merge_list(
+        "Team",
+        original.map(|project| &project.team.participants[..]),
+        &this.team.participants,
+        &other.team.participants,
+    );
+    project.team.work_packages = merger.merge_list(
+        "Team › Work packages",
+        original.map(|project| &project.team.work_packages[..]),
+        &this.team.work_packages,
+        &other.team.work_packages,
+    );
+    project.bibliography = merger.merge_bibliography(
+        original.map(|project| &project.bibliography),
+        &this.bibliography,
+        &other.bibliography,
+    );
+
+    Merged {
+        project,
+        conflicts: merger.conflicts,
+        taken: merger.taken,
+    }
+}
+
+// The state of the "Merge" window.
+#[derive(Default)]
+pub struct Merging {
+    // The workbook merged into the current project.
+    pub other: Option<Project>,
+    // The index of the snapshot both copies were made from.
+    pub original: Option<usize>,
+    pub choices: HashMap<Identity, Side>,
+    merged: Option<Merged>,
+}
+
+impl Merging {
+    pub fn with(other: Project) -> Self {
+        Merging {
+            other: Some(other),
+            ..Default::default()
+        }
+    }
+}
+
+impl Workbook {
+    pub fn show_merge(&mut self, ctx: &Context) {
+        if !self.windows.merge_open {
+            return;
+        }
+        let mut merging = std::mem::take(&mut self.windows.merge);
+        let Some(other) = merging.other.take() else {
+            self.windows.merge_open = false;
+            return;
+        };
+        let original = merging
+            .original
+            .filter(|&i| i < self.stored_projects.len())
+            .map(|i| &self.stored_projects[i].project);
+        let merged = merging
+            .merged
+            .take()
+            .unwrap_or_else(|| merge(original, &self.project, &other, &merging.choices));
+
+        let mut open = self.windows.merge_open;
+        let mut stale = false;
+        let mut apply = false;
+        egui::Window::new("Merge")
+            .open(&mut open)
+            .default_width(560.)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Merge “{}” into the current project.",
+                    other.working_name.title
+                ));
+                ui.label(
+                    RichText::new(
+                        "The titles, the order and the visibility of the sections \
+                        and the level of details are kept from the current project.",
+                    )
+                    .small()
+                    .weak(),
+                );
+                ui.horizontal(|ui| {
+                    ui.label("Common ancestor:");
+                    let name = merging.original.map_or("None".to_string(), |i| {
+                        self.version_name(Version::Snapshot(i))
+                    });
+                    egui::ComboBox::from_id_source("merge_original")
+                        .selected_text(name)
+                        .show_ui(ui, |ui| {
+                            stale |= ui
+                                .selectable_value(&mut merging.original, None, "None")
+                                .clicked();
+                            for i in (0..self.stored_projects.len()).rev() {
+                                let name = self.version_name(Version::Snapshot(i));
+                                stale |= ui
+                                    .selectable_value(&mut merging.original, Some(i), name)
+                                    .clicked();
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "The snapshot both copies were made from. \
+                            Without it, nothing is deleted and the items \
+                            changed in both copies are conflicts.",
+                        );
+                    if ui
+                        .button("Refresh")
+                        .on_hover_text("Merge with the current project again")
+                        .clicked()
+                    {
+                        stale = true;
+                    }
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .max_height(420.)
+                    .show(ui, |ui| {
+                        egui::CollapsingHeader::new(format!(
+                            "Taken from the other workbook ({})",
+                            merged.taken.len()
+                        ))
+                        .default_open(merged.conflicts.is_empty())
+                        .show(ui, |ui| {
+                            for change in &merged.taken {
+                                ui.label(RichText::new(change).small());
+                            }
+                        });
+
+                        egui::CollapsingHeader::new(format!(
+                            "Conflicts ({})",
+                            merged.conflicts.len()
+                        ))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for conflict in &merged.conflicts {
+                                stale |= edit_conflict(ui, conflict, &mut merging.choices);
+                                ui.add_space(6.);
+                            }
+                        });
+                    });
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if !merged.conflicts.is_empty() {
+                        for (side, name) in [(Side::This, "Keep all"), (Side::Other, "Take all")] {
+                            if ui.button(name).clicked() {
+                                for conflict in &merged.conflicts {
+                                    merging.choices.insert(conflict.id, side);
+                                }
+                                stale = true;
+                            }
+                        }
+                    }
+                    if ui
+                        .button("Merge")
+                        .on_hover_text(
+                            "Replace the current project with the result, \
+                            the snapshot “Before merge” is made to be able to go back",
+                        )
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                });
+            });
+
+        if apply {
+            let merged = merge(original, &self.project, &other, &merging.choices).project;
+            self.make_snapshot("Before merge");
+            self.project = merged;
+            self.windows.search.forget_undo();
+            self.windows.merge_open = false;
+            return;
+        }
+        if !stale {
+            merging.merged = Some(merged);
+        }
+        merging.other = Some(other);
+        self.windows.merge_open = open;
+        self.windows.merge = merging;
+    }
+}
+
+// The versions of the conflicting item, returns whether the choice is changed.
+fn edit_conflict(ui: &mut Ui, conflict: &Conflict, choices: &mut HashMap<Identity, Side>) -> bool {
+    let mut changed = false;
+    ui.label(RichText::new(&conflict.path).strong());
+    let mut choice = choices.get(&conflict.id).copied().unwrap_or_default();
+    ui.columns(2, |columns| {
+        for (ui, (side, name, text)) in columns.iter_mut().zip([
+            (Side::This, "This workbook", &conflict.this),
+            (Side::Other, "Other workbook", &conflict.other),
+        ]) {
+            changed |= ui.radio_value(&mut choice, side, name).changed();
+            match text {
+                Some(text) => ui.label(text),
+                None => ui.label(RichText::new("Deleted").italics().weak()),
+            };
+        }
+    });
+    if changed {
+        choices.insert(conflict.id, choice);
+    }
+    changed
+}
+
+// Test that the changes made in one copy are merged and the others are conflicts.
+#[test]
+fn merge_projects() {
+    use super::reference_formats::citation_style::CitationStyle;
+
+    let note = |text: &str| Note {
+        note: text.to_string(),
+        ..Note::new()
+    };
+    let mut original = Project::default();
+    original.idea.problem.notes = vec![note("Kept."), note("Edited."), note("Deleted.")];
+    original.team.participants = vec![Participant {
+        name: "Jane Doe".to_string(),
+        ..Default::default()
+    }];
+    original.bibliography.references = vec![Reference {
+        title: "Paper".to_string(),
+        ..Default::default()
+    }];
+
+    let mut this = original.clone();
+    this.idea.problem.notes[1].note = "Edited here.".to_string();
+    this.team.participants[0].role = "Leader".to_string();
+    this.bibliography.key_references = vec![original.bibliography.references[0].id];
+
+    let mut other = original.clone();
+    other.idea.problem.notes[1].note = "Edited there.".to_string();
+    other.idea.problem.notes.remove(2);
+    other.idea.problem.notes.insert(1, note("Added."));
+    other.team.participants[0].country = "Norway".to_string();
+    other.team.participants[0].cv.notes = vec![note("CV.")];
+    other.bibliography.references.push(Reference {
+        title: "Book".to_string(),
+        ..Default::default()
+    });
+
+    let choices = HashMap::new();
+    let merged = merge(Some(&original), &this, &other, &choices);
+    let texts = |project: &Project| -> Vec<String> {
+        project
+            .idea
+            .problem
+            .notes
+            .iter()
+            .map(|note| note.note.clone())
+            .collect()
+    };
+    assert_eq!(texts(&merged.project), ["Kept.", "Added.", "Edited here."]);
+    assert_eq!(merged.conflicts.len(), 2);
+    assert_eq!(merged.conflicts[0].path, "Idea › Problem › “Edited here.”");
+    assert_eq!(merged.conflicts[0].other, Some("Edited there.".to_string()));
+    // The participant is changed in both copies, the CV in the other one only.
+    assert_eq!(merged.conflicts[1].path, "Team › Jane Doe");
+    let participant = &merged.project.team.participants[0];
+    assert_eq!(participant.role, "Leader");
+    assert_eq!(participant.cv.notes[0].note, "CV.");
+    let bibliography = &merged.project.bibliography;
+    assert_eq!(bibliography.references.len(), 2);
+    assert_eq!(
+        bibliography.key_references,
+        vec![original.bibliography.references[0].id]
+    );
+
+    let choices = merged
+        .conflicts
+        .iter()
+        .map(|conflict| (conflict.id, Side::Other))
+        .collect();
+    let merged = merge(Some(&original), &this, &other, &choices);
+    assert_eq!(texts(&merged.project), ["Kept.", "Added.", "Edited there."]);
+    assert_eq!(merged.project.team.participants[0].country, "Norway");
+
+    // Without the ancestor, nothing is deleted.
+    let merged = merge(None, &this, &other, &HashMap::new());
+    assert_eq!(merged.project.idea.problem.notes.len(), 4);
+    assert_eq!(merged.conflicts.len(), 2);
+    assert!(merged
+        .taken
+        .iter()
+        .all(|change| change.starts_with("Added")));
+
+    // The title, the timeline and the settings are merged by the field,
+    // the work packages by the identifier.
+    let mut original = Project::default();
+    original.team.work_packages = vec![WorkPackage {
+        title: "Field work".to_string(),
+        ..Default::default()
+    }];
+    let mut this = original.clone();
+    this.working_name.title = "Title here".to_string();
+    this.timeline.duration_years = 3.;
+    let mut other = original.clone();
+    other.working_name.title = "Title there".to_string();
+    other.citation_style = CitationStyle::Ieee;
+    other.team.work_packages[0].title = "Fieldwork".to_string();
+    let merged = merge(Some(&original), &this, &other, &HashMap::new());
+    assert_eq!(merged.conflicts.len(), 1);
+    assert_eq!(merged.conflicts[0].path, "Project Title");
+    assert_eq!(merged.project.working_name.title, "Title here");
+    assert_eq!(merged.project.timeline.duration_years, 3.);
+    assert_eq!(merged.project.citation_style, CitationStyle::Ieee);
+    assert_eq!(merged.project.team.work_packages[0].title, "Fieldwork");
+
+    let choices = HashMap::from([(merged.conflicts[0].id, Side::Other)]);
+    let merged = merge(Some(&original), &this, &other, &choices);
+    assert_eq!(merged.project.working_name.title, "Title there");
+}
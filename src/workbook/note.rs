@@ -1,9 +1,15 @@
-use egui::{TextEdit, Ui};
+use egui::text::{LayoutJob, TextFormat};
+use egui::{TextEdit, TextStyle, Ui};
 use serde::{Deserialize, Serialize};
 
 use super::editor::{Editor, BIN_ICON_COLOR};
+use super::exporter::{Document, Exporter};
+use super::identity::Identity;
+use super::reference_formats::citations::{Citations, Cited};
 use super::renderer::Renderer;
-use super::visuals::{style_bin_button, style_move_button};
+use super::search;
+use super::snippets;
+use super::visuals::{style_bin_button, style_move_button, style_snippet_button};
 
 #[derive(Deserialize, Serialize, Clone, Default)]
 pub struct Notes {
@@ -16,6 +22,9 @@ pub struct Note {
     #[serde(skip)]
     pub hint: String,
     pub visible: bool,
+    // Kept in the storage apart from the note, see `Project::visit_notes()`.
+    #[serde(skip)]
+    pub id: Identity,
 }
 
 impl Default for Note {
@@ -24,6 +33,7 @@ impl Default for Note {
             note: Note::new().note,
             hint: String::from("…"),
             visible: true,
+            id: Identity::new(),
         }
     }
 }
@@ -34,6 +44,7 @@ impl Note {
             note: String::new(),
             hint: String::from("…"),
             visible: true,
+            id: Identity::new(),
         }
     }
 }
@@ -74,15 +85,31 @@ impl Editor for Notes {
                     {
                         note_to_delete = Some(i)
                     }
+
+                    // Save in the library of snippets
+                    if !item.note.trim().is_empty()
+                        && ui
+                            .add(style_snippet_button(icon_color))
+                            .on_hover_text(
+                                "Save as a snippet to insert it into other notes \
+                                and workbooks",
+                            )
+                            .clicked()
+                    {
+                        snippets::save(ui.ctx(), &item.note);
+                    }
                 });
                 ui.vertical_centered_justified(|ui| {
-                    TextEdit::multiline(&mut item.note)
+                    let output = TextEdit::multiline(&mut item.note)
                         .hint_text(&item.hint)
                         .show(ui);
+                    search::scroll_to(ui, &output.response, &item.note);
                 });
             });
         }
 
+        snippets::insert_menu(ui, self);
+
         if let Some(i) = note_to_move {
             self.notes.swap(i, i - 1);
         }
@@ -103,7 +130,36 @@ impl Renderer for Notes {
                             e.g. Some(10.0) or None",
                     ));
                 }
-                ui.label(&note.note);
+                preview_text(ui, &note.note);
+            }
+        });
+    }
+}
+
+// The text of a note with the citations resolved, the unresolved ones are highlighted.
+pub fn preview_text(ui: &mut Ui, text: &str) {
+    let Some(citations) = Citations::shared(ui.ctx()) else {
+        ui.label(text);
+        return;
+    };
+
+    let font_id = TextStyle::Body.resolve(ui.style());
+    let mut job = LayoutJob::default();
+    for cited in citations.render(text) {
+        let (part, color) = match cited {
+            Cited::Text(part) | Cited::Citation(part) => (part, ui.visuals().text_color()),
+            Cited::Unresolved(part) => (part, ui.visuals().warn_fg_color),
+        };
+        job.append(&part, 0.0, TextFormat::simple(font_id.clone(), color));
+    }
+    ui.label(job);
+}
+
+impl Exporter for Notes {
+    fn export(&self, document: &mut Document) {
+        self.notes.iter().for_each(|note| {
+            if note.visible {
+                document.paragraph(&note.note);
             }
         });
     }
@@ -0,0 +1,268 @@
+// The notifications of the results of opening, saving, importing and exporting,
+// shown for a while in the corner and kept in the log of messages.
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use chrono::{DateTime, Local};
+use egui::{Color32, Context, Id, RichText};
+
+use super::app::Workbook;
+
+// The time the notifications are shown, the errors are shown longer.
+const NOTICE_SECONDS: i64 = 4;
+const ERROR_SECONDS: i64 = 10;
+
+// The number of notifications kept in the log.
+const LOG_LENGTH: usize = 200;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Level {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn icon(&self) -> &'static str {
+        match self {
+            Level::Info => "ℹ",
+            Level::Success => "✔",
+            Level::Warning => "⚠",
+            Level::Error => "❌",
+        }
+    }
+
+    fn color(&self, visuals: &egui::Visuals) -> Color32 {
+        match self {
+            Level::Info => visuals.text_color(),
+            Level::Success => Color32::from_rgb(0x3c, 0xa0, 0x4c),
+            Level::Warning => visuals.warn_fg_color,
+            Level::Error => visuals.error_fg_color,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Notification {
+    pub level: Level,
+    pub title: String,
+    pub message: String,
+    pub time: DateTime<Local>,
+    // Closed by the user before the time is over.
+    dismissed: bool,
+}
+
+impl Notification {
+    pub fn new(level: Level, title: &str, message: impl Into<String>) -> Self {
+        Notification {
+            level,
+            title: title.to_string(),
+            message: message.into(),
+            time: Local::now(),
+            dismissed: false,
+        }
+    }
+
+    pub fn info(title: &str, message: impl Into<String>) -> Self {
+        Self::new(Level::Info, title, message)
+    }
+
+    pub fn success(title: &str, message: impl Into<String>) -> Self {
+        Self::new(Level::Success, title, message)
+    }
+
+    pub fn warning(title: &str, message: impl Into<String>) -> Self {
+        Self::new(Level::Warning, title, message)
+    }
+
+    pub fn error(title: &str, message: impl Into<String>) -> Self {
+        Self::new(Level::Error, title, message)
+    }
+
+    fn is_shown(&self, now: DateTime<Local>) -> bool {
+        let seconds = match self.level {
+            Level::Error => ERROR_SECONDS,
+            _ => NOTICE_SECONDS,
+        };
+        !self.dismissed && now - self.time < chrono::Duration::seconds(seconds)
+    }
+}
+
+// Notify from the widgets which have no access to the workbook,
+// the notifications are collected into the log in the same frame.
+pub fn notify(ctx: &Context, notification: Notification) {
+    ctx.data_mut(|data| {
+        data.get_temp_mut_or_default::<Vec<Notification>>(queue_id())
+            .push(notification)
+    });
+}
+
+fn queue_id() -> Id {
+    Id::new("workbook_notifications")
+}
+
+pub struct Notifications {
+    pub log: Vec<Notification>,
+    // The notifications sent by the tasks reading and writing files.
+    pub channel: (Sender<Notification>, Receiver<Notification>),
+    pub log_open: bool,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            log: Vec::new(),
+            channel: channel(),
+            log_open: false,
+        }
+    }
+}
+
+impl Notifications {
+    pub fn push(&mut self, notification: Notification) {
+        self.log.push(notification);
+        if self.log.len() > LOG_LENGTH {
+            self.log.remove(0);
+        }
+    }
+
+    pub fn sender(&self) -> Sender<Notification> {
+        self.channel.0.clone()
+    }
+
+    // Collect the notifications sent by the tasks and the widgets.
+    fn collect(&mut self, ctx: &Context) {
+        while let Ok(notification) = self.channel.1.try_recv() {
+            self.push(notification);
+        }
+        let queued = ctx.data_mut(|data| data.remove_temp::<Vec<Notification>>(queue_id()));
+        for notification in queued.unwrap_or_default() {
+            self.push(notification);
+        }
+    }
+}
+
+impl Workbook {
+    pub fn show_notifications(&mut self, ctx: &Context) {
+        let notifications = &mut self.notifications;
+        notifications.collect(ctx);
+
+        let now = Local::now();
+        if notifications.log.iter().any(|n| n.is_shown(now)) {
+            egui::Area::new(Id::new("workbook_toasts"))
+                .anchor(egui::Align2::RIGHT_BOTTOM, [-12., -12.])
+                .order(egui::Order::Foreground)
+                .show(ctx, |ui| {
+                    ui.set_max_width(320.);
+                    for notification in notifications.log.iter_mut() {
+                        if !notification.is_shown(now) {
+                            continue;
+                        }
+                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.horizontal_top(|ui| {
+                                let color = notification.level.color(ui.visuals());
+                                ui.label(RichText::new(notification.level.icon()).color(color));
+                                ui.vertical(|ui| {
+                                    ui.label(RichText::new(&notification.title).strong());
+                                    if !notification.message.is_empty() {
+                                        ui.label(&notification.message);
+                                    }
+                                });
+                                if ui.small_button("×").on_hover_text("Dismiss").clicked() {
+                                    notification.dismissed = true;
+                                }
+                            });
+                        });
+                    }
+                });
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+
+        let mut open = notifications.log_open;
+        let mut clear = false;
+        egui::Window::new("Messages")
+            .open(&mut open)
+            .default_width(420.)
+            .resizable(true)
+            .show(ctx, |ui| {
+                if notifications.log.is_empty() {
+                    ui.label("…no messages");
+                }
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .max_height(420.)
+                    .show(ui, |ui| {
+                        // The latest first
+                        for notification in notifications.log.iter().rev() {
+                            ui.horizontal_top(|ui| {
+                                let color = notification.level.color(ui.visuals());
+                                ui.label(RichText::new(notification.level.icon()).color(color));
+                                ui.label(
+                                    RichText::new(notification.time.format("%H:%M:%S").to_string())
+                                        .small()
+                                        .weak(),
+                                );
+                                ui.vertical(|ui| {
+                                    ui.label(RichText::new(&notification.title).strong());
+                                    if !notification.message.is_empty() {
+                                        ui.label(&notification.message);
+                                    }
+                                });
+                            });
+                            ui.separator();
+                        }
+                    });
+                if ui
+                    .add_enabled(!notifications.log.is_empty(), egui::Button::new("Clear"))
+                    .clicked()
+                {
+                    clear = true;
+                }
+            });
+        if clear {
+            notifications.log.clear();
+        }
+        notifications.log_open = open;
+    }
+}
+
+// Test that the notifications are shown for a while and kept in the log.
+#[test]
+fn notifications_log() {
+    let ctx = Context::default();
+    let mut notifications = Notifications::default();
+    notifications.push(Notification::info("Opened", "Project.w"));
+    let _ = notifications
+        .sender()
+        .send(Notification::error("Save Failed", "Read-only file system"));
+    notify(
+        &ctx,
+        Notification::warning("Unresolved citation keys", "doe2020"),
+    );
+    notifications.collect(&ctx);
+
+    let titles: Vec<&str> = notifications
+        .log
+        .iter()
+        .map(|notification| notification.title.as_str())
+        .collect();
+    assert_eq!(
+        titles,
+        ["Opened", "Save Failed", "Unresolved citation keys"]
+    );
+
+    let later = Local::now() + chrono::Duration::seconds(NOTICE_SECONDS + 1);
+    let shown: Vec<bool> = notifications
+        .log
+        .iter()
+        .map(|notification| notification.is_shown(later))
+        .collect();
+    assert_eq!(shown, [false, true, false]);
+
+    for _ in 0..LOG_LENGTH {
+        notifications.push(Notification::info("Saved", ""));
+    }
+    assert_eq!(notifications.log.len(), LOG_LENGTH);
+    assert_eq!(notifications.log[0].title, "Saved");
+}
@@ -0,0 +1,328 @@
+// The library of the profiles of partners kept with the preferences,
+// so the participants are not typed again for every proposal.
+// The profile keeps the details of the participant, the contribution,
+// the budget and the costs belong to the project. The participants added from
+// a profile or saved to it are its copies, updated when it changes.
+
+use egui::{Context, RichText, TextEdit};
+use serde::{Deserialize, Serialize};
+
+use super::app::Workbook;
+use super::identity::Identity;
+use super::note::Notes;
+use super::notifications::Notification;
+use super::project::Project;
+use super::sections::team::Participant;
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct Profile {
+    pub participant: Participant,
+    // The participants of the projects added from the profile or saved to it.
+    pub copies: Vec<Identity>,
+}
+
+impl Profile {
+    fn title(&self) -> String {
+        let participant = &self.participant;
+        let mut title = participant.name.clone();
+        for detail in [&participant.affiliation, &participant.country] {
+            if !detail.trim().is_empty() {
+                title.push_str(&format!(", {}", detail.trim()));
+            }
+        }
+        title
+    }
+
+    fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty() || self.title().to_lowercase().contains(&query)
+    }
+}
+
+// The details of the participant kept in the profile.
+fn details(participant: &Participant) -> Participant {
+    Participant {
+        index_list: Participant::default().index_list,
+        contribution: Notes::default(),
+        budget: Notes::default(),
+        costs: Vec::new(),
+        visible: false,
+        ..participant.clone()
+    }
+}
+
+// The copy of the participant differs from the profile.
+fn differs(profile: &Participant, participant: &Participant) -> bool {
+    let profile = Participant {
+        id: participant.id,
+        ..details(profile)
+    };
+    serde_json::to_string(&profile).ok() != serde_json::to_string(&details(participant)).ok()
+}
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct Profiles {
+    pub profiles: Vec<Profile>,
+}
+
+impl Profiles {
+    // The profile of the participant, i.e. the one it is a copy of.
+    pub fn find(&self, participant: &Participant) -> Option<usize> {
+        self.profiles
+            .iter()
+            .position(|profile| profile.copies.contains(&participant.id))
+    }
+
+    // Save the details of the participant to its profile or a new one.
+    pub fn save(&mut self, participant: &Participant) {
+        match self.find(participant) {
+            Some(i) => self.profiles[i].participant = details(participant),
+            None => self.profiles.push(Profile {
+                participant: details(participant),
+                copies: vec![participant.id],
+            }),
+        }
+    }
+
+    // Add the participant of the profile to the project.
+    pub fn add_to(&mut self, i: usize, project: &mut Project) {
+        let profile = &mut self.profiles[i];
+        let mut participant = profile.participant.clone();
+        participant.renew_identities();
+        participant.visible = true;
+        profile.copies.push(participant.id);
+        project.team.participants.push(participant);
+    }
+
+    // Update the details of the copy from the profile, the contribution,
+    // the budget and the costs of the project are kept.
+    pub fn update_copy(&self, i: usize, participant: &mut Participant) {
+        let mut updated = Participant {
+            index_list: participant.index_list.clone(),
+            contribution: participant.contribution.clone(),
+            budget: participant.budget.clone(),
+            costs: participant.costs.clone(),
+            visible: participant.visible,
+            ..self.profiles[i].participant.clone()
+        };
+        for notes in [&mut updated.team, &mut updated.cv, &mut updated.resources] {
+            for note in notes.notes.iter_mut() {
+                note.id = Identity::new();
+            }
+        }
+        updated.id = participant.id;
+        *participant = updated;
+    }
+}
+
+impl Workbook {
+    pub fn show_profiles(&mut self, ctx: &Context) {
+        let mut open = self.windows.profiles_open;
+        let filter = &mut self.windows.profiles_filter;
+        let library = &mut self.profiles;
+        let project = &mut self.project;
+        let mut saved = Vec::new();
+        let mut added = None;
+        let mut updated = Vec::new();
+        let mut profile_to_delete = None;
+        egui::Window::new("Participant Profiles")
+            .open(&mut open)
+            .default_width(420.)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(
+                        "The partners kept apart from the workbooks \
+                        to add them to the projects.",
+                    )
+                    .small()
+                    .weak(),
+                );
+                ui.add(
+                    TextEdit::singleline(filter)
+                        .hint_text("Search")
+                        .desired_width(f32::INFINITY),
+                );
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .max_height(420.)
+                    .show(ui, |ui| {
+                        if library.profiles.is_empty() {
+                            ui.label("…no profiles");
+                        }
+                        for (i, profile) in library.profiles.iter().enumerate() {
+                            if !profile.matches(filter) {
+                                continue;
+                            }
+                            let copies: Vec<&Participant> = project
+                                .team
+                                .participants
+                                .iter()
+                                .filter(|participant| profile.copies.contains(&participant.id))
+                                .collect();
+                            ui.horizontal(|ui| {
+                                ui.label(profile.title())
+                                    .on_hover_text(&profile.participant.expertise);
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui
+                                            .button("🗑")
+                                            .on_hover_text(
+                                                "Delete the profile, the participants \
+                                                of the projects are kept",
+                                            )
+                                            .clicked()
+                                        {
+                                            profile_to_delete = Some(i);
+                                        }
+                                        if copies.is_empty()
+                                            && ui
+                                                .button("Add")
+                                                .on_hover_text("Add to the participants")
+                                                .clicked()
+                                        {
+                                            added = Some(i);
+                                        }
+                                    },
+                                );
+                            });
+                            for copy in copies {
+                                if !differs(&profile.participant, copy) {
+                                    ui.label(RichText::new("in the project").small().weak());
+                                    continue;
+                                }
+                                ui.horizontal(|ui| {
+                                    ui.label(
+                                        RichText::new("the participant differs")
+                                            .small()
+                                            .color(ui.visuals().warn_fg_color),
+                                    );
+                                    if ui
+                                        .small_button("Update")
+                                        .on_hover_text(
+                                            "Update the participant from the profile, \
+                                            the contribution, the budget and the costs are kept",
+                                        )
+                                        .clicked()
+                                    {
+                                        updated.push((i, copy.id));
+                                    }
+                                    if ui
+                                        .small_button("Save to Profile")
+                                        .on_hover_text("Update the profile from the participant")
+                                        .clicked()
+                                    {
+                                        saved.push(copy.id);
+                                    }
+                                });
+                            }
+                            ui.separator();
+                        }
+
+                        let others: Vec<&Participant> = project
+                            .team
+                            .participants
+                            .iter()
+                            .filter(|participant| library.find(participant).is_none())
+                            .collect();
+                        if others.is_empty() {
+                            return;
+                        }
+                        ui.label(RichText::new("Participants of the project").strong());
+                        for participant in others {
+                            ui.horizontal(|ui| {
+                                ui.label(&participant.name);
+                                if ui
+                                    .small_button("Save as Profile")
+                                    .on_hover_text("Keep the participant to add to other projects")
+                                    .clicked()
+                                {
+                                    saved.push(participant.id);
+                                }
+                            });
+                        }
+                    });
+            });
+
+        for id in saved {
+            let participant = project.team.participants.iter().find(|p| p.id == id);
+            if let Some(participant) = participant {
+                library.save(participant);
+                self.notifications
+                    .push(Notification::success("Profile Saved", &participant.name));
+            }
+        }
+        for (i, id) in updated {
+            let participant = project.team.participants.iter_mut().find(|p| p.id == id);
+            if let Some(participant) = participant {
+                library.update_copy(i, participant);
+            }
+        }
+        if let Some(i) = added {
+            library.add_to(i, project);
+        }
+        if let Some(i) = profile_to_delete {
+            library.profiles.remove(i);
+        }
+        self.windows.profiles_open = open;
+    }
+}
+
+// Test that the participants are saved to the profiles, added from them
+// and updated when the profile changes.
+#[test]
+fn participant_profiles() {
+    use super::note::Note;
+
+    let note = |text: &str| Note {
+        note: text.to_string(),
+        ..Note::new()
+    };
+    let mut project = Project::default();
+    project.team.participants.push(Participant {
+        name: "Jane Doe".to_string(),
+        affiliation: "University of Oslo".to_string(),
+        cv: Notes {
+            notes: vec![note("PhD in Oceanography.")],
+        },
+        contribution: Notes {
+            notes: vec![note("Field work.")],
+        },
+        ..Default::default()
+    });
+
+    let mut library = Profiles::default();
+    library.save(&project.team.participants[0]);
+    assert_eq!(library.profiles.len(), 1);
+    assert_eq!(library.find(&project.team.participants[0]), Some(0));
+    assert!(library.profiles[0]
+        .participant
+        .contribution
+        .notes
+        .is_empty());
+    assert_eq!(library.profiles[0].title(), "Jane Doe, University of Oslo");
+
+    // Another project
+    let mut other = Project::default();
+    library.add_to(0, &mut other);
+    let copy = &other.team.participants[0];
+    assert_eq!(copy.cv.notes[0].note, "PhD in Oceanography.");
+    assert_ne!(copy.id, project.team.participants[0].id);
+    assert_eq!(library.find(copy), Some(0));
+    assert!(!differs(&library.profiles[0].participant, copy));
+
+    library.profiles[0].participant.role = "Coordinator".to_string();
+    let participant = &mut project.team.participants[0];
+    assert!(differs(&library.profiles[0].participant, participant));
+    let id = participant.id;
+    library.update_copy(0, participant);
+    assert_eq!(participant.role, "Coordinator");
+    assert_eq!(participant.id, id);
+    assert_eq!(participant.contribution.notes[0].note, "Field work.");
+    assert!(!differs(&library.profiles[0].participant, participant));
+}
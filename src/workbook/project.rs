@@ -1,12 +1,14 @@
 use serde::{Deserialize, Serialize};
 
 use super::chapter::{Segment, Variety};
+use super::reference_formats::citation_style::CitationStyle;
 use super::sections::{
     attachments::Attachments, budget::Budget, funding::FundingOptions, idea::Idea,
     literature::Literature, methodology::Methodology, outcomes::Outcomes,
-    prelim_results::PrelimResults, resources::Resources, scope::Scope, scripting::Scripting,
-    team::Team, timeline::Timeline, working_name::ProjectTitle,
+    prelim_results::PrelimResults, references::Bibliography, resources::Resources, scope::Scope,
+    scripting::Scripting, team::Team, timeline::Timeline, working_name::ProjectTitle,
 };
+use crate::open_call::CustomerAccount;
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct Project {
@@ -25,6 +27,12 @@ pub struct Project {
     // Note: This vector allows to order the sections of Workbook as required.
     pub index_list: Vec<Segment>,
 
+    // The style of bibliography entries in the preview and exports.
+    pub citation_style: CitationStyle,
+
+    // The references of the Key References and the Literature Survey.
+    pub bibliography: Bibliography,
+
     // Workbook section types.
     pub attachments: Attachments, // TODO
     pub budget: Budget, // Provide a detailed budget estimate, including anticipated expenses for research materials, equipment, participant compensation, travel, and other relevant costs. Justify the budget based on the project's scope and requirements.
@@ -48,6 +56,8 @@ impl Default for Project {
             record: String::new(),
             owner: ProjectOwner::default(),
             resolution: 1,
+            citation_style: CitationStyle::default(),
+            bibliography: Bibliography::default(),
             index_list: vec![
                 Segment {
                     variety: Variety::WorkingName,
@@ -124,20 +134,42 @@ impl Default for Project {
     }
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)] // for unit tests
+impl Project {
+    // The new project of the owner.
+    pub fn new(owner: ProjectOwner) -> Self {
+        Self {
+            owner,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq)] // for unit tests
 pub struct ProjectOwner {
     pub email: String,
     pub account: String,
+    pub name: String,
 }
 
-impl Default for ProjectOwner {
-    fn default() -> Self {
-        let config = crate::open_call::Settings::default();
-        let customer = config.customer;
-
+// The owner according to the settings of the user.
+impl From<&CustomerAccount> for ProjectOwner {
+    fn from(customer: &CustomerAccount) -> Self {
         Self {
-            email: customer.email,
-            account: customer.id,
+            email: customer.email.clone(),
+            account: customer.id.clone(),
+            name: customer.name.clone(),
+        }
+    }
+}
+
+impl ProjectOwner {
+    // The name and email as far as they are known.
+    pub fn signature(&self) -> String {
+        match (self.name.trim(), self.email.trim()) {
+            ("", "") => "unknown".to_string(),
+            (name, "") => name.to_string(),
+            ("", email) => email.to_string(),
+            (name, email) => format!("{} <{}>", name, email),
         }
     }
 }
@@ -0,0 +1,7 @@
+pub mod citation_style;
+pub mod citations;
+pub mod exchange;
+
+mod csl_json;
+mod pdf;
+mod ris;
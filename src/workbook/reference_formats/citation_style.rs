@@ -0,0 +1,623 @@
+// Bibliography entries formatted in the citation style chosen for the project.
+// An entry is a sequence of fragments, so that the preview and the exported
+// documents can emphasise titles and link sources in their own way.
+
+use serde::{Deserialize, Serialize};
+
+use crate::workbook::sections::references::{Reference, SourceKind};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq)]
+pub enum CitationStyle {
+    // American Psychological Association, author-date.
+    #[default]
+    Apa,
+    // Author-date.
+    Harvard,
+    // Institute of Electrical and Electronics Engineers, numbered.
+    Ieee,
+    // Numbered.
+    Vancouver,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fragment {
+    Text(String),
+    Emphasis(String),
+    Link(String),
+}
+
+impl CitationStyle {
+    pub const ALL: [CitationStyle; 4] = [
+        CitationStyle::Apa,
+        CitationStyle::Harvard,
+        CitationStyle::Ieee,
+        CitationStyle::Vancouver,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CitationStyle::Apa => "APA",
+            CitationStyle::Harvard => "Harvard",
+            CitationStyle::Ieee => "IEEE",
+            CitationStyle::Vancouver => "Vancouver",
+        }
+    }
+
+    // Numbered styles keep the order of the list,
+    // author-date styles sort the list by authors and year.
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, CitationStyle::Ieee | CitationStyle::Vancouver)
+    }
+
+    // The label in front of the entry number `number` in the bibliography.
+    pub fn label(&self, number: usize) -> String {
+        match self {
+            CitationStyle::Ieee => format!("[{}]", number),
+            CitationStyle::Vancouver => format!("{}.", number),
+            _ => String::new(),
+        }
+    }
+
+    // Author-date styles sort the bibliography by authors and year.
+    pub fn sort_key(reference: &Reference) -> (String, String) {
+        let first_author = reference
+            .metadata
+            .author_list()
+            .first()
+            .map(|author| author.to_lowercase())
+            .unwrap_or_else(|| reference.title.to_lowercase());
+        (first_author, reference.metadata.year.clone())
+    }
+
+    // The author and year of the source cited in text, e.g. "Smith & Doe, 2021".
+    pub fn author_date(&self, reference: &Reference) -> String {
+        let families: Vec<String> = names(reference)
+            .iter()
+            .map(|name| name.family.to_string())
+            .collect();
+        let authors = match (self, families.len()) {
+            (_, 0) => reference.title.clone(),
+            (CitationStyle::Harvard, 1..=3) => join_names(&families, " and ", " and "),
+            (_, 1..=2) => join_names(&families, " & ", " & "),
+            _ => format!("{} et al.", families[0]),
+        };
+        let year = match (self, reference.metadata.year.is_empty()) {
+            (CitationStyle::Harvard, true) => "no date",
+            (_, true) => "n.d.",
+            (_, false) => reference.metadata.year.as_str(),
+        };
+        format!("{}, {}", authors, year)
+    }
+
+    // The in-text citation of one or several sources given by their labels,
+    // i.e. numbers or authors and years, e.g. "[1], [3]" or "(Smith, 2021; Doe, 2020)".
+    pub fn in_text(&self, labels: &[String]) -> String {
+        match self {
+            CitationStyle::Ieee => labels
+                .iter()
+                .map(|label| format!("[{}]", label))
+                .collect::<Vec<String>>()
+                .join(", "),
+            CitationStyle::Vancouver => format!("({})", labels.join(", ")),
+            _ => format!("({})", labels.join("; ")),
+        }
+    }
+
+    pub fn format(&self, reference: &Reference) -> Vec<Fragment> {
+        let mut entry = Entry::default();
+        match self {
+            CitationStyle::Apa => format_apa(&mut entry, reference),
+            CitationStyle::Harvard => format_harvard(&mut entry, reference),
+            CitationStyle::Ieee => format_ieee(&mut entry, reference),
+            CitationStyle::Vancouver => format_vancouver(&mut entry, reference),
+        }
+        entry.fragments
+    }
+}
+
+#[derive(Default)]
+struct Entry {
+    fragments: Vec<Fragment>,
+}
+
+impl Entry {
+    fn text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if let Some(Fragment::Text(last)) = self.fragments.last_mut() {
+            last.push_str(text);
+        } else {
+            self.fragments.push(Fragment::Text(text.to_string()));
+        }
+    }
+
+    fn emphasis(&mut self, text: &str) {
+        if !text.is_empty() {
+            self.fragments.push(Fragment::Emphasis(text.to_string()));
+        }
+    }
+
+    fn link(&mut self, url: &str) {
+        if !url.is_empty() {
+            self.fragments.push(Fragment::Link(url.to_string()));
+        }
+    }
+
+    // Close the sentence unless it already ends with a punctuation mark.
+    fn stop(&mut self) {
+        let ends_with_mark = match self.fragments.last() {
+            Some(Fragment::Text(text) | Fragment::Emphasis(text)) => {
+                text.trim_end().ends_with(['.', '?', '!'])
+            }
+            Some(Fragment::Link(_)) | None => true,
+        };
+        if !ends_with_mark {
+            self.text(".");
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.fragments.is_empty()
+    }
+}
+
+// "Family, Given" or the name of an organisation.
+struct Name<'a> {
+    family: &'a str,
+    given: &'a str,
+}
+
+impl Name<'_> {
+    // "John Paul" -> ["J", "P"]
+    fn initials(&self) -> Vec<String> {
+        self.given
+            .split_whitespace()
+            .filter_map(|part| part.chars().next())
+            .map(|c| c.to_string())
+            .collect()
+    }
+
+    // Smith, J. P.
+    fn family_first(&self, separator: &str) -> String {
+        let initials = self.initials();
+        if initials.is_empty() {
+            self.family.to_string()
+        } else {
+            format!(
+                "{}, {}.",
+                self.family,
+                initials.join(&format!(".{}", separator))
+            )
+        }
+    }
+
+    // J. P. Smith
+    fn initials_first(&self) -> String {
+        let initials = self.initials();
+        if initials.is_empty() {
+            self.family.to_string()
+        } else {
+            format!("{}. {}", initials.join(". "), self.family)
+        }
+    }
+
+    // Smith JP
+    fn compact(&self) -> String {
+        let initials = self.initials();
+        if initials.is_empty() {
+            self.family.to_string()
+        } else {
+            format!("{} {}", self.family, initials.concat())
+        }
+    }
+}
+
+fn names(reference: &Reference) -> Vec<Name<'_>> {
+    reference
+        .metadata
+        .author_list()
+        .into_iter()
+        .map(|author| match author.split_once(',') {
+            Some((family, given)) => Name {
+                family: family.trim(),
+                given: given.trim(),
+            },
+            None => Name {
+                family: author,
+                given: "",
+            },
+        })
+        .collect()
+}
+
+// "A and B", "A, B, and C"
+fn join_names(names: &[String], pair: &str, last: &str) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.clone(),
+        [first, second] => format!("{}{}{}", first, pair, second),
+        [rest @ .., final_name] => format!("{}{}{}", rest.join(", "), last, final_name),
+    }
+}
+
+fn doi_link(reference: &Reference) -> String {
+    if reference.metadata.doi.is_empty() {
+        reference.hyperlink.clone()
+    } else {
+        format!("https://doi.org/{}", reference.metadata.doi)
+    }
+}
+
+// The title of a standalone work is emphasised.
+fn title(entry: &mut Entry, reference: &Reference) {
+    if reference.metadata.kind.is_part() {
+        entry.text(&reference.title);
+    } else {
+        entry.emphasis(&reference.title);
+    }
+}
+
+// Smith, J., & Doe, A. (2021). Title. *Journal*, *12*(3), 45–67. https://doi.org/…
+fn format_apa(entry: &mut Entry, reference: &Reference) {
+    let data = &reference.metadata;
+    let year = if data.year.is_empty() {
+        "n.d."
+    } else {
+        data.year.as_str()
+    };
+
+    let authors: Vec<String> = names(reference)
+        .iter()
+        .map(|name| name.family_first(" "))
+        .collect();
+    if authors.is_empty() {
+        title(entry, reference);
+        entry.stop();
+        entry.text(&format!(" ({}). ", year));
+    } else {
+        entry.text(&join_names(&authors, ", & ", ", & "));
+        entry.text(&format!(" ({}). ", year));
+        title(entry, reference);
+        entry.stop();
+    }
+
+    match data.kind {
+        SourceKind::Article => {
+            if !data.container.is_empty() {
+                entry.text(" ");
+                entry.emphasis(&data.container);
+                if !data.volume.is_empty() {
+                    entry.text(", ");
+                    entry.emphasis(&data.volume);
+                }
+                if !data.issue.is_empty() {
+                    entry.text(&format!("({})", data.issue));
+                }
+                if !data.pages.is_empty() {
+                    entry.text(&format!(", {}", data.pages));
+                }
+                entry.stop();
+            }
+        }
+        SourceKind::Chapter | SourceKind::Conference => {
+            if !data.container.is_empty() {
+                entry.text(" In ");
+                entry.emphasis(&data.container);
+                if !data.pages.is_empty() {
+                    entry.text(&format!(" (pp. {})", data.pages));
+                }
+                entry.stop();
+            }
+            if !data.publisher.is_empty() {
+                entry.text(&format!(" {}", data.publisher));
+                entry.stop();
+            }
+        }
+        _ => {
+            if !data.publisher.is_empty() {
+                entry.text(&format!(" {}", data.publisher));
+                entry.stop();
+            }
+        }
+    }
+
+    let link = doi_link(reference);
+    if !link.is_empty() {
+        entry.text(" ");
+        entry.link(&link);
+    }
+}
+
+// Smith, J. and Doe, A. (2021) Title. *Journal*, 12(3), pp. 45–67. Available at: …
+fn format_harvard(entry: &mut Entry, reference: &Reference) {
+    let data = &reference.metadata;
+    let year = if data.year.is_empty() {
+        "no date"
+    } else {
+        data.year.as_str()
+    };
+
+    let names = names(reference);
+    let authors: Vec<String> = names.iter().map(|name| name.family_first("")).collect();
+    if authors.len() > 3 {
+        entry.text(&format!("{} et al.", authors[0]));
+    } else {
+        entry.text(&join_names(&authors, " and ", " and "));
+    }
+    if !entry.is_empty() {
+        entry.text(" ");
+    }
+    entry.text(&format!("({}) ", year));
+    title(entry, reference);
+    entry.stop();
+
+    match data.kind {
+        SourceKind::Article => {
+            if !data.container.is_empty() {
+                entry.text(" ");
+                entry.emphasis(&data.container);
+                let mut numbering = data.volume.clone();
+                if !data.issue.is_empty() {
+                    numbering.push_str(&format!("({})", data.issue));
+                }
+                if !numbering.is_empty() {
+                    entry.text(&format!(", {}", numbering));
+                }
+                if !data.pages.is_empty() {
+                    entry.text(&format!(", pp. {}", data.pages));
+                }
+                entry.stop();
+            }
+        }
+        SourceKind::Chapter | SourceKind::Conference => {
+            if !data.container.is_empty() {
+                entry.text(" In: ");
+                entry.emphasis(&data.container);
+                entry.stop();
+            }
+            if !data.publisher.is_empty() {
+                entry.text(&format!(" {}", data.publisher));
+            }
+            if !data.pages.is_empty() {
+                entry.text(&format!(", pp. {}", data.pages));
+            }
+            entry.stop();
+        }
+        _ => {
+            if !data.publisher.is_empty() {
+                entry.text(&format!(" {}", data.publisher));
+                entry.stop();
+            }
+        }
+    }
+
+    let link = doi_link(reference);
+    if !link.is_empty() {
+        entry.text(" Available at: ");
+        entry.link(&link);
+    }
+}
+
+// J. Smith and A. Doe, “Title,” *Journal*, vol. 12, no. 3, pp. 45–67, 2021, doi: …
+fn format_ieee(entry: &mut Entry, reference: &Reference) {
+    let data = &reference.metadata;
+
+    let names = names(reference);
+    let authors: Vec<String> = names.iter().map(|name| name.initials_first()).collect();
+    if authors.len() > 6 {
+        entry.text(&format!("{} et al.", authors[0]));
+    } else {
+        entry.text(&join_names(&authors, " and ", ", and "));
+    }
+    if !entry.is_empty() {
+        entry.text(", ");
+    }
+
+    let mut details: Vec<String> = Vec::new();
+    if data.kind.is_part() {
+        entry.text(&format!("“{},” ", reference.title));
+        if !data.container.is_empty() {
+            if data.kind != SourceKind::Article {
+                entry.text("in ");
+            }
+            entry.emphasis(&data.container);
+        }
+        if !data.publisher.is_empty() && data.kind != SourceKind::Article {
+            details.push(data.publisher.clone());
+        }
+        if !data.volume.is_empty() {
+            details.push(format!("vol. {}", data.volume));
+        }
+        if !data.issue.is_empty() {
+            details.push(format!("no. {}", data.issue));
+        }
+        if !data.pages.is_empty() {
+            details.push(format!("pp. {}", data.pages));
+        }
+        if !data.year.is_empty() {
+            details.push(data.year.clone());
+        }
+    } else {
+        entry.emphasis(&reference.title);
+        entry.stop();
+        if !data.publisher.is_empty() {
+            entry.text(&format!(" {}", data.publisher));
+        }
+        if !data.year.is_empty() {
+            if data.publisher.is_empty() {
+                entry.text(&format!(" {}", data.year));
+            } else {
+                entry.text(&format!(", {}", data.year));
+            }
+        }
+    }
+    for detail in details {
+        entry.text(&format!(", {}", detail));
+    }
+
+    if data.doi.is_empty() {
+        entry.stop();
+        if !reference.hyperlink.is_empty() {
+            entry.text(" [Online]. Available: ");
+            entry.link(&reference.hyperlink);
+        }
+    } else {
+        entry.text(&format!(", doi: {}", data.doi));
+        entry.stop();
+    }
+}
+
+// Smith J, Doe A. Title. Journal. 2021;12(3):45–67. doi:…
+fn format_vancouver(entry: &mut Entry, reference: &Reference) {
+    let data = &reference.metadata;
+
+    let names = names(reference);
+    let mut authors: Vec<String> = names.iter().take(6).map(|name| name.compact()).collect();
+    if names.len() > 6 {
+        authors.push("et al".to_string());
+    }
+    if !authors.is_empty() {
+        entry.text(&authors.join(", "));
+        entry.stop();
+        entry.text(" ");
+    }
+    entry.text(&reference.title);
+    entry.stop();
+
+    match data.kind {
+        SourceKind::Article => {
+            if !data.container.is_empty() {
+                entry.text(&format!(" {}", data.container));
+                entry.stop();
+            }
+            let mut numbering = data.year.clone();
+            if !data.volume.is_empty() {
+                numbering.push_str(&format!(";{}", data.volume));
+            }
+            if !data.issue.is_empty() {
+                numbering.push_str(&format!("({})", data.issue));
+            }
+            if !data.pages.is_empty() {
+                numbering.push_str(&format!(":{}", data.pages));
+            }
+            if !numbering.is_empty() {
+                entry.text(&format!(" {}", numbering));
+                entry.stop();
+            }
+        }
+        _ => {
+            if data.kind.is_part() && !data.container.is_empty() {
+                entry.text(&format!(" In: {}", data.container));
+                entry.stop();
+            }
+            let published = [data.publisher.as_str(), data.year.as_str()]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<&str>>()
+                .join("; ");
+            if !published.is_empty() {
+                entry.text(&format!(" {}", published));
+                entry.stop();
+            }
+            if data.kind.is_part() && !data.pages.is_empty() {
+                entry.text(&format!(" p. {}.", data.pages));
+            }
+        }
+    }
+
+    if !data.doi.is_empty() {
+        entry.text(&format!(" doi:{}", data.doi));
+    } else if !reference.hyperlink.is_empty() {
+        entry.text(" Available from: ");
+        entry.link(&reference.hyperlink);
+    }
+}
+
+// Test that a journal article is formatted according to each citation style.
+#[test]
+fn citation_styles() {
+    use crate::workbook::sections::references::ReferenceMetadata;
+
+    let plain_text = |fragments: Vec<Fragment>| -> String {
+        fragments
+            .into_iter()
+            .map(|fragment| match fragment {
+                Fragment::Text(text) | Fragment::Emphasis(text) | Fragment::Link(text) => text,
+            })
+            .collect()
+    };
+
+    let article = Reference {
+        title: "A study of examples".to_string(),
+        visible: true,
+        metadata: ReferenceMetadata {
+            kind: SourceKind::Article,
+            authors: "Smith, John Paul; Doe, Ann".to_string(),
+            year: "2021".to_string(),
+            container: "Journal of Examples".to_string(),
+            volume: "12".to_string(),
+            issue: "3".to_string(),
+            pages: "45–67".to_string(),
+            doi: "10.1000/xyz123".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let formatted = |style: CitationStyle| plain_text(style.format(&article));
+    assert_eq!(
+        formatted(CitationStyle::Apa),
+        "Smith, J. P., & Doe, A. (2021). A study of examples. \
+        Journal of Examples, 12(3), 45–67. https://doi.org/10.1000/xyz123"
+    );
+    assert_eq!(
+        formatted(CitationStyle::Harvard),
+        "Smith, J.P. and Doe, A. (2021) A study of examples. \
+        Journal of Examples, 12(3), pp. 45–67. \
+        Available at: https://doi.org/10.1000/xyz123"
+    );
+    assert_eq!(
+        formatted(CitationStyle::Ieee),
+        "J. P. Smith and A. Doe, “A study of examples,” Journal of Examples, \
+        vol. 12, no. 3, pp. 45–67, 2021, doi: 10.1000/xyz123."
+    );
+    assert_eq!(
+        formatted(CitationStyle::Vancouver),
+        "Smith JP, Doe A. A study of examples. Journal of Examples. \
+        2021;12(3):45–67. doi:10.1000/xyz123"
+    );
+    assert_eq!(
+        CitationStyle::Apa.format(&article)[1],
+        Fragment::Emphasis("Journal of Examples".to_string())
+    );
+
+    let book = Reference {
+        title: "Examples".to_string(),
+        visible: true,
+        metadata: ReferenceMetadata {
+            kind: SourceKind::Book,
+            authors: "Example Consortium".to_string(),
+            publisher: "Example Press".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert_eq!(
+        plain_text(CitationStyle::Apa.format(&book)),
+        "Example Consortium (n.d.). Examples. Example Press."
+    );
+
+    // In-text citations.
+    assert_eq!(
+        CitationStyle::Apa.author_date(&article),
+        "Smith & Doe, 2021".to_string()
+    );
+    assert_eq!(
+        CitationStyle::Harvard.author_date(&book),
+        "Example Consortium, no date".to_string()
+    );
+    let labels = ["1".to_string(), "3".to_string()];
+    assert_eq!(CitationStyle::Ieee.in_text(&labels), "[1], [3]");
+    assert_eq!(CitationStyle::Vancouver.in_text(&labels), "(1, 3)");
+}
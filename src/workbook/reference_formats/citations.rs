@@ -0,0 +1,333 @@
+// In-text citations, `[@smith2021]` or `[@smith2021; @doe2020]`, written
+// in notes and resolved to the references of the project, i.e. the Key
+// References and the sources of the Literature Survey.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use egui::{Context, Id};
+
+use super::citation_style::CitationStyle;
+use super::exchange::ReferenceList;
+use crate::workbook::project::Project;
+use crate::workbook::sections::references::Reference;
+
+#[derive(Clone)]
+struct Source {
+    key_reference: bool,
+    key: String,
+    reference: Reference,
+}
+
+// The visible references of the project with their citation keys,
+// numbered in the order of the first citation.
+#[derive(Clone, Default)]
+pub struct Citations {
+    pub style: CitationStyle,
+    sources: Vec<Source>,
+    keys: HashMap<String, usize>,
+    // The indices of `sources` in the order of the first citation.
+    order: Vec<usize>,
+    // The cited keys which don't match any reference.
+    pub unresolved: Vec<String>,
+    // The keys shared by several references (the first one is cited).
+    pub duplicates: Vec<String>,
+}
+
+// The text of a note split into the plain text and the citations.
+#[derive(Debug, PartialEq)]
+pub enum Cited {
+    Text(String),
+    Citation(String),
+    // The citation as it is written, at least one key is unresolved.
+    Unresolved(String),
+}
+
+enum Part<'a> {
+    Text(&'a str),
+    Keys(Vec<&'a str>, &'a str),
+}
+
+impl Citations {
+    // The citations are shared with the preview of notes in the current frame.
+    pub fn share(ctx: &Context, citations: Arc<Citations>) {
+        ctx.data_mut(|data| data.insert_temp(Id::new("workbook_citations"), citations));
+    }
+
+    pub fn shared(ctx: &Context) -> Option<Arc<Citations>> {
+        ctx.data(|data| data.get_temp(Id::new("workbook_citations")))
+    }
+
+    pub fn new(project: &Project) -> Self {
+        let mut citations = Citations {
+            style: project.citation_style,
+            ..Default::default()
+        };
+        let bibliography = &project.bibliography;
+        for reference in bibliography.references.iter() {
+            let key_reference = bibliography.is_key_reference(reference.id);
+            if !reference.visible && !key_reference {
+                continue;
+            }
+            let key = reference.citation_key();
+            if citations.keys.contains_key(&key) {
+                if !citations.duplicates.contains(&key) {
+                    citations.duplicates.push(key.clone());
+                }
+            } else if !key.is_empty() {
+                citations.keys.insert(key.clone(), citations.sources.len());
+            }
+            citations.sources.push(Source {
+                key_reference,
+                key,
+                reference: reference.clone(),
+            });
+        }
+        citations
+    }
+
+    // Resolve the citations in the text and record the order of citation.
+    pub fn resolve(&mut self, text: &str) -> String {
+        self.cite(text);
+        self.render(text)
+            .into_iter()
+            .map(|cited| match cited {
+                Cited::Text(text) | Cited::Citation(text) | Cited::Unresolved(text) => text,
+            })
+            .collect()
+    }
+
+    // Record the order of citation only, the text is not resolved.
+    pub fn cite(&mut self, text: &str) {
+        for part in split(text) {
+            if let Part::Keys(keys, _) = part {
+                for key in keys {
+                    match self.keys.get(key) {
+                        Some(&i) => {
+                            if !self.order.contains(&i) {
+                                self.order.push(i);
+                            }
+                        }
+                        None => {
+                            if !self.unresolved.iter().any(|unresolved| unresolved == key) {
+                                self.unresolved.push(key.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn render(&self, text: &str) -> Vec<Cited> {
+        split(text)
+            .into_iter()
+            .map(|part| match part {
+                Part::Text(text) => Cited::Text(text.to_string()),
+                Part::Keys(keys, written) => {
+                    let sources: Option<Vec<usize>> = keys
+                        .iter()
+                        .map(|key| self.keys.get(*key).copied())
+                        .collect();
+                    match sources {
+                        Some(mut sources) => {
+                            if self.style.is_numeric() {
+                                sources.sort_by_key(|&i| self.number(i));
+                            }
+                            let labels: Vec<String> = sources
+                                .iter()
+                                .map(|&i| {
+                                    if self.style.is_numeric() {
+                                        self.number(i).to_string()
+                                    } else {
+                                        self.style.author_date(&self.sources[i].reference)
+                                    }
+                                })
+                                .collect();
+                            Cited::Citation(self.style.in_text(&labels))
+                        }
+                        None => Cited::Unresolved(written.to_string()),
+                    }
+                }
+            })
+            .collect()
+    }
+
+    // The cited sources are numbered first, then the others in the order of the lists.
+    fn number(&self, source: usize) -> usize {
+        match self.order.iter().position(|&i| i == source) {
+            Some(position) => position + 1,
+            None => self.order.len() + (0..source).filter(|i| !self.order.contains(i)).count() + 1,
+        }
+    }
+
+    // The numbered entries in the order of the bibliography.
+    fn entries(&self, sources: Vec<usize>) -> Vec<(usize, &Reference)> {
+        let mut entries: Vec<(usize, &Reference)> = sources
+            .into_iter()
+            .map(|i| (self.number(i), &self.sources[i].reference))
+            .collect();
+        if self.style.is_numeric() {
+            entries.sort_by_key(|(number, _)| *number);
+        } else {
+            entries.sort_by_cached_key(|(_, reference)| CitationStyle::sort_key(reference));
+        }
+        entries
+    }
+
+    // The references of the list.
+    pub fn list(&self, list: ReferenceList) -> Vec<(usize, &Reference)> {
+        self.entries(
+            (0..self.sources.len())
+                .filter(|&i| match list {
+                    ReferenceList::KeyReferences => self.sources[i].key_reference,
+                    ReferenceList::Literature => self.sources[i].reference.visible,
+                })
+                .collect(),
+        )
+    }
+
+    // The bibliography of the cited references.
+    pub fn cited(&self) -> Vec<(usize, &Reference)> {
+        self.entries(self.order.clone())
+    }
+
+    // The reference is listed but not cited, while other references are.
+    pub fn not_cited(&self, reference: &Reference) -> bool {
+        !self.order.is_empty()
+            && self
+                .sources
+                .iter()
+                .position(|source| source.reference.id == reference.id)
+                .is_some_and(|i| !self.order.contains(&i))
+    }
+
+    // The keys of the references which are not cited, if any reference is cited.
+    pub fn unused(&self) -> Vec<&str> {
+        if self.order.is_empty() {
+            return Vec::new();
+        }
+        (0..self.sources.len())
+            .filter(|i| !self.order.contains(i))
+            .map(|i| self.sources[i].key.as_str())
+            .filter(|key| !key.is_empty())
+            .collect()
+    }
+
+    // The warnings about the citation keys with the keys, if any.
+    pub fn warnings(&self) -> Vec<(String, String)> {
+        [
+            ("Unresolved citation keys", self.unresolved.join(", ")),
+            (
+                "Citation keys of several references",
+                self.duplicates.join(", "),
+            ),
+            ("References not cited", self.unused().join(", ")),
+        ]
+        .into_iter()
+        .filter(|(_, keys)| !keys.is_empty())
+        .map(|(warning, keys)| (warning.to_string(), keys))
+        .collect()
+    }
+}
+
+fn split(text: &str) -> Vec<Part<'_>> {
+    let mut parts = Vec::new();
+    // The start of the plain text and of the search for the next citation.
+    let mut plain = 0;
+    let mut from = 0;
+    while let Some(start) = text[from..].find("[@").map(|start| from + start) {
+        let Some(end) = text[start..].find(']').map(|length| start + length) else {
+            break;
+        };
+        let keys: Option<Vec<&str>> = text[start + 1..end]
+            .split(';')
+            .map(|key| key.trim().strip_prefix('@').filter(|key| is_key(key)))
+            .collect();
+        match keys {
+            Some(keys) => {
+                if plain < start {
+                    parts.push(Part::Text(&text[plain..start]));
+                }
+                parts.push(Part::Keys(keys, &text[start..=end]));
+                plain = end + 1;
+                from = end + 1;
+            }
+            // Not a citation, e.g. "[@ home]".
+            None => from = start + 1,
+        }
+    }
+    if plain < text.len() {
+        parts.push(Part::Text(&text[plain..]));
+    }
+    parts
+}
+
+// The keys are made of letters, digits and `_-:.`, e.g. "smith2021" or "doe:2020a".
+pub fn is_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | ':' | '.'))
+}
+
+// Test that the citation keys are resolved in the order of citation.
+#[test]
+fn citation_keys() {
+    use crate::workbook::sections::references::{Bibliography, ReferenceMetadata};
+
+    let reference = |title: &str, authors: &str, year: &str| Reference {
+        title: title.to_string(),
+        visible: true,
+        metadata: ReferenceMetadata {
+            authors: authors.to_string(),
+            year: year.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let key = Reference {
+        visible: false,
+        ..reference("Key", "Smith, John", "2021")
+    };
+    let project = Project {
+        bibliography: Bibliography {
+            key_references: vec![key.id],
+            references: vec![
+                key,
+                reference("First", "Doe, Ann", "2020"),
+                reference("Second", "Lee, Kim; Park, Jo", "2019"),
+            ],
+        },
+        citation_style: CitationStyle::Ieee,
+        ..Default::default()
+    };
+
+    let mut citations = Citations::new(&project);
+    assert_eq!(
+        citations.resolve("As shown [@lee2019; @doe2020], see [@doe2020] and [@nobody]."),
+        "As shown [1], [2], see [2] and [@nobody].".to_string()
+    );
+    assert_eq!(citations.unresolved, vec!["nobody".to_string()]);
+    assert_eq!(citations.unused(), vec!["smith2021"]);
+    assert!(citations.not_cited(&project.bibliography.references[0]));
+    assert!(!citations.not_cited(&project.bibliography.references[1]));
+    assert_eq!(citations.cited()[0].1.title, "Second");
+    // The uncited reference is numbered after the cited ones.
+    assert_eq!(citations.list(ReferenceList::KeyReferences)[0].0, 3);
+    assert_eq!(
+        citations.render("[@ not a key] [@smith2021]"),
+        vec![
+            Cited::Text("[@ not a key] ".to_string()),
+            Cited::Citation("[3]".to_string())
+        ]
+    );
+
+    citations.style = CitationStyle::Apa;
+    assert_eq!(
+        citations.resolve("[@lee2019]"),
+        "(Lee & Park, 2019)".to_string()
+    );
+    // Author-date styles list the references alphabetically.
+    assert_eq!(citations.cited()[0].1.title, "First");
+}
@@ -0,0 +1,229 @@
+// Citation Style Language JSON (CSL-JSON), the array of items with
+// "title", "author", "issued", "container-title", "DOI", "URL", etc.
+
+use serde_json::{json, Map, Value};
+
+use super::citations::is_key;
+use super::exchange::SourceRecord;
+use crate::workbook::sections::references::SourceKind;
+
+pub fn parse(text: &str) -> Result<Vec<SourceRecord>, serde_json::Error> {
+    let items = match serde_json::from_str(text)? {
+        Value::Array(items) => items,
+        // A single item is accepted, too.
+        item => vec![item],
+    };
+
+    Ok(items
+        .iter()
+        .filter(|item| item.is_object())
+        .map(|item| SourceRecord {
+            key: key(item),
+            kind: kind_from_type(&text_field(item, "type")),
+            title: text_field(item, "title"),
+            authors: authors(item),
+            year: year(item),
+            container: text_field(item, "container-title"),
+            volume: text_field(item, "volume"),
+            issue: text_field(item, "issue"),
+            pages: text_field(item, "page"),
+            publisher: text_field(item, "publisher"),
+            doi: text_field(item, "DOI"),
+            url: text_field(item, "URL"),
+            abstract_text: text_field(item, "abstract"),
+            notes: text_field(item, "note")
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.trim().to_string())
+                .collect(),
+        })
+        .collect())
+}
+
+// The citation key, or the identifier of the item if it can be cited.
+fn key(item: &Value) -> String {
+    let key = text_field(item, "citation-key");
+    if !key.is_empty() {
+        return key;
+    }
+    let id = text_field(item, "id");
+    if is_key(&id) {
+        id
+    } else {
+        String::new()
+    }
+}
+
+fn kind_from_type(value: &str) -> SourceKind {
+    match value {
+        "article" | "article-journal" | "article-magazine" | "article-newspaper" => {
+            SourceKind::Article
+        }
+        "book" => SourceKind::Book,
+        "chapter" => SourceKind::Chapter,
+        "paper-conference" => SourceKind::Conference,
+        "report" => SourceKind::Report,
+        "thesis" => SourceKind::Thesis,
+        "webpage" | "post" | "post-weblog" => SourceKind::Web,
+        _ => SourceKind::Other,
+    }
+}
+
+fn type_of_kind(kind: SourceKind) -> &'static str {
+    match kind {
+        SourceKind::Article => "article-journal",
+        SourceKind::Book => "book",
+        SourceKind::Chapter => "chapter",
+        SourceKind::Conference => "paper-conference",
+        SourceKind::Report => "report",
+        SourceKind::Thesis => "thesis",
+        SourceKind::Web => "webpage",
+        SourceKind::Other => "document",
+    }
+}
+
+// Numbers are allowed for "volume", "issue", "page", etc.
+fn text_field(item: &Value, key: &str) -> String {
+    item.get(key).map(text_value).unwrap_or_default()
+}
+
+fn text_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.trim().to_string(),
+        Value::Number(number) => number.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn authors(item: &Value) -> Vec<String> {
+    let Some(Value::Array(names)) = item.get("author") else {
+        return Vec::new();
+    };
+
+    names
+        .iter()
+        .map(|name| {
+            let literal = text_field(name, "literal");
+            if literal.is_empty() {
+                let family = text_field(name, "family");
+                let given = text_field(name, "given");
+                if given.is_empty() {
+                    family
+                } else {
+                    format!("{}, {}", family, given)
+                }
+            } else {
+                literal
+            }
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+// "issued": {"date-parts": [[2021, 3, 15]]} or {"raw": "2021"}
+fn year(item: &Value) -> String {
+    let Some(issued) = item.get("issued") else {
+        return String::new();
+    };
+
+    match issued.pointer("/date-parts/0/0") {
+        Some(part) if !part.is_array() => text_value(part),
+        _ => {
+            let raw = text_field(issued, "raw");
+            if raw.is_empty() {
+                text_field(issued, "literal")
+            } else {
+                raw
+            }
+        }
+    }
+}
+
+pub fn write(records: &[SourceRecord]) -> String {
+    let items: Vec<Value> = records
+        .iter()
+        .enumerate()
+        .map(|(i, record)| {
+            let mut item = Map::new();
+            if record.key.is_empty() {
+                item.insert("id".to_string(), json!(format!("ref{}", i + 1)));
+            } else {
+                item.insert("id".to_string(), json!(record.key));
+            }
+            item.insert("type".to_string(), json!(type_of_kind(record.kind)));
+
+            let mut insert_text = |key: &str, value: &str| {
+                if !value.is_empty() {
+                    item.insert(key.to_string(), json!(value));
+                }
+            };
+            insert_text("title", &record.title);
+            insert_text("container-title", &record.container);
+            insert_text("volume", &record.volume);
+            insert_text("issue", &record.issue);
+            insert_text("page", &record.pages);
+            insert_text("publisher", &record.publisher);
+            insert_text("DOI", &record.doi);
+            insert_text("URL", &record.url);
+            insert_text("abstract", &record.abstract_text);
+            insert_text("note", &record.notes.join("\n"));
+
+            if !record.authors.is_empty() {
+                let authors: Vec<Value> = record
+                    .authors
+                    .iter()
+                    .map(|author| match author.split_once(", ") {
+                        Some((family, given)) => json!({ "family": family, "given": given }),
+                        None => json!({ "literal": author }),
+                    })
+                    .collect();
+                item.insert("author".to_string(), Value::Array(authors));
+            }
+
+            if !record.year.is_empty() {
+                let issued = match record.year.parse::<i32>() {
+                    Ok(year) => json!({ "date-parts": [[year]] }),
+                    Err(_) => json!({ "raw": record.year }),
+                };
+                item.insert("issued".to_string(), issued);
+            }
+
+            Value::Object(item)
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&items).unwrap()
+}
+
+// Test that the references written in CSL-JSON format are read back unchanged.
+#[test]
+fn csl_json_round_trip() {
+    let records = vec![SourceRecord {
+        key: "smith2021".to_string(),
+        kind: SourceKind::Report,
+        title: "A study of examples".to_string(),
+        authors: vec!["Smith, John".to_string(), "Example Consortium".to_string()],
+        year: "2021".to_string(),
+        container: "Journal of Examples".to_string(),
+        volume: "12".to_string(),
+        pages: "45–67".to_string(),
+        doi: "10.1000/xyz123".to_string(),
+        abstract_text: "We study examples.".to_string(),
+        notes: vec!["Key paper".to_string(), "Read twice".to_string()],
+        ..Default::default()
+    }];
+
+    let text = write(&records);
+    assert!(text.contains(r#""abstract": "We study examples.""#));
+    assert_eq!(parse(&text).unwrap(), records);
+
+    let zotero = r#"[{"id": "x", "type": "article-journal", "title": "Numbers",
+        "volume": 7, "issued": {"date-parts": [["2019", 5]]},
+        "author": [{"family": "Doe", "given": "Ann"}]}]"#;
+    let parsed = parse(zotero).unwrap();
+    assert_eq!(parsed[0].key, "x");
+    assert_eq!(parsed[0].kind, SourceKind::Article);
+    assert_eq!(parsed[0].volume, "7");
+    assert_eq!(parsed[0].year, "2019");
+    assert_eq!(parsed[0].authors, vec!["Doe, Ann".to_string()]);
+}
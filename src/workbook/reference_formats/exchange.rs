@@ -0,0 +1,287 @@
+use crate::workbook::app::Workbook;
+use crate::workbook::identity::Identity;
+use crate::workbook::note::{Note, Notes};
+use crate::workbook::notifications::Notification;
+use crate::workbook::project::Project;
+use crate::workbook::sections::references::{Reading, Reference, ReferenceMetadata, SourceKind};
+
+use super::{csl_json, pdf, ris};
+
+// The formats of reference managers (Zotero, Mendeley, EndNote, etc.)
+// supported for importing and exporting the lists of references.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ReferenceFormat {
+    // Research Information Systems tagged format.
+    Ris,
+    // Citation Style Language JSON.
+    CslJson,
+}
+
+impl ReferenceFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReferenceFormat::Ris => "ris",
+            ReferenceFormat::CslJson => "json",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReferenceFormat::Ris => "RIS",
+            ReferenceFormat::CslJson => "CSL-JSON",
+        }
+    }
+}
+
+// The lists of references in the workbook which can be exchanged
+// with reference managers.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ReferenceList {
+    // `Idea::references`
+    KeyReferences,
+    // `Literature::references`
+    Literature,
+}
+
+impl ReferenceList {
+    pub const ALL: [ReferenceList; 2] = [ReferenceList::KeyReferences, ReferenceList::Literature];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReferenceList::KeyReferences => "Key References",
+            ReferenceList::Literature => "Literature Survey",
+        }
+    }
+
+    // Which references of the list are exported, see `Project::references()`.
+    pub fn export_hint(&self) -> &'static str {
+        match self {
+            ReferenceList::KeyReferences => "All the key references are exported",
+            ReferenceList::Literature => {
+                "Only the references shown in the Literature Survey (ticked) are exported"
+            }
+        }
+    }
+}
+
+// The bibliographic record as it is read from (or written to)
+// a file of a reference manager.
+// Note: The notes are kept in the source details of the imported reference.
+#[derive(Default, Debug, PartialEq)]
+pub struct SourceRecord {
+    pub key: String, // Citation key
+    pub kind: SourceKind,
+    pub title: String,
+    pub authors: Vec<String>, // "Family, Given"
+    pub year: String,
+    pub container: String, // Journal, book or proceedings title
+    pub volume: String,
+    pub issue: String,
+    pub pages: String,
+    pub publisher: String,
+    pub doi: String,
+    pub url: String,
+    pub abstract_text: String,
+    pub notes: Vec<String>,
+}
+
+impl From<SourceRecord> for Reference {
+    fn from(val: SourceRecord) -> Self {
+        let hyperlink = if !val.url.is_empty() {
+            val.url
+        } else if !val.doi.is_empty() {
+            format!("https://doi.org/{}", val.doi)
+        } else {
+            String::new()
+        };
+
+        Reference {
+            title: val.title,
+            hyperlink,
+            source_details: Notes {
+                notes: val
+                    .notes
+                    .into_iter()
+                    .map(|text| Note {
+                        note: text,
+                        ..Note::new()
+                    })
+                    .collect(),
+            },
+            visible: true,
+            metadata: ReferenceMetadata {
+                kind: val.kind,
+                authors: val.authors.join("; "),
+                year: val.year,
+                container: val.container,
+                volume: val.volume,
+                issue: val.issue,
+                pages: val.pages,
+                publisher: val.publisher,
+                doi: val.doi,
+                key: val.key,
+                abstract_text: val.abstract_text,
+            },
+            reading: Reading::default(),
+            id: Identity::new(),
+        }
+    }
+}
+
+impl From<&Reference> for SourceRecord {
+    fn from(val: &Reference) -> Self {
+        let data = &val.metadata;
+        let doi = if data.doi.is_empty() {
+            ["https://doi.org/", "http://doi.org/", "https://dx.doi.org/"]
+                .iter()
+                .find_map(|prefix| val.hyperlink.strip_prefix(prefix))
+                .unwrap_or_default()
+                .to_string()
+        } else {
+            data.doi.clone()
+        };
+
+        SourceRecord {
+            key: val.citation_key(),
+            kind: data.kind,
+            title: val.title.clone(),
+            authors: data
+                .author_list()
+                .into_iter()
+                .map(|author| author.to_string())
+                .collect(),
+            year: data.year.clone(),
+            container: data.container.clone(),
+            volume: data.volume.clone(),
+            issue: data.issue.clone(),
+            pages: data.pages.clone(),
+            publisher: data.publisher.clone(),
+            doi,
+            url: val.hyperlink.clone(),
+            abstract_text: data.abstract_text.clone(),
+            notes: val
+                .source_details
+                .notes
+                .iter()
+                .filter(|note| note.visible && !note.note.is_empty())
+                .map(|note| note.note.clone())
+                .collect(),
+        }
+    }
+}
+
+// Read the references from the contents of a RIS or CSL-JSON file.
+// The format is detected by the contents, not by the file extension.
+pub fn decode_references(contents: &[u8]) -> Result<Vec<Reference>, String> {
+    let text = String::from_utf8_lossy(contents);
+    let text = text.trim_start_matches('\u{feff}').trim();
+
+    let records = if text.starts_with('[') || text.starts_with('{') {
+        csl_json::parse(text).map_err(|e| format!("CSL-JSON: {}", e))?
+    } else if text.lines().any(|line| line.starts_with("TY  -")) {
+        ris::parse(text)
+    } else {
+        return Err("unknown format, RIS or CSL-JSON is expected".to_string());
+    };
+
+    Ok(records.into_iter().map(Reference::from).collect())
+}
+
+// Read the reference from the metadata embedded in a PDF file.
+pub fn decode_pdf_reference(contents: &[u8]) -> Result<Reference, String> {
+    pdf::parse(contents).map(Reference::from)
+}
+
+// Write the references in the format of reference managers.
+pub fn encode_references(references: &[&Reference], format: ReferenceFormat) -> Vec<u8> {
+    let records: Vec<SourceRecord> = references
+        .iter()
+        .map(|&reference| SourceRecord::from(reference))
+        .collect();
+
+    match format {
+        ReferenceFormat::Ris => ris::write(&records),
+        ReferenceFormat::CslJson => csl_json::write(&records),
+    }
+    .into_bytes()
+}
+
+impl Project {
+    // The key references, or the visible references of the Literature Survey.
+    pub fn references(&self, list: ReferenceList) -> Vec<&Reference> {
+        let bibliography = &self.bibliography;
+        match list {
+            ReferenceList::KeyReferences => bibliography
+                .key_references
+                .iter()
+                .filter_map(|&id| bibliography.get(id))
+                .collect(),
+            ReferenceList::Literature => bibliography
+                .references
+                .iter()
+                .filter(|reference| reference.visible)
+                .collect(),
+        }
+    }
+}
+
+impl Workbook {
+    // Append the references read into the `file_buffer` to the list.
+    pub fn import_references(&mut self, list: ReferenceList) {
+        match decode_references(&self.file_buffer) {
+            Ok(references) => {
+                self.notifications.push(Notification::success(
+                    "Imported",
+                    format!("{} references into {}", references.len(), list.name()),
+                ));
+                let bibliography = &mut self.project.bibliography;
+                match list {
+                    ReferenceList::KeyReferences => {
+                        bibliography.add_key_references(references);
+                    }
+                    ReferenceList::Literature => {
+                        for reference in references {
+                            let i = bibliography.add(reference);
+                            bibliography.references[i].visible = true;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                self.notifications.push(Notification::error(
+                    "Import Error",
+                    format!("References cannot be imported: {}", e),
+                ));
+            }
+        }
+    }
+
+    // Add the reference described by the metadata of the PDF file
+    // read into the `file_buffer` to the list.
+    pub fn import_pdf_reference(&mut self, list: ReferenceList) {
+        match decode_pdf_reference(&self.file_buffer) {
+            Ok(reference) => {
+                self.notifications.push(Notification::success(
+                    "Added",
+                    format!("“{}” to {}", reference.title, list.name()),
+                ));
+                let bibliography = &mut self.project.bibliography;
+                match list {
+                    ReferenceList::KeyReferences => {
+                        bibliography.add_key_references(vec![reference]);
+                    }
+                    ReferenceList::Literature => {
+                        let i = bibliography.add(reference);
+                        bibliography.references[i].visible = true;
+                    }
+                }
+            }
+            Err(e) => {
+                self.notifications.push(Notification::error(
+                    "Import Error",
+                    format!("The reference cannot be added: {}", e),
+                ));
+            }
+        }
+    }
+}
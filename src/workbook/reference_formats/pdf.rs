@@ -0,0 +1,548 @@
+// The metadata embedded in a PDF file: the document information dictionary
+// (`/Title`, `/Author`, `/CreationDate`, …) and the XMP packet (Dublin Core,
+// PRISM). Only the metadata of the file is read, nothing is looked up online.
+
+use super::exchange::SourceRecord;
+
+// The limit of the decompressed size of a stream.
+const STREAM_LIMIT: usize = 16 * 1024 * 1024;
+
+pub fn parse(contents: &[u8]) -> Result<SourceRecord, String> {
+    if find(&contents[..contents.len().min(1024)], b"%PDF-", 0).is_none() {
+        return Err("not a PDF file".to_string());
+    }
+
+    // The metadata may be kept in compressed object streams.
+    let streams = streams(contents);
+    let mut sections: Vec<&[u8]> = vec![contents];
+    sections.extend(streams.iter().map(|stream| stream.as_slice()));
+
+    let xmp = sections
+        .iter()
+        .find_map(|section| between(section, b"<x:xmpmeta", b"</x:xmpmeta>"))
+        .map(|packet| String::from_utf8_lossy(packet).to_string())
+        .unwrap_or_default();
+    let info = info(contents, &streams);
+    let entry = |key: &[u8]| {
+        info.as_deref()
+            .and_then(|dict| value(dict, key, contents, &streams))
+            .map(|text| text.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut record = SourceRecord {
+        title: xmp_items(&xmp, "dc:title")
+            .into_iter()
+            .next()
+            .unwrap_or_default(),
+        authors: xmp_items(&xmp, "dc:creator")
+            .iter()
+            .map(|name| family_first(name))
+            .collect(),
+        doi: ["prism:doi", "pdfx:doi", "dc:identifier"]
+            .iter()
+            .find_map(|tag| xmp_value(&xmp, tag).and_then(|text| find_doi(&text)))
+            .unwrap_or_default(),
+        year: ["prism:publicationDate", "prism:coverDate", "dc:date"]
+            .iter()
+            .find_map(|tag| {
+                xmp_value(&xmp, tag)
+                    .or_else(|| xmp_items(&xmp, tag).into_iter().next())
+                    .and_then(|date| year(&date))
+            })
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    if record.title.is_empty() || is_file_name(&record.title) {
+        record.title = entry(b"Title");
+    }
+    if is_file_name(&record.title) {
+        record.title.clear();
+    }
+    if record.authors.is_empty() {
+        record.authors = authors(&entry(b"Author"));
+    }
+    if record.doi.is_empty() {
+        record.doi = [entry(b"doi"), entry(b"Subject"), entry(b"Keywords"), xmp]
+            .iter()
+            .find_map(|text| find_doi(text))
+            .unwrap_or_default();
+    }
+    if record.year.is_empty() {
+        record.year = year(&entry(b"CreationDate")).unwrap_or_default();
+    }
+    let subject = entry(b"Subject");
+    if !subject.is_empty() && find_doi(&subject).is_none() {
+        record.abstract_text = subject;
+    }
+
+    if record.title.is_empty() && record.authors.is_empty() && record.doi.is_empty() {
+        return Err("no metadata found in the file".to_string());
+    }
+    Ok(record)
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| from + position)
+}
+
+fn between<'a>(haystack: &'a [u8], start: &[u8], end: &[u8]) -> Option<&'a [u8]> {
+    let from = find(haystack, start, 0)?;
+    let to = find(haystack, end, from)?;
+    Some(&haystack[from..to + end.len()])
+}
+
+fn is_delimiter(byte: u8) -> bool {
+    byte.is_ascii_whitespace() || b"()<>[]{}/%".contains(&byte)
+}
+
+// The decompressed object streams and XML metadata streams.
+fn streams(contents: &[u8]) -> Vec<Vec<u8>> {
+    let mut streams = Vec::new();
+    let mut from = 0;
+    while let Some(start) = find(contents, b"stream", from) {
+        from = start + 6;
+        // Skip "endstream" and streams without a dictionary.
+        if start >= 3 && &contents[start - 3..start] == b"end" {
+            continue;
+        }
+        let Some(object) = contents[..start]
+            .windows(3)
+            .rposition(|window| window == b"obj")
+        else {
+            continue;
+        };
+        let dict = &contents[object..start];
+        let data_start = match contents.get(from..from + 2) {
+            Some(b"\r\n") => from + 2,
+            Some([b'\n', _]) | Some([b'\r', _]) => from + 1,
+            _ => continue,
+        };
+        let Some(data_end) = find(contents, b"endstream", data_start) else {
+            break;
+        };
+        from = data_end + 9;
+
+        if find(dict, b"/ObjStm", 0).is_none() && find(dict, b"/XML", 0).is_none() {
+            continue;
+        }
+        let data = &contents[data_start..data_end];
+        if find(dict, b"/FlateDecode", 0).is_some() {
+            if let Ok(data) =
+                miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(data, STREAM_LIMIT)
+            {
+                streams.push(data);
+            }
+        } else if find(dict, b"/Filter", 0).is_none() {
+            streams.push(data.to_vec());
+        }
+    }
+    streams
+}
+
+// The document information dictionary referred to by the trailer.
+fn info(contents: &[u8], streams: &[Vec<u8>]) -> Option<Vec<u8>> {
+    let mut from = 0;
+    let mut info = None;
+    // The last trailer is the latest revision of the file.
+    while let Some(start) = find(contents, b"/Info", from) {
+        from = start + 5;
+        if let Some(number) = reference(&contents[from..]) {
+            info = Some(number);
+        }
+    }
+    let object = object(contents, streams, info?)?;
+    let start = find(&object, b"<<", 0)?;
+    Some(object[start..].to_vec())
+}
+
+// The indirect reference "12 0 R" at the start of the bytes.
+fn reference(bytes: &[u8]) -> Option<u32> {
+    let text = String::from_utf8_lossy(&bytes[..bytes.len().min(32)]).to_string();
+    let mut words = text.split_ascii_whitespace();
+    let number = words.next()?.parse().ok()?;
+    words.next()?.parse::<u32>().ok()?;
+    words.next()?.starts_with('R').then_some(number)
+}
+
+// The contents of the object with the number, in the file or in an object stream.
+fn object(contents: &[u8], streams: &[Vec<u8>], number: u32) -> Option<Vec<u8>> {
+    let header = format!("{} 0 obj", number);
+    let mut from = 0;
+    while let Some(start) = find(contents, header.as_bytes(), from) {
+        from = start + header.len();
+        if start == 0 || is_delimiter(contents[start - 1]) {
+            let end = find(contents, b"endobj", from).unwrap_or(contents.len());
+            return Some(contents[from..end].to_vec());
+        }
+    }
+
+    // The object stream starts with the pairs of object numbers and offsets.
+    for stream in streams {
+        let Some(first) = stream
+            .iter()
+            .position(|byte| !byte.is_ascii_digit() && !byte.is_ascii_whitespace())
+        else {
+            continue;
+        };
+        let pairs: Vec<usize> = String::from_utf8_lossy(&stream[..first])
+            .split_ascii_whitespace()
+            .filter_map(|word| word.parse().ok())
+            .collect();
+        for (i, pair) in pairs.chunks(2).enumerate() {
+            if pair.len() == 2 && pair[0] == number as usize {
+                let start = first + pair[1];
+                let end = pairs
+                    .get(i * 2 + 3)
+                    .map(|offset| first + offset)
+                    .unwrap_or(stream.len());
+                return stream.get(start..end).map(|bytes| bytes.to_vec());
+            }
+        }
+    }
+    None
+}
+
+// The text value of the key in the dictionary, direct or referred to.
+fn value(dict: &[u8], key: &[u8], contents: &[u8], streams: &[Vec<u8>]) -> Option<String> {
+    let name = [b"/", key].concat();
+    let mut from = 0;
+    let start = loop {
+        let start = find(dict, &name, from)?;
+        from = start + name.len();
+        if dict.get(from).is_none_or(|&byte| is_delimiter(byte)) {
+            break from;
+        }
+    };
+    let rest = &dict[start..];
+    let skip = rest.iter().position(|byte| !byte.is_ascii_whitespace())?;
+    let rest = &rest[skip..];
+    match rest.first()? {
+        b'(' => Some(decode_text(&literal_string(rest))),
+        b'<' if rest.get(1) != Some(&b'<') => Some(decode_text(&hex_string(rest))),
+        _ => {
+            let number = reference(rest)?;
+            let object = object(contents, streams, number)?;
+            let skip = object.iter().position(|byte| !byte.is_ascii_whitespace())?;
+            let object = &object[skip..];
+            match object.first()? {
+                b'(' => Some(decode_text(&literal_string(object))),
+                b'<' => Some(decode_text(&hex_string(object))),
+                _ => None,
+            }
+        }
+    }
+}
+
+// "(text with \(escapes\) and (balanced) parentheses)"
+fn literal_string(bytes: &[u8]) -> Vec<u8> {
+    let mut text = Vec::new();
+    let mut depth = 0;
+    let mut i = 1;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        match byte {
+            b'\\' => {
+                i += 1;
+                let Some(&escaped) = bytes.get(i) else {
+                    break;
+                };
+                match escaped {
+                    b'n' => text.push(b'\n'),
+                    b'r' => text.push(b'\r'),
+                    b't' => text.push(b'\t'),
+                    b'b' => text.push(8),
+                    b'f' => text.push(12),
+                    b'0'..=b'7' => {
+                        let mut code = 0u32;
+                        let mut digits = 0;
+                        while digits < 3 && matches!(bytes.get(i), Some(b'0'..=b'7')) {
+                            code = code * 8 + (bytes[i] - b'0') as u32;
+                            i += 1;
+                            digits += 1;
+                        }
+                        text.push(code as u8);
+                        continue;
+                    }
+                    // The line continuation.
+                    b'\r' | b'\n' => {}
+                    other => text.push(other),
+                }
+            }
+            b'(' => {
+                depth += 1;
+                text.push(byte);
+            }
+            b')' => {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+                text.push(byte);
+            }
+            _ => text.push(byte),
+        }
+        i += 1;
+    }
+    text
+}
+
+// "<FEFF0041>"
+fn hex_string(bytes: &[u8]) -> Vec<u8> {
+    let end = bytes
+        .iter()
+        .position(|&byte| byte == b'>')
+        .unwrap_or(bytes.len());
+    let digits: Vec<u8> = bytes[1..end]
+        .iter()
+        .filter_map(|&byte| (byte as char).to_digit(16).map(|digit| digit as u8))
+        .collect();
+    digits
+        .chunks(2)
+        .map(|pair| pair[0] * 16 + pair.get(1).copied().unwrap_or(0))
+        .collect()
+}
+
+// The text strings are UTF-16 with the byte order mark, UTF-8 with the mark
+// (PDF 2.0) or PDFDocEncoding, which is close enough to Latin-1.
+fn decode_text(bytes: &[u8]) -> String {
+    let utf16 = |bytes: &[u8], big_endian: bool| {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| {
+                if big_endian {
+                    u16::from_be_bytes([pair[0], pair[1]])
+                } else {
+                    u16::from_le_bytes([pair[0], pair[1]])
+                }
+            })
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+    match bytes {
+        [0xfe, 0xff, rest @ ..] => utf16(rest, true),
+        [0xff, 0xfe, rest @ ..] => utf16(rest, false),
+        [0xef, 0xbb, 0xbf, rest @ ..] => String::from_utf8_lossy(rest).to_string(),
+        _ => bytes.iter().map(|&byte| byte as char).collect(),
+    }
+}
+
+// The contents of the XMP element, e.g. `<prism:doi>…</prism:doi>`,
+// or of the attribute, e.g. `prism:doi="…"`.
+fn xmp_value(xmp: &str, tag: &str) -> Option<String> {
+    let attribute = format!("{}=\"", tag);
+    if let Some(start) = xmp.find(&attribute).map(|start| start + attribute.len()) {
+        let end = xmp[start..].find('"')? + start;
+        return Some(unescape(&xmp[start..end]));
+    }
+    let content = element(xmp, tag)?;
+    if content.contains('<') {
+        return None;
+    }
+    Some(unescape(content.trim())).filter(|value| !value.is_empty())
+}
+
+// The items of the XMP array, e.g. `<dc:creator><rdf:Seq><rdf:li>…`.
+fn xmp_items(xmp: &str, tag: &str) -> Vec<String> {
+    let Some(mut content) = element(xmp, tag) else {
+        return Vec::new();
+    };
+    let mut items = Vec::new();
+    while let Some(start) = content.find("<rdf:li") {
+        let Some(open) = content[start..].find('>').map(|open| start + open + 1) else {
+            break;
+        };
+        let Some(close) = content[open..].find("</rdf:li>").map(|close| open + close) else {
+            break;
+        };
+        let item = unescape(content[open..close].trim());
+        if !item.is_empty() {
+            items.push(item);
+        }
+        content = &content[close..];
+    }
+    items
+}
+
+fn element<'a>(xmp: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut from = 0;
+    loop {
+        let start = xmp[from..].find(&open)? + from + open.len();
+        from = start;
+        // Skip the tags with the same prefix, e.g. `dc:date` and `dc:dateAccepted`.
+        if xmp[start..].starts_with(|c: char| c == '>' || c.is_whitespace()) {
+            let content = xmp[start..].find('>')? + start + 1;
+            if xmp[..content].ends_with("/>") {
+                return None;
+            }
+            let end = xmp[content..].find(&close)? + content;
+            return Some(&xmp[content..end]);
+        }
+    }
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|code| u32::from_str_radix(code, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|code| code.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => {
+                unescaped.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+// The titles set by the word processors, e.g. "Microsoft Word - paper.docx".
+fn is_file_name(title: &str) -> bool {
+    let title = title.to_lowercase();
+    title.starts_with("microsoft word")
+        || [".doc", ".docx", ".odt", ".tex", ".dvi", ".pdf", ".rtf"]
+            .iter()
+            .any(|extension| title.ends_with(extension))
+}
+
+// The names in the `/Author` entry, "John Smith; Ann Doe" or "John Smith, Ann Doe and Kim Lee".
+fn authors(text: &str) -> Vec<String> {
+    let names: Vec<&str> = if text.contains(';') {
+        text.split(';').collect()
+    } else if text.matches(',').count() == 1 && !text.contains(" and ") {
+        // "Smith, John"
+        vec![text]
+    } else {
+        text.split(',')
+            .flat_map(|name| name.split(" and "))
+            .collect()
+    };
+    names
+        .into_iter()
+        .map(|name| family_first(name.trim()))
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+// "John Smith" -> "Smith, John"
+fn family_first(name: &str) -> String {
+    let name = name.trim();
+    if name.contains(',') {
+        return name.to_string();
+    }
+    match name.rsplit_once(' ') {
+        Some((given, family)) => format!("{}, {}", family, given.trim()),
+        None => name.to_string(),
+    }
+}
+
+// The first four digits, "D:20210315…" or "2021-03-15".
+fn year(date: &str) -> Option<String> {
+    let digits = date.trim().trim_start_matches("D:");
+    let year: String = digits.chars().take(4).collect();
+    (year.len() == 4 && year.chars().all(|c| c.is_ascii_digit())).then_some(year)
+}
+
+// "10.1000/xyz123" in the text, e.g. "doi:10.1000/xyz123" or a link.
+fn find_doi(text: &str) -> Option<String> {
+    let mut from = 0;
+    while let Some(start) = text[from..].find("10.").map(|start| from + start) {
+        from = start + 3;
+        let prefix: String = text[from..]
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        if prefix.len() < 4 || !text[from + prefix.len()..].starts_with('/') {
+            continue;
+        }
+        let suffix_start = from + prefix.len() + 1;
+        let suffix: String = text[suffix_start..]
+            .chars()
+            .take_while(|c| !c.is_whitespace() && !"\"<>".contains(*c))
+            .collect();
+        let suffix = suffix.trim_end_matches(['.', ',', ';', ')']);
+        if !suffix.is_empty() {
+            return Some(format!("10.{}/{}", prefix, suffix));
+        }
+    }
+    None
+}
+
+// Test that the metadata is read from the information dictionary and the XMP packet.
+#[test]
+fn pdf_metadata() {
+    let xmp = "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF><rdf:Description \
+        prism:doi=\"10.1000/xyz123\"><dc:title><rdf:Alt><rdf:li xml:lang=\"x-default\">\
+        A study of examples &amp; cases</rdf:li></rdf:Alt></dc:title><dc:creator><rdf:Seq>\
+        <rdf:li>John Smith</rdf:li><rdf:li>Doe, Ann</rdf:li></rdf:Seq></dc:creator>\
+        </rdf:Description></rdf:RDF></x:xmpmeta>";
+    let metadata = format!(
+        "4 0 obj\n<< /Type /Metadata /Subtype /XML /Length {} >>\nstream\n{}\nendstream\nendobj\n",
+        xmp.len(),
+        xmp
+    );
+    let pdf = |info: &str, metadata: &[u8]| {
+        let mut pdf = b"%PDF-1.7\n1 0 obj\n<< /Type /Catalog >>\nendobj\n".to_vec();
+        pdf.extend_from_slice(metadata);
+        pdf.extend_from_slice(info.as_bytes());
+        pdf.extend_from_slice(b"trailer\n<< /Root 1 0 R /Info 3 0 R >>\n%%EOF\n");
+        pdf
+    };
+    let info = "3 0 obj\n<< /Title (Microsoft Word - draft.docx) /Author (Kim Lee) \
+        /CreationDate (D:20210315120000Z) >>\nendobj\n";
+
+    let record = parse(&pdf(info, metadata.as_bytes())).unwrap();
+    assert_eq!(record.title, "A study of examples & cases");
+    assert_eq!(record.authors, vec!["Smith, John", "Doe, Ann"]);
+    assert_eq!(record.doi, "10.1000/xyz123");
+    assert_eq!(record.year, "2021");
+
+    // The information dictionary compressed in an object stream, without XMP.
+    let objects = "3 0 << /Title <FEFF0054006900740072006500> \
+        /Author (Lee, Kim; Park, Jo) /Subject (doi:10.5555/abc.) >>";
+    let compressed = miniz_oxide::deflate::compress_to_vec_zlib(objects.as_bytes(), 6);
+    let mut stream = format!(
+        "5 0 obj\n<< /Type /ObjStm /N 1 /First 4 /Filter /FlateDecode /Length {} >>\nstream\n",
+        compressed.len()
+    )
+    .into_bytes();
+    stream.extend_from_slice(&compressed);
+    stream.extend_from_slice(b"\nendstream\nendobj\n");
+
+    let record = parse(&pdf("", &stream)).unwrap();
+    assert_eq!(record.title, "Titre");
+    assert_eq!(record.authors, vec!["Lee, Kim", "Park, Jo"]);
+    assert_eq!(record.doi, "10.5555/abc");
+    assert_eq!(record.year, "");
+
+    assert!(parse(b"TY  - JOUR").is_err());
+}
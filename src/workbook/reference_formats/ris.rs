@@ -0,0 +1,229 @@
+// Research Information Systems (RIS) tagged format.
+// Each line is "TG  - value", a record starts with the `TY` (type) tag
+// and ends with the `ER` (end of record) tag.
+
+use super::citations::is_key;
+use super::exchange::SourceRecord;
+use crate::workbook::sections::references::SourceKind;
+
+pub fn parse(text: &str) -> Vec<SourceRecord> {
+    let mut records = Vec::new();
+    let mut record: Option<SourceRecord> = None;
+    let mut last_tag = String::new();
+
+    for line in text.lines() {
+        let line = line.trim_end();
+        match split_tag(line) {
+            Some(("TY", value)) => {
+                record = Some(SourceRecord {
+                    kind: kind_from_type(value),
+                    ..Default::default()
+                });
+            }
+            Some(("ER", _)) => {
+                if let Some(complete) = record.take() {
+                    records.push(complete);
+                }
+            }
+            Some((tag, value)) => {
+                if let Some(current) = record.as_mut() {
+                    apply_tag(current, tag, value);
+                }
+                last_tag = tag.to_string();
+            }
+            None => {
+                // Long abstracts and notes may be wrapped onto untagged lines.
+                let value = line.trim();
+                if let (Some(current), false) = (record.as_mut(), value.is_empty()) {
+                    match last_tag.as_str() {
+                        "AB" | "N2" => {
+                            current.abstract_text.push(' ');
+                            current.abstract_text.push_str(value);
+                        }
+                        "N1" => {
+                            if let Some(note) = current.notes.last_mut() {
+                                note.push(' ');
+                                note.push_str(value);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    // The last record without the closing `ER` tag is kept as well.
+    if let Some(incomplete) = record {
+        records.push(incomplete);
+    }
+
+    records
+}
+
+fn kind_from_type(value: &str) -> SourceKind {
+    match value {
+        "JOUR" | "JFULL" | "MGZN" | "NEWS" => SourceKind::Article,
+        "BOOK" | "EBOOK" | "EDBOOK" => SourceKind::Book,
+        "CHAP" | "ECHAP" => SourceKind::Chapter,
+        "CONF" | "CPAPER" => SourceKind::Conference,
+        "RPRT" => SourceKind::Report,
+        "THES" => SourceKind::Thesis,
+        "ELEC" | "WEB" | "BLOG" => SourceKind::Web,
+        _ => SourceKind::Other,
+    }
+}
+
+fn type_of_kind(kind: SourceKind) -> &'static str {
+    match kind {
+        SourceKind::Article => "JOUR",
+        SourceKind::Book => "BOOK",
+        SourceKind::Chapter => "CHAP",
+        SourceKind::Conference => "CPAPER",
+        SourceKind::Report => "RPRT",
+        SourceKind::Thesis => "THES",
+        SourceKind::Web => "ELEC",
+        SourceKind::Other => "GEN",
+    }
+}
+
+fn split_tag(line: &str) -> Option<(&str, &str)> {
+    let tag = line.get(..2)?;
+    let value = line.get(2..)?.strip_prefix("  -")?;
+    let mut chars = tag.chars();
+    let is_tag = chars.next()?.is_ascii_uppercase()
+        && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+
+    if is_tag {
+        Some((tag, value.trim()))
+    } else {
+        None
+    }
+}
+
+fn apply_tag(record: &mut SourceRecord, tag: &str, value: &str) {
+    if value.is_empty() {
+        return;
+    }
+    let set_once = |field: &mut String| {
+        if field.is_empty() {
+            *field = value.to_string();
+        }
+    };
+
+    match tag {
+        "ID" if is_key(value) => set_once(&mut record.key),
+        "TI" | "T1" => set_once(&mut record.title),
+        "AU" | "A1" => record.authors.push(value.to_string()),
+        // "2021///" or "2021/03/15/"
+        "PY" | "Y1" | "DA" if record.year.is_empty() => {
+            record.year = value.split('/').next().unwrap_or_default().to_string();
+        }
+        "T2" | "JO" | "JF" | "JA" | "J2" | "BT" => set_once(&mut record.container),
+        "VL" => set_once(&mut record.volume),
+        "IS" => set_once(&mut record.issue),
+        "SP" => set_once(&mut record.pages),
+        "EP" => {
+            if record.pages.is_empty() {
+                record.pages = value.to_string();
+            } else {
+                record.pages = format!("{}–{}", record.pages, value);
+            }
+        }
+        "PB" => set_once(&mut record.publisher),
+        "DO" => set_once(&mut record.doi),
+        "UR" | "L1" | "L2" => set_once(&mut record.url),
+        "AB" | "N2" => set_once(&mut record.abstract_text),
+        "N1" => record.notes.push(value.to_string()),
+        _ => {}
+    }
+}
+
+pub fn write(records: &[SourceRecord]) -> String {
+    let mut text = String::new();
+
+    for record in records {
+        push_tag(&mut text, "TY", type_of_kind(record.kind));
+        push_tag(&mut text, "ID", &record.key);
+        push_tag(&mut text, "TI", &record.title);
+        for author in &record.authors {
+            push_tag(&mut text, "AU", author);
+        }
+        push_tag(&mut text, "PY", &record.year);
+        push_tag(&mut text, "T2", &record.container);
+        push_tag(&mut text, "VL", &record.volume);
+        push_tag(&mut text, "IS", &record.issue);
+        match record.pages.split_once(['–', '-']) {
+            Some((start, end)) => {
+                push_tag(&mut text, "SP", start.trim());
+                push_tag(&mut text, "EP", end.trim());
+            }
+            None => push_tag(&mut text, "SP", &record.pages),
+        }
+        push_tag(&mut text, "PB", &record.publisher);
+        push_tag(&mut text, "DO", &record.doi);
+        push_tag(&mut text, "UR", &record.url);
+        push_tag(&mut text, "AB", &record.abstract_text);
+        for note in &record.notes {
+            push_tag(&mut text, "N1", note);
+        }
+        // The end of record tag has no value.
+        text.push_str("ER  - \r\n");
+    }
+
+    text
+}
+
+fn push_tag(text: &mut String, tag: &str, value: &str) {
+    if !value.is_empty() {
+        // Line breaks are not allowed inside the value of a tag.
+        let value = value.split_whitespace().collect::<Vec<&str>>().join(" ");
+        text.push_str(&format!("{}  - {}\r\n", tag, value));
+    }
+}
+
+// Test that the references written in RIS format are read back unchanged.
+#[test]
+fn ris_round_trip() {
+    use crate::workbook::sections::references::Reference;
+
+    let records = vec![
+        SourceRecord {
+            key: "smith2021".to_string(),
+            title: "A study of examples".to_string(),
+            authors: vec!["Smith, John".to_string(), "Doe, Ann".to_string()],
+            year: "2021".to_string(),
+            container: "Journal of Examples".to_string(),
+            volume: "12".to_string(),
+            issue: "3".to_string(),
+            pages: "45–67".to_string(),
+            doi: "10.1000/xyz123".to_string(),
+            url: "https://example.com/study".to_string(),
+            abstract_text: "We study examples.".to_string(),
+            notes: vec!["Key paper".to_string()],
+            ..Default::default()
+        },
+        SourceRecord {
+            kind: SourceKind::Other,
+            title: "Second source".to_string(),
+            ..Default::default()
+        },
+    ];
+
+    let text = write(&records);
+    assert!(text.starts_with("TY  - JOUR\r\nID  - smith2021\r\nTI  - A study of examples\r\n"));
+    assert!(text.contains("AB  - We study examples.\r\nN1  - Key paper\r\n"));
+    assert_eq!(parse(&text), records);
+
+    // The abstract is kept apart from the notes of the reference.
+    let reference = Reference::from(parse(&text).remove(0));
+    assert_eq!(reference.metadata.abstract_text, "We study examples.");
+    assert_eq!(reference.source_details.notes.len(), 1);
+    assert_eq!(SourceRecord::from(&reference), records[0]);
+
+    let wrapped = "TY  - JOUR\nT1  - Wrapped\nAB  - First line\n  second line\nER  -\n";
+    let parsed = parse(wrapped);
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(parsed[0].kind, SourceKind::Article);
+    assert_eq!(parsed[0].title, "Wrapped");
+    assert_eq!(parsed[0].abstract_text, "First line second line");
+}
@@ -1,6 +1,8 @@
 // use chrono::Datelike;
 use egui::{Color32, RichText, Ui};
 
+use super::reference_formats::citations::Citations;
+use super::sections::references::preview_entry;
 use super::{app::Workbook, chapter::Variety};
 
 pub const SPACE_INTERNAL_PREVIEW: f32 = 10.0;
@@ -31,6 +33,9 @@ impl Renderer for Workbook {
         let budget = &self.project.budget;
         // let todo_attachments = &self.project.attachments;
 
+        let citations = self.citations.clone();
+        Citations::share(ui.ctx(), citations.clone());
+
         self.preview_version(ui);
         for section in sections {
             if section.tier > self.project.resolution {
@@ -44,7 +49,7 @@ impl Renderer for Workbook {
                         funding_options.preview(ui, None);
                     }
                     Variety::Idea => {
-                        idea.preview(ui, self.project.resolution);
+                        idea.preview(ui, self.project.resolution, &citations);
                     }
                     Variety::Timeline => {
                         timeline.preview(ui, self.project.resolution);
@@ -65,7 +70,7 @@ impl Renderer for Workbook {
                         scripting.preview(ui, None);
                     }
                     Variety::Literature => {
-                        literature.preview(ui, None);
+                        literature.preview(ui, &citations);
                     }
                     Variety::Outcomes => {
                         expected_results.preview(ui, self.project.resolution);
@@ -74,7 +79,7 @@ impl Renderer for Workbook {
                         resources.preview(ui, None);
                     }
                     Variety::Budget => {
-                        budget.preview(ui, self.project.resolution);
+                        budget.preview(ui, self.project.resolution, &self.project.costs());
                     }
                     Variety::Attachments => {
                         // todo_attachments.preview(ui, None);
@@ -83,9 +88,49 @@ impl Renderer for Workbook {
                 }
             }
         }
+        preview_citations(ui, &citations);
+    }
+}
+
+// The bibliography of the cited references and the warnings about citation keys.
+fn preview_citations(ui: &mut Ui, citations: &Citations) {
+    let cited = citations.cited();
+    if !cited.is_empty() {
+        ui.add_space(SPACE_SECTIONS_PREVIEW);
+        ui.heading("Bibliography");
+        for (number, reference) in cited {
+            preview_entry(ui, number, reference, citations);
+        }
+    }
+
+    for (warning, keys) in citations.warnings() {
+        ui.add_space(SPACE_INTERNAL_PREVIEW);
+        let color = ui.visuals().warn_fg_color;
+        ui.label(
+            RichText::new(format!("{}: {}", warning, keys))
+                .color(color)
+                .small(),
+        );
     }
 }
 
+// The table with the header in the first row and the totals in the last one.
+pub fn preview_table(ui: &mut Ui, id: &str, table: Vec<Vec<String>>) {
+    let last = table.len().saturating_sub(1);
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+        for (i, row) in table.into_iter().enumerate() {
+            for cell in row {
+                if i == 0 || i == last {
+                    ui.label(RichText::new(cell).strong());
+                } else {
+                    ui.label(cell);
+                }
+            }
+            ui.end_row();
+        }
+    });
+}
+
 impl Workbook {
     // Project Version
     fn preview_version(&self, ui: &mut Ui) {
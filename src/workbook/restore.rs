@@ -0,0 +1,428 @@
+// Restoring the chosen sections or notes of a snapshot into the current project,
+// the rest of the current project is kept.
+
+use std::collections::HashSet;
+
+use egui::{Context, RichText};
+
+use super::app::Workbook;
+use super::chapter::Variety;
+use super::compare::Version;
+use super::identity::Identity;
+use super::note::{Note, Notes};
+use super::project::Project;
+use super::sections::{
+    attachments::Attachments, budget::Budget, funding::FundingOptions, idea::Idea,
+    literature::Literature, methodology::Methodology, outcomes::Outcomes,
+    prelim_results::PrelimResults, references::Bibliography, references::Reference,
+    resources::Resources, scope::Scope, scripting::Scripting, team::Team, timeline::Timeline,
+};
+
+// The state of the "Restore" window.
+pub struct Restoring {
+    // The index in the `stored_projects`.
+    pub snapshot: usize,
+    pub sections: Vec<Variety>,
+    pub notes: HashSet<Identity>,
+}
+
+impl Restoring {
+    pub fn with(snapshot: usize) -> Self {
+        Restoring {
+            snapshot,
+            sections: Vec::new(),
+            notes: HashSet::new(),
+        }
+    }
+}
+
+impl Default for Restoring {
+    fn default() -> Self {
+        Restoring::with(0)
+    }
+}
+
+// The note of the snapshot which differs from the current project.
+struct Difference<'a> {
+    note: &'a Note,
+    // The note is deleted from the current project.
+    deleted: bool,
+}
+
+fn section_lists(project: &Project) -> Vec<(&'static str, Notes)> {
+    let mut lists = Vec::new();
+    project.visit_section_notes(&mut |name, notes| lists.push((name, notes.clone())));
+    lists
+}
+
+// The notes of the snapshot deleted or changed in the current list.
+fn differences<'a>(snapshot: &'a Notes, current: &Notes) -> Vec<Difference<'a>> {
+    snapshot
+        .notes
+        .iter()
+        .filter_map(
+            |note| match current.notes.iter().find(|other| other.id == note.id) {
+                Some(other) if other.note == note.note && other.visible == note.visible => None,
+                other => Some(Difference {
+                    note,
+                    deleted: other.is_none(),
+                }),
+            },
+        )
+        .collect()
+}
+
+impl Project {
+    // The title of the section as shown in the editor.
+    pub fn section_title(&self, section: &Variety) -> String {
+        let (title, default_title) = match section {
+            Variety::WorkingName => return "Project Title".to_string(),
+            Variety::Attachments => (&self.attachments.title, Attachments::default().title),
+            Variety::Budget => (&self.budget.title, Budget::default().title),
+            Variety::Funding => (&self.funding.title, FundingOptions::default().title),
+            Variety::Idea => (&self.idea.title, Idea::default().title),
+            Variety::Literature => (&self.literature.title, Literature::default().title),
+            Variety::Methodology => (&self.methodology.title, Methodology::default().title),
+            Variety::Outcomes => (&self.outcomes.title, Outcomes::default().title),
+            Variety::PrelimResults => (&self.prelim_results.title, PrelimResults::default().title),
+            Variety::Resources => (&self.resources.title, Resources::default().title),
+            Variety::Scope => (&self.scope.title, Scope::default().title),
+            Variety::Scripting => (&self.scripting.title, Scripting::default().title),
+            Variety::Team => (&self.team.title, Team::default().title),
+            Variety::Timeline => (&self.timeline.title, Timeline::default().title),
+            _ => return String::new(),
+        };
+        if title.is_empty() {
+            default_title
+        } else {
+            title.clone()
+        }
+    }
+
+    // Replace the section with the one of the snapshot.
+    // Note: The references are shared by the Key References of the Idea
+    //       and the Literature Survey, the ones of the other section are kept.
+    pub fn restore_section(&mut self, snapshot: &Project, section: &Variety) {
+        match section {
+            Variety::WorkingName => self.working_name = snapshot.working_name.clone(),
+            Variety::Attachments => self.attachments = snapshot.attachments.clone(),
+            Variety::Budget => self.budget = snapshot.budget.clone(),
+            Variety::Funding => self.funding = snapshot.funding.clone(),
+            Variety::Idea => {
+                self.idea = snapshot.idea.clone();
+                self.restore_key_references(&snapshot.bibliography);
+            }
+            Variety::Literature => {
+                self.literature = snapshot.literature.clone();
+                self.restore_literature(&snapshot.bibliography);
+            }
+            Variety::Methodology => self.methodology = snapshot.methodology.clone(),
+            Variety::Outcomes => self.outcomes = snapshot.outcomes.clone(),
+            Variety::PrelimResults => self.prelim_results = snapshot.prelim_results.clone(),
+            Variety::Resources => self.resources = snapshot.resources.clone(),
+            Variety::Scope => self.scope = snapshot.scope.clone(),
+            Variety::Scripting => self.scripting = snapshot.scripting.clone(),
+            Variety::Team => self.team = snapshot.team.clone(),
+            Variety::Timeline => self.timeline = snapshot.timeline.clone(),
+            _ => {}
+        }
+    }
+
+    // The key references of the snapshot, the sources are put back into
+    // the bibliography unless they are there, without being listed
+    // in the Literature Survey.
+    fn restore_key_references(&mut self, snapshot: &Bibliography) {
+        let bibliography = &mut self.bibliography;
+        for &id in &snapshot.key_references {
+            let Some(reference) = snapshot.get(id) else {
+                continue;
+            };
+            match bibliography.get_mut(id) {
+                Some(current) => {
+                    *current = Reference {
+                        visible: current.visible,
+                        ..reference.clone()
+                    }
+                }
+                None => bibliography.references.push(Reference {
+                    visible: false,
+                    ..reference.clone()
+                }),
+            }
+        }
+        bibliography.key_references = snapshot.key_references.clone();
+    }
+
+    // The references of the snapshot in its order, the key references
+    // added after the snapshot are kept without being listed.
+    fn restore_literature(&mut self, snapshot: &Bibliography) {
+        let bibliography = &mut self.bibliography;
+        let mut references = snapshot.references.clone();
+        for reference in &bibliography.references {
+            if bibliography.is_key_reference(reference.id) && snapshot.get(reference.id).is_none() {
+                references.push(Reference {
+                    visible: false,
+                    ..reference.clone()
+                });
+            }
+        }
+        bibliography.references = references;
+    }
+
+    // Put the notes of the snapshot back into the lists of the sections:
+    // the changed notes are replaced, the deleted ones are inserted after
+    // the notes they follow in the snapshot.
+    pub fn restore_notes(&mut self, snapshot: &Project, notes: &HashSet<Identity>) {
+        let lists = section_lists(snapshot);
+        let mut i = 0;
+        self.visit_section_notes_mut(&mut |_, current| {
+            let snapshot = &lists[i].1.notes;
+            i += 1;
+            for (k, note) in snapshot.iter().enumerate() {
+                if !notes.contains(&note.id) {
+                    continue;
+                }
+                match current.notes.iter_mut().find(|other| other.id == note.id) {
+                    Some(other) => *other = note.clone(),
+                    None => {
+                        let position = snapshot[..k]
+                            .iter()
+                            .rev()
+                            .find_map(|previous| {
+                                current
+                                    .notes
+                                    .iter()
+                                    .position(|other| other.id == previous.id)
+                            })
+                            .map_or(0, |j| j + 1);
+                        current.notes.insert(position, note.clone());
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl Workbook {
+    pub fn show_restore(&mut self, ctx: &Context) {
+        if !self.windows.restore_open {
+            return;
+        }
+        if self.windows.restore.snapshot >= self.stored_projects.len() {
+            self.windows.restore_open = false;
+            return;
+        }
+
+        let mut restoring = std::mem::take(&mut self.windows.restore);
+        let mut open = self.windows.restore_open;
+        let mut apply = false;
+        egui::Window::new("Restore from Snapshot")
+            .open(&mut open)
+            .default_width(480.)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Snapshot:");
+                    egui::ComboBox::from_id_source("restore_snapshot")
+                        .selected_text(self.version_name(Version::Snapshot(restoring.snapshot)))
+                        .show_ui(ui, |ui| {
+                            for i in (0..self.stored_projects.len()).rev() {
+                                let name = self.version_name(Version::Snapshot(i));
+                                if ui
+                                    .selectable_value(&mut restoring.snapshot, i, name)
+                                    .clicked()
+                                {
+                                    restoring.notes.clear();
+                                }
+                            }
+                        });
+                });
+                ui.separator();
+
+                let snapshot = &self.stored_projects[restoring.snapshot].project;
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .max_height(420.)
+                    .show(ui, |ui| {
+                        egui::CollapsingHeader::new("Sections")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for segment in &snapshot.index_list {
+                                    let section = &segment.variety;
+                                    let mut selected = restoring.sections.contains(section);
+                                    if ui
+                                        .checkbox(&mut selected, snapshot.section_title(section))
+                                        .changed()
+                                    {
+                                        if selected {
+                                            restoring.sections.push(section.clone());
+                                        } else {
+                                            restoring.sections.retain(|other| other != section);
+                                        }
+                                    }
+                                }
+                            });
+
+                        let current = section_lists(&self.project);
+                        let lists = section_lists(snapshot);
+                        let changed: usize = lists
+                            .iter()
+                            .zip(current.iter())
+                            .map(|((_, notes), (_, current))| differences(notes, current).len())
+                            .sum();
+                        egui::CollapsingHeader::new(format!("Notes ({})", changed))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                if changed == 0 {
+                                    ui.label("The notes are the same as in the current project.");
+                                }
+                                for ((name, notes), (_, current)) in
+                                    lists.iter().zip(current.iter())
+                                {
+                                    let differences = differences(notes, current);
+                                    if differences.is_empty() {
+                                        continue;
+                                    }
+                                    ui.label(RichText::new(*name).strong());
+                                    for difference in differences {
+                                        let id = difference.note.id;
+                                        let mut selected = restoring.notes.contains(&id);
+                                        let status = if difference.deleted {
+                                            "deleted"
+                                        } else {
+                                            "changed"
+                                        };
+                                        ui.horizontal_top(|ui| {
+                                            if ui.checkbox(&mut selected, "").changed() {
+                                                if selected {
+                                                    restoring.notes.insert(id);
+                                                } else {
+                                                    restoring.notes.remove(&id);
+                                                }
+                                            }
+                                            ui.label(RichText::new(status).small().weak());
+                                            ui.label(&difference.note.note);
+                                        });
+                                    }
+                                    ui.add_space(6.);
+                                }
+                            });
+                    });
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    let chosen = !restoring.sections.is_empty() || !restoring.notes.is_empty();
+                    if ui
+                        .add_enabled(chosen, egui::Button::new("Restore"))
+                        .on_hover_text(
+                            "Replace the chosen sections and notes of the current project \
+                            with the ones of the snapshot",
+                        )
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                });
+            });
+
+        if apply {
+            let snapshot = &self.stored_projects[restoring.snapshot].project;
+            for section in &restoring.sections {
+                self.project.restore_section(snapshot, section);
+            }
+            self.project.restore_notes(snapshot, &restoring.notes);
+            self.project.identify();
+            restoring = Restoring::with(restoring.snapshot);
+        }
+        self.windows.restore_open = open;
+        self.windows.restore = restoring;
+    }
+}
+
+// Test that the chosen sections and notes of the snapshot are restored.
+#[test]
+fn restore_from_snapshot() {
+    let note = |text: &str| Note {
+        note: text.to_string(),
+        ..Note::new()
+    };
+    let mut snapshot = Project::default();
+    snapshot.idea.problem.notes = vec![note("First."), note("Second."), note("Third.")];
+    snapshot.methodology.title = "Methods".to_string();
+    snapshot.scope.tasks.notes = vec![note("Task.")];
+
+    let mut project = snapshot.clone();
+    project.idea.problem.notes.remove(1);
+    project.idea.problem.notes[1].note = "Third, edited.".to_string();
+    project.idea.problem.notes.push(note("Fourth."));
+    project.methodology.title = "Approach".to_string();
+    project.scope.tasks.notes.clear();
+
+    assert_eq!(
+        differences(&snapshot.idea.problem, &project.idea.problem).len(),
+        2
+    );
+    let chosen = HashSet::from([snapshot.idea.problem.notes[1].id]);
+    project.restore_notes(&snapshot, &chosen);
+    project.restore_section(&snapshot, &Variety::Methodology);
+
+    let texts: Vec<&str> = project
+        .idea
+        .problem
+        .notes
+        .iter()
+        .map(|note| note.note.as_str())
+        .collect();
+    assert_eq!(texts, ["First.", "Second.", "Third, edited.", "Fourth."]);
+    assert_eq!(project.methodology.title, "Methods");
+    assert!(project.scope.tasks.notes.is_empty());
+    assert_eq!(project.section_title(&Variety::Idea), "Idea");
+}
+
+// Test that restoring a section keeps the references of the other section.
+#[test]
+fn restore_references() {
+    let reference = |title: &str, visible: bool| Reference {
+        title: title.to_string(),
+        visible,
+        ..Default::default()
+    };
+    let mut snapshot = Project::default();
+    snapshot.bibliography.references = vec![reference("Survey", true), reference("Key", false)];
+    snapshot.bibliography.key_references = vec![snapshot.bibliography.references[1].id];
+
+    let mut project = snapshot.clone();
+    project.bibliography.references[0].title = "Survey, edited".to_string();
+    project.bibliography.references.remove(1);
+    project.bibliography.key_references.clear();
+    let added = reference("Key, added", false);
+    project.bibliography.key_references.push(added.id);
+    project.bibliography.references.push(added.clone());
+    project
+        .bibliography
+        .references
+        .push(reference("Listed", true));
+
+    // The Literature Survey of the snapshot, the key reference added is kept.
+    let mut restored = project.clone();
+    restored.restore_section(&snapshot, &Variety::Literature);
+    let titles = |project: &Project| -> Vec<String> {
+        let references = &project.bibliography.references;
+        references.iter().map(|r| r.title.clone()).collect()
+    };
+    assert_eq!(titles(&restored), ["Survey", "Key", "Key, added"]);
+    assert_eq!(restored.bibliography.key_references, vec![added.id]);
+
+    // The key references of the snapshot, the Literature Survey is kept.
+    let mut restored = project.clone();
+    restored.restore_section(&snapshot, &Variety::Idea);
+    assert_eq!(
+        titles(&restored),
+        ["Survey, edited", "Key, added", "Listed", "Key"]
+    );
+    assert!(!restored.bibliography.references[3].visible);
+    assert_eq!(
+        restored.bibliography.key_references,
+        snapshot.bibliography.key_references
+    );
+}
@@ -0,0 +1,1059 @@
+// Project-wide search (Ctrl+F) and replacement in the titles of sections,
+// the notes, the participants, the programmes and the references. The selected
+// match is revealed in the editor: its section is expanded and scrolled to.
+
+use std::ops::Range;
+
+use egui::collapsing_header::CollapsingState;
+use egui::{Align, Context, Id, Key, KeyboardShortcut, Modifiers, Response, RichText, Ui};
+
+use super::app::Workbook;
+use super::chapter::{Segment, Variety};
+use super::note::Notes;
+use super::project::Project;
+use super::sections::budget::{Budget, SectionBudget};
+use super::sections::funding::FundingOptions;
+use super::sections::idea::{Idea, SectionIdea};
+use super::sections::literature::Literature;
+use super::sections::methodology::Methodology;
+use super::sections::outcomes::{Outcomes, SectionOutcomes};
+use super::sections::prelim_results::PrelimResults;
+use super::sections::references::Bibliography;
+use super::sections::resources::Resources;
+use super::sections::scope::{Scope, SectionScope};
+use super::sections::scripting::Scripting;
+use super::sections::team::{ModuleParticipant, SectionTeam, Team};
+use super::sections::timeline::{SectionTimeline, Timeline};
+
+pub const SEARCH_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::F);
+
+// The number of frames the selected match is waiting for its field to be shown.
+const JUMP_FRAMES: usize = 10;
+
+// The location of a text field in the editor.
+#[derive(Clone, PartialEq)]
+pub struct Location {
+    // The section of the editor.
+    pub section: Variety,
+    // The participant, programme or reference in the section.
+    pub item: Option<usize>,
+    // The collapsing group of fields in the item, e.g. "Bibliographic data".
+    pub group: &'static str,
+    // The level of details the field is shown at.
+    pub tier: usize,
+    // "Team › Jane Doe › Short CV", as shown in the list of matches.
+    pub path: String,
+    // The links and the identifiers are found but left out of the replacement.
+    pub verbatim: bool,
+}
+
+impl Location {
+    fn section(section: Variety, tier: usize, title: &str, default_title: String) -> Self {
+        Location {
+            section,
+            item: None,
+            group: "",
+            tier,
+            verbatim: false,
+            path: if title.is_empty() {
+                default_title
+            } else {
+                title.to_string()
+            },
+        }
+    }
+
+    fn child(&self, name: &str) -> Self {
+        Location {
+            path: format!("{} › {}", self.path, name),
+            ..self.clone()
+        }
+    }
+
+    fn item(&self, item: usize, name: &str) -> Self {
+        Location {
+            item: Some(item),
+            ..self.child(name)
+        }
+    }
+
+    fn group(&self, group: &'static str) -> Self {
+        Location {
+            group,
+            ..self.child(group)
+        }
+    }
+
+    fn tier(&self, index_list: &[Segment], variety: Variety) -> Self {
+        let tier = index_list
+            .iter()
+            .find(|segment| segment.variety == variety)
+            .map_or(1, |segment| segment.tier);
+        Location {
+            tier: self.tier.max(tier),
+            ..self.clone()
+        }
+    }
+}
+
+type Visit<'a> = dyn FnMut(&Location, &String) + 'a;
+type VisitMut<'a> = dyn FnMut(&Location, &mut String) + 'a;
+
+// The text fields are visited by reference or by mutable reference `T`.
+fn field<T, F>(visit: &mut F, location: &Location, name: &str, text: T)
+where
+    F: FnMut(&Location, T) + ?Sized,
+{
+    visit(&location.child(name), text);
+}
+
+// The link or the identifier, e.g. the DOI, which is not replaced.
+fn verbatim<T, F>(visit: &mut F, location: &Location, name: &str, text: T)
+where
+    F: FnMut(&Location, T) + ?Sized,
+{
+    let location = Location {
+        verbatim: true,
+        ..location.child(name)
+    };
+    visit(&location, text);
+}
+
+// The notes visited by reference or by mutable reference.
+trait NoteTexts {
+    type Text;
+    fn texts(self) -> Vec<Self::Text>;
+}
+
+impl<'a> NoteTexts for &'a Notes {
+    type Text = &'a String;
+    fn texts(self) -> Vec<&'a String> {
+        self.notes.iter().map(|note| &note.note).collect()
+    }
+}
+
+impl<'a> NoteTexts for &'a mut Notes {
+    type Text = &'a mut String;
+    fn texts(self) -> Vec<&'a mut String> {
+        self.notes.iter_mut().map(|note| &mut note.note).collect()
+    }
+}
+
+fn notes<N, F>(visit: &mut F, location: &Location, name: &str, notes: N)
+where
+    N: NoteTexts,
+    F: FnMut(&Location, N::Text) + ?Sized,
+{
+    let texts = notes.texts();
+    let several = texts.len() > 1;
+    for (i, text) in texts.into_iter().enumerate() {
+        if several {
+            visit(&location.child(&format!("{} › Note {}", name, i + 1)), text);
+        } else {
+            visit(&location.child(name), text);
+        }
+    }
+}
+
+// `visit_texts(&self)` and `visit_texts_mut(&mut self)`, the same fields
+// in the same order, e.g. for the search and for the replacement.
+macro_rules! visit_texts {
+    ($method:ident, $visit:ident, $iter:ident $(, $mutability:ident)?) => {
+        impl Project {
+            // Visit the text fields of the project in the order of the sections.
+            pub fn $method(&$($mutability)? self, visit: &mut $visit) {
+                for section in self.index_list.clone() {
+                    let tier = section.tier;
+                    match section.variety {
+                        Variety::WorkingName => {
+                            let working_name = &$($mutability)? self.working_name;
+                            let at = Location::section(
+                                Variety::WorkingName,
+                                tier,
+                                "",
+                                "Project Title".to_string(),
+                            );
+                            field(visit, &at, "Working name", &$($mutability)? working_name.title);
+                            notes(
+                                visit,
+                                &at,
+                                "Alternative titles",
+                                &$($mutability)? working_name.options,
+                            );
+                        }
+                        Variety::Funding => {
+                            let funding = &$($mutability)? self.funding;
+                            let at = Location::section(
+                                Variety::Funding,
+                                tier,
+                                &funding.title,
+                                FundingOptions::default().title,
+                            );
+                            field(visit, &at, "Section title", &$($mutability)? funding.title);
+                            for (i, programme) in funding.funding_options.$iter().enumerate() {
+                                let at = at.item(i, &item_name(&programme.title, "Programme", i));
+                                field(visit, &at, "Name", &$($mutability)? programme.title);
+                                verbatim(visit, &at, "Link", &$($mutability)? programme.hyperlink);
+                                notes(
+                                    visit,
+                                    &at,
+                                    "Annotation",
+                                    &$($mutability)? programme.annotation,
+                                );
+                            }
+                        }
+                        Variety::Idea => {
+                            let idea = &$($mutability)? self.idea;
+                            let at = Location::section(
+                                Variety::Idea,
+                                tier,
+                                &idea.title,
+                                Idea::default().title,
+                            );
+                            let at_chapter =
+                                |chapter| at.tier(&idea.index_list, Variety::SectionIdea(chapter));
+                            field(visit, &at, "Section title", &$($mutability)? idea.title);
+                            for (chapter, name, list) in [
+                                (SectionIdea::Problem, "Problem", &$($mutability)? idea.problem),
+                                (
+                                    SectionIdea::Hypothesis,
+                                    "Hypothesis",
+                                    &$($mutability)? idea.hypothesis,
+                                ),
+                                (SectionIdea::Abstract, "Abstract", &$($mutability)? idea.summary),
+                                (
+                                    SectionIdea::ProjectDescription,
+                                    "Description",
+                                    &$($mutability)? idea.description,
+                                ),
+                            ] {
+                                notes(visit, &at_chapter(chapter), name, list);
+                            }
+                            field(
+                                visit,
+                                &at_chapter(SectionIdea::KeyReferences),
+                                "Key References title",
+                                &$($mutability)? idea.references.title,
+                            );
+                        }
+                        Variety::Timeline => {
+                            let timeline = &$($mutability)? self.timeline;
+                            let at = Location::section(
+                                Variety::Timeline,
+                                tier,
+                                &timeline.title,
+                                Timeline::default().title,
+                            );
+                            field(visit, &at, "Section title", &$($mutability)? timeline.title);
+                            let milestones = at.tier(
+                                &timeline.index_list,
+                                Variety::SectionTimeline(SectionTimeline::Milestones),
+                            );
+                            notes(
+                                visit,
+                                &milestones,
+                                "Milestones",
+                                &$($mutability)? timeline.milestones,
+                            );
+                        }
+                        Variety::Scope => {
+                            let scope = &$($mutability)? self.scope;
+                            let at = Location::section(
+                                Variety::Scope,
+                                tier,
+                                &scope.title,
+                                Scope::default().title,
+                            );
+                            field(visit, &at, "Section title", &$($mutability)? scope.title);
+                            for (chapter, name, list) in [
+                                (
+                                    SectionScope::SuggestedTasks,
+                                    "Suggested tasks",
+                                    &$($mutability)? scope.suggested_tasks,
+                                ),
+                                (
+                                    SectionScope::Objectives,
+                                    "Objectives",
+                                    &$($mutability)? scope.objectives,
+                                ),
+                                (
+                                    SectionScope::Activities,
+                                    "Activities",
+                                    &$($mutability)? scope.activities,
+                                ),
+                                (
+                                    SectionScope::WorkPlan,
+                                    "Work plan",
+                                    &$($mutability)? scope.work_plan,
+                                ),
+                                (SectionScope::Tasks, "Tasks", &$($mutability)? scope.tasks),
+                            ] {
+                                let at = at.tier(&scope.index_list, Variety::SectionScope(chapter));
+                                notes(visit, &at, name, list);
+                            }
+                        }
+                        Variety::Scripting => {
+                            let scripting = &$($mutability)? self.scripting;
+                            let at = Location::section(
+                                Variety::Scripting,
+                                tier,
+                                &scripting.title,
+                                Scripting::default().title,
+                            );
+                            field(visit, &at, "Section title", &$($mutability)? scripting.title);
+                            notes(visit, &at, "Details", &$($mutability)? scripting.scripting);
+                        }
+                        Variety::Team => self.team.$method(visit, tier),
+                        Variety::PrelimResults => {
+                            let results = &$($mutability)? self.prelim_results;
+                            let at = Location::section(
+                                Variety::PrelimResults,
+                                tier,
+                                &results.title,
+                                PrelimResults::default().title,
+                            );
+                            field(visit, &at, "Section title", &$($mutability)? results.title);
+                            notes(visit, &at, "Results", &$($mutability)? results.prelim_results);
+                        }
+                        Variety::Methodology => {
+                            let methodology = &$($mutability)? self.methodology;
+                            let at = Location::section(
+                                Variety::Methodology,
+                                tier,
+                                &methodology.title,
+                                Methodology::default().title,
+                            );
+                            field(visit, &at, "Section title", &$($mutability)? methodology.title);
+                            notes(
+                                visit,
+                                &at,
+                                "Methodology",
+                                &$($mutability)? methodology.methodology,
+                            );
+                        }
+                        Variety::Outcomes => {
+                            let outcomes = &$($mutability)? self.outcomes;
+                            let at = Location::section(
+                                Variety::Outcomes,
+                                tier,
+                                &outcomes.title,
+                                Outcomes::default().title,
+                            );
+                            field(visit, &at, "Section title", &$($mutability)? outcomes.title);
+                            for (chapter, name, list) in [
+                                (
+                                    SectionOutcomes::ExpectedResults,
+                                    "Expected results",
+                                    &$($mutability)? outcomes.results,
+                                ),
+                                (
+                                    SectionOutcomes::Impact,
+                                    "Impact",
+                                    &$($mutability)? outcomes.impact,
+                                ),
+                                (
+                                    SectionOutcomes::Propagation,
+                                    "Dissemination",
+                                    &$($mutability)? outcomes.propagation,
+                                ),
+                            ] {
+                                let at = at.tier(
+                                    &outcomes.index_list,
+                                    Variety::SectionOutcomes(chapter),
+                                );
+                                notes(visit, &at, name, list);
+                            }
+                        }
+                        Variety::Literature => {
+                            let literature = &$($mutability)? self.literature;
+                            let at = Location::section(
+                                Variety::Literature,
+                                tier,
+                                &literature.title,
+                                Literature::default().title,
+                            );
+                            field(visit, &at, "Section title", &$($mutability)? literature.title);
+                            notes(
+                                visit,
+                                &at,
+                                "Survey",
+                                &$($mutability)? literature.literature_survey,
+                            );
+                            self.bibliography.$method(visit, &at);
+                        }
+                        Variety::Resources => {
+                            let resources = &$($mutability)? self.resources;
+                            let at = Location::section(
+                                Variety::Resources,
+                                tier,
+                                &resources.title,
+                                Resources::default().title,
+                            );
+                            field(visit, &at, "Section title", &$($mutability)? resources.title);
+                            notes(visit, &at, "Existing", &$($mutability)? resources.existing);
+                            notes(visit, &at, "Additional", &$($mutability)? resources.further);
+                        }
+                        Variety::Budget => {
+                            let budget = &$($mutability)? self.budget;
+                            let at = Location::section(
+                                Variety::Budget,
+                                tier,
+                                &budget.title,
+                                Budget::default().title,
+                            );
+                            field(visit, &at, "Section title", &$($mutability)? budget.title);
+                            for (chapter, name, list) in [
+                                (
+                                    SectionBudget::Personnel,
+                                    "Personnel",
+                                    &$($mutability)? budget.personnel,
+                                ),
+                                (
+                                    SectionBudget::Facilities,
+                                    "Facilities",
+                                    &$($mutability)? budget.facilities,
+                                ),
+                                (
+                                    SectionBudget::Materials,
+                                    "Materials",
+                                    &$($mutability)? budget.materials,
+                                ),
+                                (
+                                    SectionBudget::Workshops,
+                                    "Workshops",
+                                    &$($mutability)? budget.workshops,
+                                ),
+                                (
+                                    SectionBudget::Overheads,
+                                    "Overheads",
+                                    &$($mutability)? budget.overheads,
+                                ),
+                                (
+                                    SectionBudget::Miscellaneous,
+                                    "Miscellaneous",
+                                    &$($mutability)? budget.misc,
+                                ),
+                            ] {
+                                let at = at.tier(
+                                    &budget.index_list,
+                                    Variety::SectionBudget(chapter),
+                                );
+                                notes(visit, &at, name, list);
+                            }
+                        }
+                        // The attachments are not edited yet.
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        impl Team {
+            fn $method(&$($mutability)? self, visit: &mut $visit, tier: usize) {
+                let at = Location::section(Variety::Team, tier, &self.title, Team::default().title);
+                field(visit, &at, "Section title", &$($mutability)? self.title);
+                for (chapter, name, list) in [
+                    (
+                        SectionTeam::ProposedPartners,
+                        "Proposed partners",
+                        &$($mutability)? self.proposed_partners,
+                    ),
+                    (
+                        SectionTeam::ProjectLeader,
+                        "Project leader",
+                        &$($mutability)? self.project_leader,
+                    ),
+                    (
+                        SectionTeam::IndustrialPartners,
+                        "Industrial partners",
+                        &$($mutability)? self.industrial_partners,
+                    ),
+                    (SectionTeam::Proponents, "Cooperation", &$($mutability)? self.proponents),
+                ] {
+                    notes(
+                        visit,
+                        &at.tier(&self.index_list, Variety::SectionTeam(chapter)),
+                        name,
+                        list,
+                    );
+                }
+
+                let effort = at.tier(&self.index_list, Variety::SectionTeam(SectionTeam::Effort));
+                let at = at.tier(
+                    &self.index_list,
+                    Variety::SectionTeam(SectionTeam::Participants),
+                );
+                for (i, participant) in self.participants.$iter().enumerate() {
+                    let at = at.item(i, &item_name(&participant.name, "Participant", i));
+                    field(visit, &at, "Name", &$($mutability)? participant.name);
+                    field(visit, &at, "Role", &$($mutability)? participant.role);
+                    field(visit, &at, "Affiliation", &$($mutability)? participant.affiliation);
+                    verbatim(visit, &at, "Web address", &$($mutability)? participant.hyperlink);
+                    field(visit, &at, "Country", &$($mutability)? participant.country);
+                    field(visit, &at, "Expertise", &$($mutability)? participant.expertise);
+                    let cv = at.tier(
+                        &participant.index_list,
+                        Variety::SectionTeam(SectionTeam::ModuleParticipant(ModuleParticipant::CV)),
+                    );
+                    for (j, entry) in participant.cv_entries.$iter().enumerate() {
+                        let name = format!("CV › Entry {}", j + 1);
+                        field(visit, &cv, &name, &$($mutability)? entry.title);
+                        field(visit, &cv, &name, &$($mutability)? entry.place);
+                    }
+                    let budget = at.tier(
+                        &participant.index_list,
+                        Variety::SectionTeam(
+                            SectionTeam::ModuleParticipant(ModuleParticipant::Budget),
+                        ),
+                    );
+                    for (j, cost) in participant.costs.$iter().enumerate() {
+                        let name = format!("Costs › Cost {}", j + 1);
+                        field(visit, &budget, &name, &$($mutability)? cost.description);
+                    }
+                    for (module, name, list) in [
+                        (
+                            ModuleParticipant::Contribution,
+                            "Contribution",
+                            &$($mutability)? participant.contribution,
+                        ),
+                        (ModuleParticipant::Team, "Team", &$($mutability)? participant.team),
+                        (ModuleParticipant::CV, "Short CV", &$($mutability)? participant.cv),
+                        (
+                            ModuleParticipant::Resources,
+                            "Resources",
+                            &$($mutability)? participant.resources,
+                        ),
+                        (ModuleParticipant::Budget, "Budget", &$($mutability)? participant.budget),
+                    ] {
+                        let chapter = Variety::SectionTeam(SectionTeam::ModuleParticipant(module));
+                        notes(
+                            visit,
+                            &at.tier(&participant.index_list, chapter),
+                            name,
+                            list,
+                        );
+                    }
+                }
+                for (i, package) in self.work_packages.$iter().enumerate() {
+                    let name = format!("Work Package {}", i + 1);
+                    field(visit, &effort, &name, &$($mutability)? package.title);
+                }
+            }
+        }
+
+        impl Bibliography {
+            // The references are edited in the Literature Survey.
+            fn $method(&$($mutability)? self, visit: &mut $visit, at: &Location) {
+                for (i, reference) in self.references.$iter().enumerate() {
+                    let at = at.item(i, &item_name(&reference.title, "Reference", i));
+                    field(visit, &at, "Title", &$($mutability)? reference.title);
+                    verbatim(visit, &at, "Link", &$($mutability)? reference.hyperlink);
+
+                    let data = &$($mutability)? reference.metadata;
+                    let metadata = at.group("Bibliographic data");
+                    for (name, text) in [
+                        ("Authors", &$($mutability)? data.authors),
+                        ("Year", &$($mutability)? data.year),
+                        ("Published in", &$($mutability)? data.container),
+                        ("Volume", &$($mutability)? data.volume),
+                        ("Issue", &$($mutability)? data.issue),
+                        ("Pages", &$($mutability)? data.pages),
+                        ("Publisher", &$($mutability)? data.publisher),
+                        ("Abstract", &$($mutability)? data.abstract_text),
+                    ] {
+                        visit(&metadata.child(name), text);
+                    }
+                    verbatim(visit, &metadata, "DOI", &$($mutability)? data.doi);
+                    verbatim(visit, &metadata, "Citation key", &$($mutability)? data.key);
+
+                    let reading = at.group("Reading notes");
+                    field(visit, &reading, "Tags", &$($mutability)? reference.reading.tags);
+                    field(
+                        visit,
+                        &reading,
+                        "Annotation",
+                        &$($mutability)? reference.reading.annotation,
+                    );
+
+                    notes(visit, &at, "Source details", &$($mutability)? reference.source_details);
+                }
+            }
+        }
+    };
+}
+
+visit_texts!(visit_texts, Visit, iter);
+visit_texts!(visit_texts_mut, VisitMut, iter_mut, mut);
+
+fn item_name(title: &str, kind: &str, i: usize) -> String {
+    if title.trim().is_empty() {
+        format!("{} {}", kind, i + 1)
+    } else {
+        title.trim().to_string()
+    }
+}
+
+// The text to find in the fields, with the options of the search.
+#[derive(Clone, Default, PartialEq)]
+pub struct Pattern {
+    pub query: String,
+    pub match_case: bool,
+    // Only the occurrences which are not a part of a longer word.
+    pub whole_word: bool,
+}
+
+impl Pattern {
+    // The byte ranges of the occurrences of the query in the text.
+    pub fn find(&self, text: &str) -> Vec<Range<usize>> {
+        let query: Vec<char> = self.query.chars().collect();
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let same =
+            |a: char, b: char| a == b || !self.match_case && a.to_lowercase().eq(b.to_lowercase());
+        let is_word = |i: usize| {
+            chars
+                .get(i)
+                .is_some_and(|(_, c)| c.is_alphanumeric() || *c == '_')
+        };
+
+        let mut found = Vec::new();
+        if query.is_empty() {
+            return found;
+        }
+        let mut i = 0;
+        while i + query.len() <= chars.len() {
+            let end = i + query.len();
+            if (0..query.len()).all(|k| same(chars[i + k].1, query[k]))
+                && (!self.whole_word || (i == 0 || !is_word(i - 1)) && !is_word(end))
+            {
+                found.push(chars[i].0..chars.get(end).map_or(text.len(), |(byte, _)| *byte));
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+        found
+    }
+
+    pub fn replace(&self, text: &str, replacement: &str) -> String {
+        let mut replaced = String::new();
+        let mut last = 0;
+        for range in self.find(text) {
+            replaced.push_str(&text[last..range.start]);
+            replaced.push_str(replacement);
+            last = range.end;
+        }
+        replaced.push_str(&text[last..]);
+        replaced
+    }
+}
+
+// A text field containing the query.
+#[derive(Clone)]
+pub struct Match {
+    pub location: Location,
+    // The text of the field.
+    pub text: String,
+    // The number of occurrences in the field.
+    pub count: usize,
+    // The text around the first occurrence of the query.
+    pub excerpt: String,
+    // The same text after the replacement, the excerpt in the verbatim fields.
+    pub replaced: String,
+}
+
+// The state of the search window.
+#[derive(Default)]
+pub struct Search {
+    pub pattern: Pattern,
+    pub replacement: String,
+    replacing: bool,
+    matches: Vec<Match>,
+    // The pattern, the replacement and the fingerprint of the project the matches
+    // are found for: the project is searched again only after one of them is changed.
+    found: Option<(Pattern, String, u64)>,
+    selected: Option<usize>,
+    focus: bool,
+    // The project before the last replacement and the number of replaced occurrences.
+    undo: Option<(Box<Project>, usize)>,
+}
+
+impl Search {
+    // The replacement cannot be undone in another project, i.e. after the project
+    // is replaced by another tab, the file opened, the snapshot restored or the merge.
+    pub fn forget_undo(&mut self) {
+        self.undo = None;
+    }
+
+    pub fn find(project: &Project, pattern: &Pattern, replacement: &str) -> Vec<Match> {
+        let mut matches = Vec::new();
+        if pattern.query.trim().is_empty() {
+            return matches;
+        }
+        project.visit_texts(&mut |location, text| {
+            let found = pattern.find(text);
+            if let Some(first) = found.first() {
+                let excerpt_text = excerpt(text, first.clone());
+                let replaced = if location.verbatim {
+                    excerpt_text.clone()
+                } else {
+                    let start = first.start;
+                    let replaced = pattern.replace(text, replacement);
+                    excerpt(&replaced, start..start + replacement.len())
+                };
+                matches.push(Match {
+                    location: location.clone(),
+                    text: text.clone(),
+                    count: found.len(),
+                    excerpt: excerpt_text,
+                    replaced,
+                });
+            }
+        });
+        matches
+    }
+
+    // Replace all occurrences in the project but in the links and the identifiers,
+    // returns the number of replaced occurrences.
+    pub fn replace_all(project: &mut Project, pattern: &Pattern, replacement: &str) -> usize {
+        let mut count = 0;
+        if pattern.query.trim().is_empty() {
+            return count;
+        }
+        project.visit_texts_mut(&mut |location, text| {
+            if location.verbatim {
+                return;
+            }
+            let found = pattern.find(text).len();
+            if found > 0 {
+                *text = pattern.replace(text, replacement);
+                count += found;
+            }
+        });
+        count
+    }
+}
+
+// The words around the range of the text.
+fn excerpt(text: &str, range: Range<usize>) -> String {
+    const CONTEXT: usize = 40;
+
+    let from = text[..range.start]
+        .char_indices()
+        .rev()
+        .nth(CONTEXT - 1)
+        .map_or(0, |(i, _)| i);
+    let to = text[range.end..]
+        .char_indices()
+        .nth(CONTEXT)
+        .map_or(text.len(), |(i, _)| range.end + i);
+    let mut excerpt = text[from..to]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if from > 0 {
+        excerpt.insert(0, '…');
+    }
+    if to < text.len() {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+// The selected match waiting to be revealed in the editor.
+#[derive(Clone)]
+pub struct Jump {
+    pub location: Location,
+    pub text: String,
+    frames: usize,
+}
+
+fn jump_id() -> Id {
+    Id::new("workbook_search_jump")
+}
+
+pub fn jump(ctx: &Context) -> Option<Jump> {
+    ctx.data(|data| data.get_temp(jump_id()))
+}
+
+// Expand the collapsing section or item containing the selected match.
+pub fn reveal(ctx: &Context, state: &mut CollapsingState, contains: impl Fn(&Location) -> bool) {
+    if jump(ctx).is_some_and(|jump| contains(&jump.location)) {
+        state.set_open(true);
+    }
+}
+
+// Scroll to the field of the selected match.
+pub fn scroll_to(ui: &Ui, response: &Response, text: &str) {
+    if jump(ui.ctx()).is_some_and(|jump| jump.text == text) {
+        response.scroll_to_me(Some(Align::Center));
+        response.request_focus();
+        ui.ctx().data_mut(|data| data.remove::<Jump>(jump_id()));
+    }
+}
+
+impl Workbook {
+    pub fn show_search(&mut self, ctx: &Context) {
+        if ctx.input_mut(|input| input.consume_shortcut(&SEARCH_SHORTCUT)) {
+            self.windows.search_open = true;
+            self.windows.search.focus = true;
+        }
+
+        // The selected match is revealed in a few frames or given up.
+        if let Some(mut jump) = jump(ctx) {
+            if jump.frames == 0 {
+                ctx.data_mut(|data| data.remove::<Jump>(jump_id()));
+            } else {
+                jump.frames -= 1;
+                ctx.data_mut(|data| data.insert_temp(jump_id(), jump));
+                ctx.request_repaint();
+            }
+        }
+
+        let mut open = self.windows.search_open;
+        let search = &mut self.windows.search;
+        let mut selected = None;
+        egui::Window::new("Search")
+            .open(&mut open)
+            .default_width(360.)
+            .resizable(true)
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut search.pattern.query)
+                        .hint_text("Notes, titles, participants, programmes, references")
+                        .desired_width(f32::INFINITY),
+                );
+                if search.focus {
+                    response.request_focus();
+                    search.focus = false;
+                }
+                if response.changed() {
+                    search.selected = None;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut search.pattern.match_case, "Match case");
+                    ui.checkbox(&mut search.pattern.whole_word, "Whole word");
+                    ui.toggle_value(&mut search.replacing, "Replace…");
+                });
+
+                let replacement = if search.replacing {
+                    search.replacement.as_str()
+                } else {
+                    ""
+                };
+                // The matches follow the changes of the project.
+                let found = (search.pattern.clone(), replacement.to_string(), self.cited);
+                if search.found.as_ref() != Some(&found) {
+                    search.matches = Search::find(&self.project, &search.pattern, replacement);
+                    search.found = Some(found);
+                }
+                let count: usize = search.matches.iter().map(|found| found.count).sum();
+                let replaceable: usize = search
+                    .matches
+                    .iter()
+                    .filter(|found| !found.location.verbatim)
+                    .map(|found| found.count)
+                    .sum();
+
+                if search.replacing {
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut search.replacement)
+                                .hint_text("Replace with")
+                                .desired_width(200.),
+                        );
+                        if ui
+                            .add_enabled(replaceable > 0, egui::Button::new("Replace All"))
+                            .on_hover_text(
+                                "Replace the occurrences in all listed locations\n\
+                                but in the links, the DOIs and the citation keys",
+                            )
+                            .clicked()
+                        {
+                            let previous = Box::new(self.project.clone());
+                            let replaced = Search::replace_all(
+                                &mut self.project,
+                                &search.pattern,
+                                &search.replacement,
+                            );
+                            search.undo = Some((previous, replaced));
+                            search.found = None;
+                            search.selected = None;
+                        }
+                    });
+                }
+                if let Some((_, replaced)) = &search.undo {
+                    if ui
+                        .button(format!("Undo Replace ({})", replaced))
+                        .on_hover_text(
+                            "Restore the project as it was before the replacement.\n\
+                            ALERT: The changes made after the replacement are lost as well!",
+                        )
+                        .clicked()
+                    {
+                        if let Some((previous, _)) = search.undo.take() {
+                            self.project = *previous;
+                            search.found = None;
+                        }
+                    }
+                }
+
+                if search.pattern.query.trim().is_empty() {
+                    return;
+                }
+                ui.label(
+                    RichText::new(match count {
+                        0 => "No matches".to_string(),
+                        1 => "1 match".to_string(),
+                        _ => format!("{} matches in {} fields", count, search.matches.len()),
+                    })
+                    .small(),
+                );
+
+                let next = response.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter));
+                let fields = search.matches.len();
+                if next && fields > 0 {
+                    let i = search.selected.map_or(0, |i| (i + 1) % fields);
+                    search.selected = Some(i);
+                    selected = Some(search.matches[i].clone());
+                    response.request_focus();
+                }
+
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(420.)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for (i, found) in search.matches.iter().enumerate() {
+                            let path = if found.count > 1 {
+                                format!("{} ({})", found.location.path, found.count)
+                            } else {
+                                found.location.path.clone()
+                            };
+                            ui.label(RichText::new(path).small().weak());
+                            let label =
+                                ui.selectable_label(search.selected == Some(i), &found.excerpt);
+                            if next && search.selected == Some(i) {
+                                label.scroll_to_me(None);
+                            }
+                            if label.clicked() {
+                                search.selected = Some(i);
+                                selected = Some(found.clone());
+                            }
+                            // The preview of the replacement.
+                            if search.replacing && found.location.verbatim {
+                                ui.label(RichText::new("Not replaced").small().weak());
+                            } else if search.replacing {
+                                ui.label(
+                                    RichText::new(format!("→ {}", found.replaced))
+                                        .color(ui.visuals().warn_fg_color),
+                                );
+                            }
+                            ui.add_space(4.);
+                        }
+                    });
+            });
+        self.windows.search_open = open;
+
+        if let Some(found) = selected {
+            // The match may be in the sections hidden at the current level of details.
+            self.project.resolution = self.project.resolution.max(found.location.tier.min(4));
+            let jump = Jump {
+                location: found.location,
+                text: found.text,
+                frames: JUMP_FRAMES,
+            };
+            ctx.data_mut(|data| data.insert_temp(jump_id(), jump));
+        }
+    }
+}
+
+// Test that the matches are listed with the paths of their sections.
+#[test]
+fn project_search() {
+    use super::note::Note;
+    use super::sections::references::Reference;
+    use super::sections::team::Participant;
+
+    let mut project = Project::default();
+    project.idea.problem.notes.push(Note {
+        note: "The pilot study showed that the approach works.".to_string(),
+        ..Note::new()
+    });
+    project.team.participants.push(Participant {
+        name: "Jane Doe".to_string(),
+        expertise: "Pilot studies".to_string(),
+        ..Default::default()
+    });
+    project.bibliography.references.push(Reference {
+        title: "A study of examples".to_string(),
+        ..Default::default()
+    });
+
+    let pattern = |query: &str| Pattern {
+        query: query.to_string(),
+        ..Default::default()
+    };
+    let matches = Search::find(&project, &pattern("PILOT"), "");
+    let paths: Vec<&str> = matches
+        .iter()
+        .map(|found| found.location.path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["Idea › Problem", "Team › Jane Doe › Expertise"]);
+    assert_eq!(matches[1].location.item, Some(0));
+    assert_eq!(matches[1].location.tier, 3);
+
+    let matches = Search::find(&project, &pattern("examples"), "");
+    assert!(matches[0].location.section == Variety::Literature);
+    assert_eq!(matches[0].location.tier, 4);
+
+    assert_eq!(
+        excerpt(&"word ".repeat(20), 0..4),
+        format!("{}…", "word ".repeat(9).trim())
+    );
+}
+
+// Test that the occurrences are replaced with the options of the pattern, but not in the links.
+#[test]
+fn find_and_replace() {
+    use super::sections::team::Participant;
+
+    let pattern = Pattern {
+        query: "ACME".to_string(),
+        match_case: true,
+        whole_word: true,
+    };
+    assert_eq!(
+        pattern.replace("ACME, Acme and ACMEs of ACME", "Ace"),
+        "Ace, Acme and ACMEs of Ace"
+    );
+    let pattern = Pattern {
+        match_case: false,
+        ..pattern
+    };
+    assert_eq!(
+        pattern.find("Acme – acme_x, ÄACME acme"),
+        vec![0..4, 24..28]
+    );
+
+    let mut project = Project::default();
+    project.team.participants.push(Participant {
+        name: "Jane Doe".to_string(),
+        affiliation: "Acme Institute".to_string(),
+        hyperlink: "https://acme.org".to_string(),
+        contribution: Notes {
+            notes: vec![super::note::Note {
+                note: "ACME leads, acme tests.".to_string(),
+                ..super::note::Note::new()
+            }],
+        },
+        ..Default::default()
+    });
+    let matches = Search::find(&project, &pattern, "Ace");
+    assert_eq!(matches[1].replaced, "https://acme.org");
+    assert_eq!(matches[2].replaced, "Ace leads, Ace tests.");
+    assert_eq!(Search::replace_all(&mut project, &pattern, "Ace"), 3);
+    assert_eq!(project.team.participants[0].affiliation, "Ace Institute");
+    // The links are not rewritten.
+    assert_eq!(project.team.participants[0].hyperlink, "https://acme.org");
+}
@@ -1,5 +1,8 @@
 pub mod attachments;
 pub mod budget;
+pub mod costs;
+pub mod cv;
+pub mod effort;
 pub mod funding;
 pub mod idea;
 pub mod literature;
@@ -5,9 +5,12 @@ use serde::{Deserialize, Serialize};
 use crate::workbook::chapter::{Segment, Variety};
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE};
+use crate::workbook::exporter::{Document, Exporter};
 use crate::workbook::note::{Note, Notes};
 use crate::workbook::renderer::Renderer;
 use crate::workbook::renderer::{SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW};
+use crate::workbook::search;
+use crate::workbook::sections::costs::{self, Summary};
 use crate::workbook::visuals::style_info_button;
 
 // SectionBudget reflects the essence (character, nature) of the contents
@@ -17,6 +20,7 @@ use crate::workbook::visuals::style_info_button;
 // which, being combined, make up the contents of the section.
 #[derive(Deserialize, Serialize, Clone, PartialEq)]
 pub enum SectionBudget {
+    Costs,
     Facilities,
     Materials,
     Miscellaneous,
@@ -47,6 +51,10 @@ impl Default for Budget {
         Self {
             title: String::from("Budget Estimates"),
             index_list: vec![
+                Segment {
+                    variety: Variety::SectionBudget(SectionBudget::Costs),
+                    tier: 2,
+                },
                 Segment {
                     variety: Variety::SectionBudget(SectionBudget::Personnel),
                     tier: 4,
@@ -84,7 +92,13 @@ impl Default for Budget {
 }
 
 impl Budget {
-    pub fn edit(&mut self, ui: &mut Ui, edit_section_titles: bool, resolution: usize) {
+    pub fn edit(
+        &mut self,
+        ui: &mut Ui,
+        edit_section_titles: bool,
+        resolution: usize,
+        summary: &Summary,
+    ) {
         let mut title = self.title.clone();
         if title.is_empty() {
             title = Self::default().title;
@@ -92,7 +106,11 @@ impl Budget {
         let id = ui.make_persistent_id("collapsing_header_budget");
 
         ui.add_space(SPACE_SECTIONS_EDITOR);
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::Budget
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
 
@@ -117,6 +135,20 @@ impl Budget {
                 for chapter in chapters {
                     if chapter.tier > resolution { continue; } else {
                         match chapter.variety {
+                            Variety::SectionBudget(SectionBudget::Costs) => {
+                                ui.add_space(SPACE_INTERNAL_EDITOR);
+                                ui.horizontal(|ui| {
+                                    ui.label("Costs of the partners:");
+
+                                    ui.add(style_info_button())
+                                        .on_hover_text("The costs are entered for each participant \
+                                        in the Team section and added up here by category and year");
+                                });
+                                if summary.total() == 0. {
+                                    ui.label(RichText::new("…no costs of the participants").weak());
+                                }
+                                costs::preview(ui, summary);
+                            }
                             Variety::SectionBudget(SectionBudget::Personnel) => {
                                 ui.add_space(SPACE_INTERNAL_EDITOR);
                                 ui.horizontal(|ui| {
@@ -210,7 +242,7 @@ impl Budget {
 }
 
 impl Budget {
-    pub fn preview(&self, ui: &mut egui::Ui, resolution: usize) {
+    pub fn preview(&self, ui: &mut egui::Ui, resolution: usize, summary: &Summary) {
         if self.visible {
             ui.add_space(SPACE_SECTIONS_PREVIEW);
             if self.title.is_empty() {
@@ -225,6 +257,9 @@ impl Budget {
                     continue;
                 } else {
                     match chapter.variety {
+                        Variety::SectionBudget(SectionBudget::Costs) => {
+                            costs::preview(ui, summary);
+                        }
                         Variety::SectionBudget(SectionBudget::Personnel) => {
                             self.personnel.preview(ui, Some(SPACE_INTERNAL_PREVIEW));
                         }
@@ -250,3 +285,45 @@ impl Budget {
         }
     }
 }
+
+impl Exporter for Budget {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.heading(&Self::default().title);
+            } else {
+                document.heading(&self.title);
+            }
+
+            for chapter in &self.index_list {
+                if chapter.tier > document.resolution {
+                    continue;
+                }
+                match chapter.variety {
+                    Variety::SectionBudget(SectionBudget::Costs) => {
+                        costs::export(document);
+                    }
+                    Variety::SectionBudget(SectionBudget::Personnel) => {
+                        self.personnel.export(document);
+                    }
+                    Variety::SectionBudget(SectionBudget::Facilities) => {
+                        self.facilities.export(document);
+                    }
+                    Variety::SectionBudget(SectionBudget::Materials) => {
+                        self.materials.export(document);
+                    }
+                    Variety::SectionBudget(SectionBudget::Workshops) => {
+                        self.workshops.export(document);
+                    }
+                    Variety::SectionBudget(SectionBudget::Overheads) => {
+                        self.overheads.export(document);
+                    }
+                    Variety::SectionBudget(SectionBudget::Miscellaneous) => {
+                        self.misc.export(document);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,380 @@
+// The costs of the participants by category and year, added up into
+// the tables of the Budget section, so the budget of the project is always
+// the sum of the budgets of the partners.
+
+use egui::{DragValue, Id, RichText, TextEdit, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::workbook::editor::BIN_ICON_COLOR;
+use crate::workbook::exporter::Document;
+use crate::workbook::identity::Identity;
+use crate::workbook::renderer::{preview_table, SPACE_INTERNAL_PREVIEW};
+use crate::workbook::search;
+use crate::workbook::sections::team::Participant;
+use crate::workbook::visuals::style_bin_button;
+
+// The categories of costs, the same as the chapters of the Budget section.
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq)]
+pub enum CostCategory {
+    #[default]
+    Personnel,
+    Facilities,
+    Materials,
+    Workshops,
+    Overheads,
+    Miscellaneous,
+}
+
+impl CostCategory {
+    pub const ALL: [CostCategory; 6] = [
+        CostCategory::Personnel,
+        CostCategory::Facilities,
+        CostCategory::Materials,
+        CostCategory::Workshops,
+        CostCategory::Overheads,
+        CostCategory::Miscellaneous,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CostCategory::Personnel => "Personnel",
+            CostCategory::Facilities => "Equipment",
+            CostCategory::Materials => "Materials, travel",
+            CostCategory::Workshops => "Workshops",
+            CostCategory::Overheads => "Overheads",
+            CostCategory::Miscellaneous => "Miscellaneous",
+        }
+    }
+}
+
+// The last year of the project the costs are planned for.
+pub const MAX_YEARS: usize = 10;
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Cost {
+    pub category: CostCategory,
+    // The year of the project, from 1 to `MAX_YEARS`.
+    pub year: usize,
+    pub amount: f64,
+    pub description: String,
+}
+
+impl Default for Cost {
+    fn default() -> Self {
+        Self {
+            category: CostCategory::default(),
+            year: 1,
+            amount: 0.,
+            description: String::new(),
+        }
+    }
+}
+
+// The amount rounded, the thousands separated, e.g. 12 500.
+pub fn format_amount(amount: f64) -> String {
+    let digits = amount.round().abs().to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(' ');
+        }
+        grouped.push(digit);
+    }
+    if amount.round() < 0. {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+// The total of the costs with the amounts of the categories, e.g. in the Team section.
+pub fn line(costs: &[Cost]) -> String {
+    let amounts: Vec<String> = CostCategory::ALL
+        .into_iter()
+        .filter_map(|category| {
+            let amount: f64 = costs
+                .iter()
+                .filter(|cost| cost.category == category)
+                .map(|cost| cost.amount)
+                .sum();
+            (amount != 0.).then(|| format!("{}: {}", category.name(), format_amount(amount)))
+        })
+        .collect();
+    let total: f64 = costs.iter().map(|cost| cost.amount).sum();
+    format!(
+        "Total costs: {} ({})",
+        format_amount(total),
+        amounts.join("; ")
+    )
+}
+
+// The number of years of the project, from one to `MAX_YEARS`.
+pub fn project_years(duration_years: f32) -> usize {
+    (duration_years.ceil() as usize).clamp(1, MAX_YEARS)
+}
+
+// The costs of the visible participants by category and by year.
+pub struct Summary {
+    pub years: usize,
+    // The name of the participant, the costs per category and per year.
+    pub partners: Vec<(String, Vec<f64>, Vec<f64>)>,
+}
+
+impl Summary {
+    pub fn new(participants: &[Participant], duration_years: f32) -> Self {
+        let participants: Vec<&Participant> = participants
+            .iter()
+            .filter(|participant| participant.visible)
+            .collect();
+        let last = participants
+            .iter()
+            .flat_map(|participant| participant.costs.iter().map(|cost| cost.year))
+            .max()
+            .unwrap_or(1)
+            .clamp(1, MAX_YEARS);
+        let years = project_years(duration_years).max(last);
+        let partners = participants
+            .iter()
+            .enumerate()
+            .map(|(i, participant)| {
+                let mut categories = vec![0.; CostCategory::ALL.len()];
+                let mut by_year = vec![0.; years];
+                for cost in participant.costs.iter() {
+                    let category = CostCategory::ALL
+                        .iter()
+                        .position(|category| *category == cost.category)
+                        .unwrap_or_default();
+                    categories[category] += cost.amount;
+                    by_year[cost.year.clamp(1, years) - 1] += cost.amount;
+                }
+                (participant.title(i), categories, by_year)
+            })
+            .collect();
+        Summary { years, partners }
+    }
+
+    pub fn total(&self) -> f64 {
+        self.partners
+            .iter()
+            .map(|(_, categories, _)| categories.iter().sum::<f64>())
+            .sum()
+    }
+
+    // The costs of the partners per category with the totals.
+    pub fn by_category(&self) -> Vec<Vec<String>> {
+        let columns = CostCategory::ALL.map(|category| category.name().to_string());
+        let rows = self
+            .partners
+            .iter()
+            .map(|(name, categories, _)| (name.clone(), categories.clone()));
+        table(&columns, rows)
+    }
+
+    // The costs of the partners per year with the totals.
+    pub fn by_year(&self) -> Vec<Vec<String>> {
+        let columns: Vec<String> = (1..=self.years)
+            .map(|year| format!("Year {}", year))
+            .collect();
+        let rows = self
+            .partners
+            .iter()
+            .map(|(name, _, by_year)| (name.clone(), by_year.clone()));
+        table(&columns, rows)
+    }
+}
+
+// The table of the amounts with the header, the total of each row and the totals row.
+fn table(columns: &[String], rows: impl Iterator<Item = (String, Vec<f64>)>) -> Vec<Vec<String>> {
+    let mut header = vec!["Participant".to_string()];
+    header.extend(columns.iter().cloned());
+    header.push("Total".to_string());
+    let mut table = vec![header];
+    let mut totals = vec![0.; columns.len()];
+    for (name, amounts) in rows {
+        let mut row = vec![name];
+        for (j, amount) in amounts.iter().enumerate() {
+            totals[j] += amount;
+            row.push(format_amount(*amount));
+        }
+        row.push(format_amount(amounts.iter().sum()));
+        table.push(row);
+    }
+    let mut row = vec!["Total".to_string()];
+    row.extend(totals.iter().map(|amount| format_amount(*amount)));
+    row.push(format_amount(totals.iter().sum()));
+    table.push(row);
+    table
+}
+
+// Edit the costs of the participant `id` in the project of `years`.
+pub fn edit(ui: &mut Ui, costs: &mut Vec<Cost>, id: Identity, years: usize) {
+    let mut cost_to_delete: Option<usize> = None;
+    for (i, cost) in costs.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source(Id::new(("cost_category", id, i)))
+                .width(120.)
+                .selected_text(cost.category.name())
+                .show_ui(ui, |ui| {
+                    for category in CostCategory::ALL {
+                        ui.selectable_value(&mut cost.category, category, category.name());
+                    }
+                });
+            ui.add(
+                DragValue::new(&mut cost.year)
+                    .clamp_range(1..=MAX_YEARS)
+                    .prefix("Year "),
+            );
+            ui.add(
+                DragValue::new(&mut cost.amount)
+                    .speed(100.)
+                    .clamp_range(0.0..=f64::MAX)
+                    .max_decimals(2),
+            );
+            let output = TextEdit::singleline(&mut cost.description)
+                .hint_text("Description, e.g. PhD student 50%")
+                .desired_width(220.)
+                .show(ui);
+            search::scroll_to(ui, &output.response, &cost.description);
+            if ui
+                .add(style_bin_button(BIN_ICON_COLOR))
+                .on_hover_text(
+                    "Click to delete this cost entirely. \n\
+                    ALERT: You cannot undo this action!",
+                )
+                .clicked()
+            {
+                cost_to_delete = Some(i)
+            }
+        });
+        if cost.year > years {
+            ui.label(
+                RichText::new(format!(
+                    "Year {} is after the end of the project in the Timeline.",
+                    cost.year
+                ))
+                .small()
+                .color(ui.visuals().warn_fg_color),
+            );
+        }
+    }
+    if let Some(i) = cost_to_delete {
+        costs.remove(i);
+    }
+    if !costs.is_empty() {
+        let total: f64 = costs.iter().map(|cost| cost.amount).sum();
+        ui.label(RichText::new(format!("Total: {}", format_amount(total))).strong());
+    }
+}
+
+pub fn preview(ui: &mut Ui, summary: &Summary) {
+    if summary.total() == 0. {
+        return;
+    }
+    for (title, id, table) in [
+        (
+            "Costs per partner and category",
+            "budget_costs_categories",
+            summary.by_category(),
+        ),
+        (
+            "Costs per partner and year",
+            "budget_costs_years",
+            summary.by_year(),
+        ),
+    ] {
+        ui.add_space(SPACE_INTERNAL_PREVIEW);
+        ui.label(RichText::new(title).strong());
+        preview_table(ui, id, table);
+    }
+}
+
+pub fn export(document: &mut Document) {
+    let summary = &document.costs;
+    if summary.total() == 0. {
+        return;
+    }
+    let (by_category, by_year) = (summary.by_category(), summary.by_year());
+    document.paragraph("**Costs per partner and category**");
+    document.table(&by_category);
+    document.paragraph("**Costs per partner and year**");
+    document.table(&by_year);
+}
+
+// Test that the costs of the participants are added up per category and per year.
+#[test]
+fn budget_roll_up() {
+    let cost = |category, year, amount| Cost {
+        category,
+        year,
+        amount,
+        ..Default::default()
+    };
+    let participants = vec![
+        Participant {
+            name: "Jane Doe".to_string(),
+            costs: vec![
+                cost(CostCategory::Personnel, 1, 40000.),
+                cost(CostCategory::Personnel, 2, 42000.),
+                cost(CostCategory::Materials, 2, 3500.),
+            ],
+            visible: true,
+            ..Default::default()
+        },
+        Participant {
+            costs: vec![cost(CostCategory::Facilities, 4, 12000.)],
+            visible: true,
+            ..Default::default()
+        },
+        Participant {
+            costs: vec![cost(CostCategory::Overheads, 1, 1e6)],
+            ..Default::default()
+        },
+    ];
+
+    let summary = Summary::new(&participants, 2.5);
+    assert_eq!(summary.years, 4);
+    assert_eq!(summary.total(), 97500.);
+    let by_category = summary.by_category();
+    assert_eq!(
+        by_category[1],
+        ["Jane Doe", "82 000", "0", "3 500", "0", "0", "0", "85 500"]
+    );
+    assert_eq!(
+        by_category[3],
+        ["Total", "82 000", "12 000", "3 500", "0", "0", "0", "97 500"]
+    );
+    let by_year = summary.by_year();
+    assert_eq!(
+        by_year[0],
+        [
+            "Participant",
+            "Year 1",
+            "Year 2",
+            "Year 3",
+            "Year 4",
+            "Total"
+        ]
+    );
+    assert_eq!(
+        by_year[3],
+        ["Total", "40 000", "45 500", "0", "12 000", "97 500"]
+    );
+
+    assert_eq!(
+        line(&participants[0].costs),
+        "Total costs: 85 500 (Personnel: 82 000; Materials, travel: 3 500)"
+    );
+    assert_eq!(format_amount(999.6), "1 000");
+    assert_eq!(format_amount(1234567.), "1 234 567");
+    assert_eq!(format_amount(16_777_217.), "16 777 217");
+
+    // The years are limited however long the project or late the cost.
+    let late = vec![Participant {
+        costs: vec![cost(CostCategory::Personnel, usize::MAX, 1.)],
+        visible: true,
+        ..Default::default()
+    }];
+    let summary = Summary::new(&late, 1e9);
+    assert_eq!(summary.years, MAX_YEARS);
+    assert_eq!(summary.partners[0].2[MAX_YEARS - 1], 1.);
+}
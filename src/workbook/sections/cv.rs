@@ -0,0 +1,464 @@
+// The structured CV of the participant: the education, the positions,
+// the selected publications, the grants and the supervision with dates.
+// The entries are rendered the same way for all participants, most recent
+// first, in the length required by the funder, e.g. a 2-page CV or a short bio.
+
+use egui::{Id, RichText, TextEdit, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::workbook::editor::BIN_ICON_COLOR;
+use crate::workbook::exporter::Document;
+use crate::workbook::identity::Identity;
+use crate::workbook::renderer::SPACE_INTERNAL_PREVIEW;
+use crate::workbook::search;
+use crate::workbook::visuals::{style_bin_button, style_move_button};
+
+// The number of the latest entries of each kind in the 1-page CV.
+const ONE_PAGE_ENTRIES: usize = 3;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq)]
+pub enum CvKind {
+    #[default]
+    Education,
+    Position,
+    Publication,
+    Grant,
+    Supervision,
+}
+
+impl CvKind {
+    pub const ALL: [CvKind; 5] = [
+        CvKind::Education,
+        CvKind::Position,
+        CvKind::Publication,
+        CvKind::Grant,
+        CvKind::Supervision,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CvKind::Education => "Education",
+            CvKind::Position => "Position",
+            CvKind::Publication => "Publication",
+            CvKind::Grant => "Grant",
+            CvKind::Supervision => "Supervision",
+        }
+    }
+
+    // The heading of the entries of the kind in the CV.
+    pub fn heading(&self) -> &'static str {
+        match self {
+            CvKind::Education => "Education",
+            CvKind::Position => "Positions",
+            CvKind::Publication => "Selected Publications",
+            CvKind::Grant => "Grants",
+            CvKind::Supervision => "Supervision",
+        }
+    }
+
+    // The entries without the end date are running, e.g. the current position.
+    fn is_running(&self) -> bool {
+        matches!(self, CvKind::Position | CvKind::Grant | CvKind::Supervision)
+    }
+
+    fn hint(&self) -> &'static str {
+        match self {
+            CvKind::Education => "Degree, e.g. PhD in Oceanography",
+            CvKind::Position => "Position, e.g. Associate Professor",
+            CvKind::Publication => "Authors, title",
+            CvKind::Grant => "Title of the project",
+            CvKind::Supervision => "Student, thesis",
+        }
+    }
+
+    fn place_hint(&self) -> &'static str {
+        match self {
+            CvKind::Education | CvKind::Position | CvKind::Supervision => "Institution",
+            CvKind::Publication => "Journal, volume, pages",
+            CvKind::Grant => "Funder, amount",
+        }
+    }
+
+    // The number of the entries of the kind in the short bio.
+    fn count(&self, count: usize) -> String {
+        let (one, many) = match self {
+            CvKind::Education => ("degree", "degrees"),
+            CvKind::Position => ("position", "positions"),
+            CvKind::Publication => ("selected publication", "selected publications"),
+            CvKind::Grant => ("grant", "grants"),
+            CvKind::Supervision => ("supervised student", "supervised students"),
+        };
+        format!("{} {}", count, if count == 1 { one } else { many })
+    }
+}
+
+// The length of the CV required by the funder.
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq)]
+pub enum CvLength {
+    // All the entries.
+    #[default]
+    TwoPages,
+    // The latest entries of each kind.
+    OnePage,
+    // A paragraph about the current position, the degree and the numbers
+    // of publications, grants and students.
+    ShortBio,
+}
+
+impl CvLength {
+    pub const ALL: [CvLength; 3] = [CvLength::TwoPages, CvLength::OnePage, CvLength::ShortBio];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CvLength::TwoPages => "2-page CV",
+            CvLength::OnePage => "1-page CV",
+            CvLength::ShortBio => "Short bio",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct CvEntry {
+    pub kind: CvKind,
+    // The year or the year and month, e.g. 2019 or 2019-09.
+    pub from: String,
+    // Empty for the running entries and the single dates.
+    pub to: String,
+    // The degree, the position, the publication, the grant or the student.
+    pub title: String,
+    // The institution, the journal or the funder.
+    pub place: String,
+    pub visible: bool,
+}
+
+// The year and the month of the date entered as 2019, 2019-09 or 09/2019.
+fn parse_date(date: &str) -> Option<(i32, Option<u32>)> {
+    let date = date.trim();
+    let parts: Vec<&str> = date.split(['-', '/', '.']).collect();
+    let (year, month) = match parts[..] {
+        [year] => (year, None),
+        [first, second] if first.len() == 4 => (first, Some(second)),
+        [first, second] => (second, Some(first)),
+        _ => return None,
+    };
+    let year: i32 = year.parse().ok().filter(|_| year.len() == 4)?;
+    match month {
+        None => Some((year, None)),
+        Some(month) => {
+            let month: u32 = month
+                .parse()
+                .ok()
+                .filter(|month| (1..=12).contains(month))?;
+            Some((year, Some(month)))
+        }
+    }
+}
+
+// The date in the same format for all entries, as entered if not recognised.
+fn format_date(date: &str) -> String {
+    match parse_date(date) {
+        Some((year, Some(month))) => format!("{:02}/{}", month, year),
+        Some((year, None)) => year.to_string(),
+        None => date.trim().to_string(),
+    }
+}
+
+impl CvEntry {
+    pub fn new(kind: CvKind) -> Self {
+        CvEntry {
+            kind,
+            visible: true,
+            ..Default::default()
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.kind.is_running() && self.to.trim().is_empty() && !self.from.trim().is_empty()
+    }
+
+    // The dates of the entry, e.g. 09/2015–08/2019 or 2019–present.
+    pub fn period(&self) -> String {
+        let from = format_date(&self.from);
+        let to = format_date(&self.to);
+        if self.is_running() {
+            format!("{}–present", from)
+        } else if to.is_empty() || from == to {
+            from
+        } else if from.is_empty() {
+            to
+        } else {
+            format!("{}–{}", from, to)
+        }
+    }
+
+    // The entry in a line of the CV.
+    pub fn line(&self) -> String {
+        let text: Vec<&str> = [self.title.trim(), self.place.trim()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect();
+        let text = text.join(", ");
+        let period = self.period();
+        if period.is_empty() {
+            text
+        } else {
+            format!("{}: {}", period, text)
+        }
+    }
+
+    // The key ordering the entries, the latest and the running ones first.
+    fn recency(&self) -> (bool, (i32, u32), (i32, u32)) {
+        let date = |date: &str| {
+            parse_date(date).map_or((0, 0), |(year, month)| (year, month.unwrap_or(0)))
+        };
+        let from = date(&self.from);
+        let to = if self.to.trim().is_empty() {
+            from
+        } else {
+            date(&self.to)
+        };
+        (self.is_running(), to, from)
+    }
+}
+
+// The visible entries of the kind, the latest first.
+fn entries_of(entries: &[CvEntry], kind: CvKind) -> Vec<&CvEntry> {
+    let mut entries: Vec<&CvEntry> = entries
+        .iter()
+        .filter(|entry| entry.visible && entry.kind == kind)
+        .collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.recency()));
+    entries
+}
+
+// The lines of the CV under the headings of the kinds, in the length required.
+pub fn lines(entries: &[CvEntry], length: CvLength) -> Vec<(&'static str, Vec<String>)> {
+    let limit = match length {
+        CvLength::OnePage => ONE_PAGE_ENTRIES,
+        _ => usize::MAX,
+    };
+    CvKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let lines: Vec<String> = entries_of(entries, kind)
+                .into_iter()
+                .take(limit)
+                .map(CvEntry::line)
+                .collect();
+            (kind.heading(), lines)
+        })
+        .filter(|(_, lines)| !lines.is_empty())
+        .collect()
+}
+
+// The paragraph about the latest position and degree and the numbers of the other entries.
+pub fn short_bio(entries: &[CvEntry]) -> String {
+    let mut sentences = Vec::new();
+    for kind in [CvKind::Position, CvKind::Education] {
+        if let Some(entry) = entries_of(entries, kind).first() {
+            let text = [entry.title.trim(), entry.place.trim()]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<&str>>()
+                .join(", ");
+            let period = entry.period();
+            if period.is_empty() {
+                sentences.push(format!("{}.", text));
+            } else {
+                sentences.push(format!("{} ({}).", text, period));
+            }
+        }
+    }
+    let counts: Vec<String> = [CvKind::Publication, CvKind::Grant, CvKind::Supervision]
+        .into_iter()
+        .map(|kind| (kind, entries_of(entries, kind).len()))
+        .filter(|(_, count)| *count > 0)
+        .map(|(kind, count)| kind.count(count))
+        .collect();
+    if !counts.is_empty() {
+        sentences.push(format!("{}.", counts.join(", ")));
+    }
+    sentences.join(" ")
+}
+
+// Edit the entries of the CV of the participant `id`.
+pub fn edit(ui: &mut Ui, entries: &mut Vec<CvEntry>, id: Identity) {
+    let mut entry_to_delete: Option<usize> = None;
+    let mut entry_to_move: Option<usize> = None;
+    for (i, entry) in entries.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut entry.visible, "").on_hover_text(
+                "Any entry can be optionally shown/hidden \
+                in the document by adding/removing a tick mark in this field",
+            );
+            egui::ComboBox::from_id_source(Id::new(("cv_kind", id, i)))
+                .width(100.)
+                .selected_text(entry.kind.name())
+                .show_ui(ui, |ui| {
+                    for kind in CvKind::ALL {
+                        ui.selectable_value(&mut entry.kind, kind, kind.name());
+                    }
+                });
+            ui.add(
+                TextEdit::singleline(&mut entry.from)
+                    .hint_text("From")
+                    .desired_width(60.),
+            )
+            .on_hover_text("The year or the year and month, e.g. 2019 or 2019-09");
+            ui.add(
+                TextEdit::singleline(&mut entry.to)
+                    .hint_text(if entry.kind.is_running() {
+                        "present"
+                    } else {
+                        "To"
+                    })
+                    .desired_width(60.),
+            )
+            .on_hover_text("Leave empty for a single date or an entry running at present");
+
+            let icon_color = BIN_ICON_COLOR;
+            if i > 0 {
+                // ⬆ Move up in the list
+                if ui
+                    .add(style_move_button(icon_color))
+                    .on_hover_text("Move up in the list")
+                    .clicked()
+                {
+                    entry_to_move = Some(i)
+                }
+            }
+            // Remove from the list
+            if ui
+                .add(style_bin_button(icon_color))
+                .on_hover_text(
+                    "Click to delete this entry entirely. \n\
+                    ALERT: You cannot undo this action!",
+                )
+                .clicked()
+            {
+                entry_to_delete = Some(i)
+            }
+        });
+        ui.horizontal(|ui| {
+            let output = TextEdit::singleline(&mut entry.title)
+                .hint_text(entry.kind.hint())
+                .desired_width(240.)
+                .show(ui);
+            search::scroll_to(ui, &output.response, &entry.title);
+            let output = TextEdit::singleline(&mut entry.place)
+                .hint_text(entry.kind.place_hint())
+                .desired_width(180.)
+                .show(ui);
+            search::scroll_to(ui, &output.response, &entry.place);
+        });
+    }
+
+    if let Some(i) = entry_to_move {
+        entries.swap(i, i - 1);
+    }
+    if let Some(i) = entry_to_delete {
+        entries.remove(i);
+    }
+}
+
+pub fn preview(ui: &mut Ui, entries: &[CvEntry], length: CvLength) {
+    if length == CvLength::ShortBio {
+        let bio = short_bio(entries);
+        if !bio.is_empty() {
+            ui.add_space(SPACE_INTERNAL_PREVIEW);
+            ui.label(bio);
+        }
+        return;
+    }
+    for (heading, lines) in lines(entries, length) {
+        ui.add_space(SPACE_INTERNAL_PREVIEW);
+        ui.label(RichText::new(heading).strong());
+        for line in lines {
+            ui.label(line);
+        }
+    }
+}
+
+pub fn export(document: &mut Document, entries: &[CvEntry], length: CvLength) {
+    if length == CvLength::ShortBio {
+        document.paragraph(&short_bio(entries));
+        return;
+    }
+    for (heading, lines) in lines(entries, length) {
+        document.paragraph(&format!("**{}**", heading));
+        document.list(&lines);
+    }
+}
+
+// Test that the entries are dated the same way, ordered and shortened
+// to the length required.
+#[test]
+fn structured_cv() {
+    let entry = |kind, from: &str, to: &str, title: &str, place: &str| CvEntry {
+        from: from.to_string(),
+        to: to.to_string(),
+        title: title.to_string(),
+        place: place.to_string(),
+        ..CvEntry::new(kind)
+    };
+    let mut entries = vec![
+        entry(
+            CvKind::Education,
+            "2011",
+            "2015",
+            "PhD in Oceanography",
+            "University of Oslo",
+        ),
+        entry(CvKind::Position, "2015-09", "08/2019", "Postdoc", "NOAA"),
+        entry(
+            CvKind::Position,
+            "2019-09",
+            "",
+            "Associate Professor",
+            "University of Bergen",
+        ),
+    ];
+    for year in 2016..=2020 {
+        entries.push(entry(
+            CvKind::Publication,
+            &year.to_string(),
+            "",
+            "Doe J.",
+            "Nature",
+        ));
+    }
+    entries.push(CvEntry {
+        visible: false,
+        ..entry(CvKind::Grant, "2020", "2023", "Arctic Currents", "ERC")
+    });
+
+    assert_eq!(entries[1].period(), "09/2015–08/2019");
+    assert_eq!(entries[2].period(), "09/2019–present");
+    assert_eq!(
+        entries[0].line(),
+        "2011–2015: PhD in Oceanography, University of Oslo"
+    );
+    assert_eq!(format_date("autumn"), "autumn");
+
+    let full = lines(&entries, CvLength::TwoPages);
+    assert_eq!(
+        full.iter().map(|(heading, _)| *heading).collect::<Vec<_>>(),
+        ["Education", "Positions", "Selected Publications"]
+    );
+    assert_eq!(
+        full[1].1[0],
+        "09/2019–present: Associate Professor, University of Bergen"
+    );
+    assert_eq!(full[2].1.len(), 5);
+    let short = lines(&entries, CvLength::OnePage);
+    assert_eq!(short[2].1.len(), ONE_PAGE_ENTRIES);
+    assert_eq!(short[2].1[0], "2020: Doe J., Nature");
+
+    assert_eq!(
+        short_bio(&entries),
+        "Associate Professor, University of Bergen (09/2019–present). \
+        PhD in Oceanography, University of Oslo (2011–2015). \
+        5 selected publications."
+    );
+}
@@ -0,0 +1,341 @@
+// The effort of the participants in the work packages in person-months,
+// with the totals per participant and per work package, checked against
+// the duration of the project in the Timeline.
+
+use egui::{DragValue, RichText, TextEdit, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::workbook::editor::{BIN_ICON_COLOR, SPACE_INTERNAL_EDITOR};
+use crate::workbook::exporter::Document;
+use crate::workbook::identity::Identity;
+use crate::workbook::renderer::{preview_table, SPACE_INTERNAL_PREVIEW};
+use crate::workbook::search;
+use crate::workbook::sections::team::Participant;
+use crate::workbook::visuals::{style_bin_button, style_move_button};
+
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq)]
+pub struct Effort {
+    pub participant: Identity,
+    pub months: f32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq)]
+pub struct WorkPackage {
+    pub title: String,
+    // The person-months of the participants in the work package.
+    pub effort: Vec<Effort>,
+    pub id: Identity,
+}
+
+impl WorkPackage {
+    pub fn months(&self, participant: Identity) -> f32 {
+        self.effort
+            .iter()
+            .find(|effort| effort.participant == participant)
+            .map_or(0., |effort| effort.months)
+    }
+
+    pub fn set_months(&mut self, participant: Identity, months: f32) {
+        self.effort
+            .retain(|effort| effort.participant != participant);
+        if months > 0. {
+            self.effort.push(Effort {
+                participant,
+                months,
+            });
+        }
+    }
+}
+
+// The short label of the work package in the table, e.g. WP1.
+pub fn label(i: usize) -> String {
+    format!("WP{}", i + 1)
+}
+
+fn format_months(months: f32) -> String {
+    ((months * 10.).round() / 10.).to_string()
+}
+
+// The effort of the participants (rows) in the work packages (columns)
+// with the total of each row, the totals of the columns and the grand total.
+pub struct Matrix {
+    pub rows: Vec<(String, Vec<f32>, f32)>,
+    pub totals: Vec<f32>,
+    pub total: f32,
+}
+
+impl Matrix {
+    pub fn new(participants: &[&Participant], packages: &[WorkPackage]) -> Self {
+        let rows: Vec<(String, Vec<f32>, f32)> = participants
+            .iter()
+            .enumerate()
+            .map(|(i, participant)| {
+                let months: Vec<f32> = packages
+                    .iter()
+                    .map(|package| package.months(participant.id))
+                    .collect();
+                let total = months.iter().sum();
+                (participant.title(i), months, total)
+            })
+            .collect();
+        let totals = (0..packages.len())
+            .map(|j| rows.iter().map(|(_, months, _)| months[j]).sum())
+            .collect();
+        let total = rows.iter().map(|(_, _, total)| total).sum();
+        Matrix {
+            rows,
+            totals,
+            total,
+        }
+    }
+
+    // The table with the header and the totals, the months formatted.
+    pub fn table(&self) -> Vec<Vec<String>> {
+        let mut header = vec!["Participant".to_string()];
+        header.extend((0..self.totals.len()).map(label));
+        header.push("Total".to_string());
+        let mut table = vec![header];
+        for (name, months, total) in &self.rows {
+            let mut row = vec![name.clone()];
+            row.extend(months.iter().map(|months| format_months(*months)));
+            row.push(format_months(*total));
+            table.push(row);
+        }
+        let mut row = vec!["Total".to_string()];
+        row.extend(self.totals.iter().map(|months| format_months(*months)));
+        row.push(format_months(self.total));
+        table.push(row);
+        table
+    }
+}
+
+// The inconsistencies of the effort, e.g. a participant working more months
+// than the project lasts.
+pub fn check(
+    participants: &[Participant],
+    packages: &[WorkPackage],
+    duration_years: f32,
+) -> Vec<String> {
+    let all: Vec<&Participant> = participants.iter().collect();
+    let matrix = Matrix::new(&all, packages);
+    let mut warnings = Vec::new();
+    if matrix.total > 0. && duration_years <= 0. {
+        warnings.push("The project duration is not set in the Timeline.".to_string());
+    }
+    let duration = duration_years * 12.;
+    for (name, _, total) in &matrix.rows {
+        if duration > 0. && *total > duration {
+            warnings.push(format!(
+                "{} has {} person-months, more than one person full time \
+                during the {} months of the project.",
+                name,
+                format_months(*total),
+                format_months(duration)
+            ));
+        } else if *total == 0. && !packages.is_empty() {
+            warnings.push(format!("{} has no effort in the work packages.", name));
+        }
+    }
+    for (j, total) in matrix.totals.iter().enumerate() {
+        if *total == 0. && !participants.is_empty() {
+            warnings.push(format!("{} has no effort of the participants.", label(j)));
+        }
+    }
+    warnings
+}
+
+// Edit the work packages and the person-months of the participants in them.
+pub fn edit(
+    ui: &mut Ui,
+    packages: &mut Vec<WorkPackage>,
+    participants: &[Participant],
+    duration_years: f32,
+) {
+    let mut package_to_delete: Option<usize> = None;
+    let mut package_to_move: Option<usize> = None;
+    for (i, package) in packages.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(label(i));
+            let output = TextEdit::singleline(&mut package.title)
+                .hint_text("Title of the work package")
+                .desired_width(280.)
+                .show(ui);
+            search::scroll_to(ui, &output.response, &package.title);
+
+            let icon_color = BIN_ICON_COLOR;
+            if i > 0 {
+                // ⬆ Move up in the list
+                if ui
+                    .add(style_move_button(icon_color))
+                    .on_hover_text("Move up in the list")
+                    .clicked()
+                {
+                    package_to_move = Some(i)
+                }
+            }
+            // Remove from the list
+            if ui
+                .add(style_bin_button(icon_color))
+                .on_hover_text(
+                    "Click to delete this work package and its effort entirely. \n\
+                    ALERT: You cannot undo this action!",
+                )
+                .clicked()
+            {
+                package_to_delete = Some(i)
+            }
+        });
+    }
+    if let Some(i) = package_to_move {
+        packages.swap(i, i - 1);
+    }
+    if let Some(i) = package_to_delete {
+        packages.remove(i);
+    }
+    if ui.button("Add a work package").clicked() {
+        packages.push(WorkPackage::default());
+    }
+
+    if packages.is_empty() || participants.is_empty() {
+        return;
+    }
+    ui.add_space(SPACE_INTERNAL_EDITOR);
+    let all: Vec<&Participant> = participants.iter().collect();
+    let matrix = Matrix::new(&all, packages);
+    egui::Grid::new("team_effort_matrix")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label(RichText::new("Participant").strong());
+            for (j, package) in packages.iter().enumerate() {
+                ui.label(RichText::new(label(j)).strong())
+                    .on_hover_text(&package.title);
+            }
+            ui.label(RichText::new("Total").strong());
+            ui.end_row();
+
+            for (participant, (name, _, total)) in participants.iter().zip(&matrix.rows) {
+                ui.label(name);
+                for package in packages.iter_mut() {
+                    let mut months = package.months(participant.id);
+                    if ui
+                        .add(
+                            DragValue::new(&mut months)
+                                .speed(0.5)
+                                .clamp_range(0.0..=1200.0)
+                                .max_decimals(1),
+                        )
+                        .changed()
+                    {
+                        package.set_months(participant.id, months);
+                    }
+                }
+                ui.label(format_months(*total));
+                ui.end_row();
+            }
+
+            ui.label(RichText::new("Total").strong());
+            for total in &matrix.totals {
+                ui.label(format_months(*total));
+            }
+            ui.label(RichText::new(format_months(matrix.total)).strong());
+            ui.end_row();
+        });
+
+    for warning in check(participants, packages, duration_years) {
+        ui.label(
+            RichText::new(warning)
+                .small()
+                .color(ui.visuals().warn_fg_color),
+        );
+    }
+}
+
+fn visible(participants: &[Participant]) -> Vec<&Participant> {
+    participants
+        .iter()
+        .filter(|participant| participant.visible)
+        .collect()
+}
+
+pub fn preview(ui: &mut Ui, packages: &[WorkPackage], participants: &[Participant]) {
+    let participants = visible(participants);
+    if packages.is_empty() || participants.is_empty() {
+        return;
+    }
+    ui.add_space(SPACE_INTERNAL_PREVIEW);
+    for (j, package) in packages.iter().enumerate() {
+        ui.label(format!("{}: {}", label(j), package.title.trim()));
+    }
+    ui.add_space(SPACE_INTERNAL_PREVIEW);
+    let table = Matrix::new(&participants, packages).table();
+    preview_table(ui, "team_effort_preview", table);
+}
+
+pub fn export(document: &mut Document, packages: &[WorkPackage], participants: &[Participant]) {
+    let participants = visible(participants);
+    if packages.is_empty() || participants.is_empty() {
+        return;
+    }
+    let titles: Vec<String> = packages
+        .iter()
+        .enumerate()
+        .map(|(j, package)| format!("{}: {}", label(j), package.title.trim()))
+        .collect();
+    document.list(&titles);
+    document.table(&Matrix::new(&participants, packages).table());
+}
+
+// Test that the person-months are summed per participant and per work package
+// and checked against the duration of the project.
+#[test]
+fn effort_matrix() {
+    let participants = vec![
+        Participant {
+            name: "Jane Doe".to_string(),
+            visible: true,
+            ..Default::default()
+        },
+        Participant {
+            visible: true,
+            ..Default::default()
+        },
+    ];
+    let mut packages = vec![WorkPackage::default(), WorkPackage::default()];
+    packages[0].set_months(participants[0].id, 20.);
+    packages[1].set_months(participants[0].id, 18.5);
+    packages[0].set_months(participants[1].id, 6.);
+    packages[0].set_months(participants[1].id, 0.);
+    assert_eq!(packages[0].effort.len(), 1);
+
+    let all: Vec<&Participant> = participants.iter().collect();
+    let matrix = Matrix::new(&all, &packages);
+    assert_eq!(matrix.rows[0].2, 38.5);
+    assert_eq!(matrix.totals, [20., 18.5]);
+    assert_eq!(
+        matrix.table(),
+        [
+            ["Participant", "WP1", "WP2", "Total"],
+            ["Jane Doe", "20", "18.5", "38.5"],
+            ["Participant 2", "0", "0", "0"],
+            ["Total", "20", "18.5", "38.5"],
+        ]
+    );
+
+    assert_eq!(
+        check(&participants, &packages, 0.),
+        [
+            "The project duration is not set in the Timeline.",
+            "Participant 2 has no effort in the work packages.",
+        ]
+    );
+    let warnings = check(&participants, &packages, 3.);
+    assert_eq!(
+        warnings[0],
+        "Jane Doe has 38.5 person-months, more than one person full time \
+        during the 36 months of the project."
+    );
+    assert_eq!(
+        check(&participants[..1], &packages, 4.),
+        Vec::<String>::new()
+    );
+}
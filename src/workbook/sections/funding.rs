@@ -4,15 +4,19 @@ use egui::collapsing_header::CollapsingState;
 use egui::{RichText, TextEdit, Ui};
 use serde::{Deserialize, Serialize};
 
+use crate::workbook::chapter::Variety;
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{
     BIN_ICON_COLOR, SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE,
 };
+use crate::workbook::exporter::{Document, Exporter};
+use crate::workbook::identity::Identity;
 use crate::workbook::note::{Note, Notes};
 use crate::workbook::renderer::Renderer;
 use crate::workbook::renderer::{
     SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW, SUBSECTION_FONT_COLOR, SUBSECTION_FONT_SIZE,
 };
+use crate::workbook::search;
 use crate::workbook::sections::timeline::Date;
 use crate::workbook::visuals::{style_bin_button, style_info_button, style_move_button};
 
@@ -42,6 +46,7 @@ pub struct Programme {
 
     pub annotation: Notes,
     pub visible: bool,
+    pub id: Identity,
 }
 
 impl Editor for FundingOptions {
@@ -53,7 +58,11 @@ impl Editor for FundingOptions {
         let id = ui.make_persistent_id("collapsing_header_funding");
 
         ui.add_space(SPACE_SECTIONS_EDITOR);
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::Funding
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
 
@@ -116,7 +125,11 @@ impl Editor for FundingOptions {
                     let id = ui.make_persistent_id(id_src);
 
                     ui.add_space(SPACE_INTERNAL_EDITOR);
-                    CollapsingState::load_with_default_open(ui.ctx(), id, true)
+                    let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+                    search::reveal(ui.ctx(), &mut state, |location| {
+                        location.section == Variety::Funding && location.item == Some(i)
+                    });
+                    state
                         .show_header(ui, |ui| {
                             ui.checkbox(&mut option.visible, "").on_hover_text(
                                 "\
@@ -125,9 +138,10 @@ impl Editor for FundingOptions {
                                 ",
                             );
 
-                            TextEdit::singleline(&mut option.title)
+                            let output = TextEdit::singleline(&mut option.title)
                                 .hint_text("Funding program name")
                                 .show(ui);
+                            search::scroll_to(ui, &output.response, &option.title);
 
                             let icon_color = BIN_ICON_COLOR;
                             if i > 0 {
@@ -229,9 +243,10 @@ impl Editor for Programme {
         ui.add_space(SPACE_INTERNAL_EDITOR);
         ui.label("Link to the website with all documentation:");
         ui.vertical_centered_justified(|ui| {
-            TextEdit::singleline(&mut self.hyperlink)
+            let output = TextEdit::singleline(&mut self.hyperlink)
                 .hint_text("https://docs.example.com/program_name")
                 .show(ui);
+            search::scroll_to(ui, &output.response, &self.hyperlink);
         });
         ui.add_space(SPACE_INTERNAL_EDITOR);
         egui::Grid::new("program_deadline_grid")
@@ -321,3 +336,37 @@ impl Renderer for Programme {
         }
     }
 }
+
+impl Exporter for FundingOptions {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.heading(&Self::default().title);
+            } else {
+                document.heading(&self.title);
+            }
+
+            self.funding_options.iter().for_each(|option| {
+                if option.visible {
+                    document.subheading(&option.title);
+                    option.export(document);
+                }
+            });
+        }
+    }
+}
+
+impl Exporter for Programme {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            self.annotation.export(document);
+            document.link(&self.hyperlink);
+            document.paragraph(&format!(
+                "Application deadline: {:}-{:02}-{:02}",
+                self.deadline.date.year(),
+                self.deadline.date.month(),
+                self.deadline.date.day()
+            ));
+        }
+    }
+}
@@ -5,10 +5,13 @@ use serde::{Deserialize, Serialize};
 use crate::workbook::chapter::{Segment, Variety};
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE};
+use crate::workbook::exporter::{Document, Exporter};
 use crate::workbook::note::{Note, Notes};
+use crate::workbook::reference_formats::citations::Citations;
 use crate::workbook::renderer::Renderer;
 use crate::workbook::renderer::{SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW};
-use crate::workbook::sections::references::References;
+use crate::workbook::search;
+use crate::workbook::sections::references::{Bibliography, References};
 use crate::workbook::visuals::style_info_button;
 
 // SectionIdea reflects the essence (character, nature) of the contents
@@ -78,7 +81,13 @@ impl Default for Idea {
 }
 
 impl Idea {
-    pub fn edit(&mut self, ui: &mut Ui, edit_section_titles: bool, resolution: usize) {
+    pub fn edit(
+        &mut self,
+        ui: &mut Ui,
+        edit_section_titles: bool,
+        resolution: usize,
+        bibliography: &mut Bibliography,
+    ) {
         let mut title = self.title.clone();
         if title.is_empty() {
             title = Self::default().title;
@@ -86,7 +95,11 @@ impl Idea {
         let id = ui.make_persistent_id("collapsing_header_idea");
 
         ui.add_space(SPACE_SECTIONS_EDITOR);
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::Idea
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
 
@@ -189,7 +202,7 @@ impl Idea {
                                     }
                             }
                             Variety::SectionIdea(SectionIdea::KeyReferences) => {
-                                self.references.edit(ui, edit_section_titles);
+                                self.references.edit(ui, edit_section_titles, bibliography);
                             }
                             _ => {}
                         }
@@ -200,7 +213,7 @@ impl Idea {
 }
 
 impl Idea {
-    pub fn preview(&self, ui: &mut egui::Ui, resolution: usize) {
+    pub fn preview(&self, ui: &mut egui::Ui, resolution: usize, citations: &Citations) {
         if self.visible {
             ui.add_space(SPACE_SECTIONS_PREVIEW);
             if self.title.is_empty() {
@@ -228,7 +241,7 @@ impl Idea {
                             self.description.preview(ui, Some(SPACE_INTERNAL_PREVIEW));
                         }
                         Variety::SectionIdea(SectionIdea::KeyReferences) => {
-                            self.references.preview(ui, Some(SPACE_INTERNAL_PREVIEW));
+                            self.references.preview(ui, citations);
                         }
                         _ => {}
                     }
@@ -237,3 +250,39 @@ impl Idea {
         }
     }
 }
+
+impl Exporter for Idea {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.heading(&Self::default().title);
+            } else {
+                document.heading(&self.title);
+            }
+
+            for chapter in &self.index_list {
+                if chapter.tier > document.resolution {
+                    continue;
+                }
+                match chapter.variety {
+                    Variety::SectionIdea(SectionIdea::Problem) => {
+                        self.problem.export(document);
+                    }
+                    Variety::SectionIdea(SectionIdea::Hypothesis) => {
+                        self.hypothesis.export(document);
+                    }
+                    Variety::SectionIdea(SectionIdea::Abstract) => {
+                        self.summary.export(document);
+                    }
+                    Variety::SectionIdea(SectionIdea::ProjectDescription) => {
+                        self.description.export(document);
+                    }
+                    Variety::SectionIdea(SectionIdea::KeyReferences) => {
+                        self.references.export(document);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -2,23 +2,28 @@ use egui::collapsing_header::CollapsingState;
 use egui::{RichText, TextEdit, Ui};
 use serde::{Deserialize, Serialize};
 
+use crate::workbook::chapter::Variety;
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{
     BIN_ICON_COLOR, SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE,
 };
+use crate::workbook::exporter::{Document, Exporter};
+use crate::workbook::identity::Identity;
 use crate::workbook::note::{Note, Notes};
+use crate::workbook::reference_formats::citations::Citations;
+use crate::workbook::reference_formats::exchange::ReferenceList;
 use crate::workbook::renderer::Renderer;
-use crate::workbook::renderer::{
-    SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW, SUBSECTION_FONT_COLOR, SUBSECTION_FONT_SIZE,
+use crate::workbook::renderer::{SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW};
+use crate::workbook::search;
+use crate::workbook::sections::references::{
+    export_bibliography, preview_bibliography, Bibliography, ReadingStatus, Reference,
 };
-use crate::workbook::sections::references::Reference;
 use crate::workbook::visuals::{style_bin_button, style_move_button};
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct Literature {
     pub title: String,
     pub literature_survey: Notes,
-    pub references: Vec<Reference>,
     pub visible: bool,
 }
 
@@ -27,14 +32,18 @@ impl Default for Literature {
         Self {
             title: String::from("Literature Survey"),
             literature_survey: Notes::default(),
-            references: Vec::new(),
             visible: false,
         }
     }
 }
 
-impl Editor for Literature {
-    fn edit(&mut self, ui: &mut Ui, edit_section_titles: bool) {
+impl Literature {
+    pub fn edit(
+        &mut self,
+        ui: &mut Ui,
+        edit_section_titles: bool,
+        bibliography: &mut Bibliography,
+    ) {
         let mut title = self.title.clone();
         if title.is_empty() {
             title = Self::default().title;
@@ -42,7 +51,11 @@ impl Editor for Literature {
         let id = ui.make_persistent_id("collapsing_header_survey");
 
         ui.add_space(SPACE_SECTIONS_EDITOR);
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::Literature
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
                 ui.toggle_value(
@@ -79,31 +92,58 @@ impl Editor for Literature {
                 ui.horizontal(|ui| {
                     ui.label("Add references to the cited papers:");
                     if ui.button("+").on_hover_text("Add a reference").clicked() {
-                        self.references.push(Reference::default());
+                        bibliography.references.push(Reference {
+                            visible: true,
+                            ..Default::default()
+                        });
                     }
                 });
 
-                // Cited papers
+                let view_id = ui.make_persistent_id("literature_view");
+                let mut view: ReferenceView =
+                    ui.data(|data| data.get_temp(view_id)).unwrap_or_default();
+                if !bibliography.references.is_empty() {
+                    view.edit(ui);
+                    ui.data_mut(|data| data.insert_temp(view_id, view.clone()));
+                }
+
+                // Cited papers, i.e. the bibliography of the project
                 let mut source_to_delete: Option<usize> = None;
                 let mut source_to_move: Option<usize> = None;
-                for (i, reference) in self.references.iter_mut().enumerate() {
+                let mut source_to_select: Option<Identity> = None;
+                for i in view.arrange(&bibliography.references) {
+                    let reference = &mut bibliography.references[i];
+                    let mut key_reference = bibliography.key_references.contains(&reference.id);
                     let id_src = format!("my_collapsing_source{}", i + 1);
                     let id = ui.make_persistent_id(id_src);
 
                     ui.add_space(SPACE_INTERNAL_EDITOR);
-                    CollapsingState::load_with_default_open(ui.ctx(), id, true)
+                    let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+                    search::reveal(ui.ctx(), &mut state, |location| {
+                        location.section == Variety::Literature && location.item == Some(i)
+                    });
+                    state
                         .show_header(ui, |ui| {
                             ui.checkbox(&mut reference.visible, "").on_hover_text(
-                                "Check to show the reference to this source.\n\
-                                Uncheck to hide it in the document.",
+                                "Check to list this source in the Literature Survey.\n\
+                                Uncheck to hide it there.",
                             );
 
-                            TextEdit::singleline(&mut reference.title)
+                            let output = TextEdit::singleline(&mut reference.title)
                                 .hint_text("Reference title")
                                 .show(ui);
+                            search::scroll_to(ui, &output.response, &reference.title);
+
+                            if ui
+                                .toggle_value(&mut key_reference, "Key")
+                                .on_hover_text("Select as a key reference of the Idea section")
+                                .changed()
+                            {
+                                source_to_select = Some(reference.id)
+                            }
 
                             let icon_color = BIN_ICON_COLOR;
-                            if i > 0 {
+                            if i > 0 && view.is_list() {
                                 // ⬆ Move up in the list
                                 if ui
                                     .add(style_move_button(icon_color))
@@ -118,7 +158,8 @@ impl Editor for Literature {
                             if ui
                                 .add(style_bin_button(icon_color))
                                 .on_hover_text(
-                                    "Click to delete this reference entirely. \n\
+                                    "Click to delete this reference entirely, \
+                                also from the key references. \n\
                                 ALERT: You cannot undo this action!",
                                 )
                                 .clicked()
@@ -127,29 +168,142 @@ impl Editor for Literature {
                             }
                         })
                         .body(|ui| {
-                            ui.label("Link to reference/source:");
-                            ui.vertical_centered_justified(|ui| {
-                                TextEdit::singleline(&mut reference.hyperlink)
-                                    .hint_text("https://links.example.com/source_example")
-                                    .show(ui);
-                            });
-
                             reference.edit(ui, edit_section_titles);
                         });
                 }
 
+                if let Some(id) = source_to_select {
+                    bibliography.toggle_key_reference(id);
+                }
                 if let Some(i) = source_to_move {
-                    self.references.swap(i, i - 1);
+                    bibliography.references.swap(i, i - 1);
                 }
                 if let Some(i) = source_to_delete {
-                    self.references.remove(i);
+                    bibliography.remove(i);
                 }
             });
     }
 }
 
-impl Renderer for Literature {
-    fn preview(&self, ui: &mut egui::Ui, _leading_space: Option<f32>) {
+// The filter and order of the references in the editor.
+#[derive(Clone, Default)]
+struct ReferenceView {
+    status: Option<ReadingStatus>,
+    min_relevance: u8,
+    tag: String,
+    order: ReferenceOrder,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum ReferenceOrder {
+    #[default]
+    List,
+    Title,
+    Year,
+    Relevance,
+    Status,
+}
+
+impl ReferenceOrder {
+    const ALL: [ReferenceOrder; 5] = [
+        ReferenceOrder::List,
+        ReferenceOrder::Title,
+        ReferenceOrder::Year,
+        ReferenceOrder::Relevance,
+        ReferenceOrder::Status,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            ReferenceOrder::List => "List order",
+            ReferenceOrder::Title => "Title",
+            ReferenceOrder::Year => "Year",
+            ReferenceOrder::Relevance => "Relevance",
+            ReferenceOrder::Status => "Reading status",
+        }
+    }
+}
+
+impl ReferenceView {
+    // All the references are shown in the order of the list.
+    fn is_list(&self) -> bool {
+        self.order == ReferenceOrder::List
+            && self.status.is_none()
+            && self.min_relevance == 0
+            && self.tag.trim().is_empty()
+    }
+
+    fn matches(&self, reference: &Reference) -> bool {
+        let reading = &reference.reading;
+        let tag = self.tag.trim().to_lowercase();
+        self.status.is_none_or(|status| reading.status == status)
+            && reading.relevance >= self.min_relevance
+            && (tag.is_empty()
+                || reading
+                    .tag_list()
+                    .iter()
+                    .any(|other| other.to_lowercase().contains(&tag)))
+    }
+
+    // The indices of the references shown, in the order of the view.
+    fn arrange(&self, references: &[Reference]) -> Vec<usize> {
+        let mut shown: Vec<usize> = (0..references.len())
+            .filter(|&i| self.matches(&references[i]))
+            .collect();
+        match self.order {
+            ReferenceOrder::List => {}
+            ReferenceOrder::Title => {
+                shown.sort_by_cached_key(|&i| references[i].title.to_lowercase())
+            }
+            ReferenceOrder::Year => shown.sort_by(|&a, &b| {
+                references[a]
+                    .metadata
+                    .year
+                    .cmp(&references[b].metadata.year)
+            }),
+            // The most relevant first
+            ReferenceOrder::Relevance => {
+                shown.sort_by_key(|&i| std::cmp::Reverse(references[i].reading.relevance))
+            }
+            ReferenceOrder::Status => shown.sort_by_key(|&i| references[i].reading.status),
+        }
+        shown
+    }
+
+    fn edit(&mut self, ui: &mut Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Show:");
+            egui::ComboBox::from_id_source("literature_status_filter")
+                .selected_text(self.status.map_or("All", |status| status.name()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.status, None, "All");
+                    for status in ReadingStatus::ALL {
+                        ui.selectable_value(&mut self.status, Some(status), status.name());
+                    }
+                });
+
+            ui.label("Relevance from:");
+            ui.add(egui::DragValue::new(&mut self.min_relevance).clamp_range(0..=5));
+
+            ui.label("Tag:");
+            TextEdit::singleline(&mut self.tag)
+                .desired_width(80.0)
+                .show(ui);
+
+            ui.label("Sort by:");
+            egui::ComboBox::from_id_source("literature_order")
+                .selected_text(self.order.name())
+                .show_ui(ui, |ui| {
+                    for order in ReferenceOrder::ALL {
+                        ui.selectable_value(&mut self.order, order, order.name());
+                    }
+                });
+        });
+    }
+}
+
+impl Literature {
+    pub fn preview(&self, ui: &mut egui::Ui, citations: &Citations) {
         if self.visible {
             ui.add_space(SPACE_SECTIONS_PREVIEW);
             if self.title.is_empty() {
@@ -161,24 +315,66 @@ impl Renderer for Literature {
             self.literature_survey
                 .preview(ui, Some(SPACE_INTERNAL_PREVIEW));
 
-            if !self.references.is_empty() {
+            let references = citations.list(ReferenceList::Literature);
+            if !references.is_empty() {
                 ui.add_space(SPACE_INTERNAL_PREVIEW);
             }
 
-            for reference in self.references.iter() {
-                if reference.visible {
-                    ui.add_space(SPACE_INTERNAL_PREVIEW);
-                    ui.label(
-                        RichText::new(&reference.title)
-                            .size(SUBSECTION_FONT_SIZE)
-                            .color(SUBSECTION_FONT_COLOR),
-                    );
-                    if !&reference.hyperlink.is_empty() {
-                        ui.hyperlink(&reference.hyperlink);
-                    }
-                    reference.preview(ui, None);
-                }
+            preview_bibliography(ui, &references, citations);
+        }
+    }
+}
+
+impl Exporter for Literature {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.heading(&Self::default().title);
+            } else {
+                document.heading(&self.title);
             }
+
+            self.literature_survey.export(document);
+            export_bibliography(document, ReferenceList::Literature);
         }
     }
 }
+
+// Test that the references are filtered and sorted in the editor.
+#[test]
+fn reference_view() {
+    use crate::workbook::sections::references::Reading;
+
+    let reference = |title: &str, status: ReadingStatus, relevance: u8, tags: &str| Reference {
+        title: title.to_string(),
+        reading: Reading {
+            status,
+            relevance,
+            tags: tags.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let references = vec![
+        reference("B", ReadingStatus::Read, 2, "method"),
+        reference("A", ReadingStatus::ToRead, 5, "review, Method"),
+        reference("C", ReadingStatus::Read, 4, ""),
+    ];
+
+    let mut view = ReferenceView::default();
+    assert!(view.is_list());
+    assert_eq!(view.arrange(&references), vec![0, 1, 2]);
+
+    view.order = ReferenceOrder::Title;
+    assert_eq!(view.arrange(&references), vec![1, 0, 2]);
+
+    view.order = ReferenceOrder::Relevance;
+    view.status = Some(ReadingStatus::Read);
+    assert_eq!(view.arrange(&references), vec![2, 0]);
+
+    view.status = None;
+    view.tag = "method".to_string();
+    view.min_relevance = 3;
+    assert_eq!(view.arrange(&references), vec![1]);
+    assert!(!view.is_list());
+}
@@ -5,9 +5,11 @@ use serde::{Deserialize, Serialize};
 use crate::workbook::chapter::{Segment, Variety};
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE};
+use crate::workbook::exporter::{Document, Exporter};
 use crate::workbook::note::{Note, Notes};
 use crate::workbook::renderer::Renderer;
 use crate::workbook::renderer::{SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW};
+use crate::workbook::search;
 
 // SectionOutcomes reflects the essence (character, nature) of the contents
 // of paragraphs (subsections) of the Outcomes section, i.e. the structured
@@ -70,7 +72,11 @@ impl Outcomes {
         let id = ui.make_persistent_id("collapsing_header_outcome");
 
         ui.add_space(SPACE_SECTIONS_EDITOR);
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::Outcomes
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
 
@@ -187,3 +193,33 @@ impl Outcomes {
         }
     }
 }
+
+impl Exporter for Outcomes {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.heading(&Self::default().title);
+            } else {
+                document.heading(&self.title);
+            }
+
+            for chapter in &self.index_list {
+                if chapter.tier > document.resolution {
+                    continue;
+                }
+                match chapter.variety {
+                    Variety::SectionOutcomes(SectionOutcomes::ExpectedResults) => {
+                        self.results.export(document);
+                    }
+                    Variety::SectionOutcomes(SectionOutcomes::Impact) => {
+                        self.impact.export(document);
+                    }
+                    Variety::SectionOutcomes(SectionOutcomes::Propagation) => {
+                        self.propagation.export(document);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
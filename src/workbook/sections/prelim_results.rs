@@ -2,11 +2,14 @@ use egui::collapsing_header::CollapsingState;
 use egui::{RichText, TextEdit, Ui};
 use serde::{Deserialize, Serialize};
 
+use crate::workbook::chapter::Variety;
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE};
+use crate::workbook::exporter::{Document, Exporter};
 use crate::workbook::note::{Note, Notes};
 use crate::workbook::renderer::Renderer;
 use crate::workbook::renderer::{SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW};
+use crate::workbook::search;
 use crate::workbook::visuals::style_info_button;
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -35,7 +38,11 @@ impl Editor for PrelimResults {
         let id = ui.make_persistent_id("collapsing_header_results");
 
         ui.add_space(SPACE_SECTIONS_EDITOR);
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::PrelimResults
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
 
@@ -103,3 +110,17 @@ impl Renderer for PrelimResults {
         }
     }
 }
+
+impl Exporter for PrelimResults {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.heading(&Self::default().title);
+            } else {
+                document.heading(&self.title);
+            }
+
+            self.prelim_results.export(document);
+        }
+    }
+}
@@ -1,22 +1,27 @@
 use egui::collapsing_header::CollapsingState;
-use egui::{RichText, TextEdit, Ui};
+use egui::{Context, Id, RichText, TextEdit, Ui};
 use serde::{Deserialize, Serialize};
+use std::sync::mpsc::Sender;
 
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{
     BIN_ICON_COLOR, SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE,
 };
-use crate::workbook::note::{Note, Notes};
+use crate::workbook::exporter::{Document, Exporter};
+use crate::workbook::identity::Identity;
+use crate::workbook::note::{preview_text, Note, Notes};
+use crate::workbook::reference_formats::citation_style::Fragment;
+use crate::workbook::reference_formats::citations::Citations;
+use crate::workbook::reference_formats::exchange::ReferenceList;
 use crate::workbook::renderer::Renderer;
-use crate::workbook::renderer::{
-    SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW, SUBSECTION_FONT_COLOR, SUBSECTION_FONT_SIZE,
-};
+use crate::workbook::renderer::{SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW};
+use crate::workbook::search;
 use crate::workbook::visuals::{style_bin_button, style_info_button, style_move_button};
 
+// The 'Key References' of the Idea section selected from the bibliography.
 #[derive(Deserialize, Serialize, Clone)]
 pub struct References {
     pub title: String,
-    pub references: Vec<Reference>,
     pub visible: bool,
 }
 
@@ -24,22 +29,298 @@ impl Default for References {
     fn default() -> Self {
         Self {
             title: String::from("Key References"),
-            references: Vec::new(),
             visible: false,
         }
     }
 }
 
+// The bibliography of the project, i.e. all the sources referred to.
+// The sources are listed in the Literature Survey if visible,
+// the Key References are selected from them by the identifier.
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub struct Bibliography {
+    pub references: Vec<Reference>,
+    pub key_references: Vec<Identity>,
+}
+
+impl Bibliography {
+    pub fn is_key_reference(&self, id: Identity) -> bool {
+        self.key_references.contains(&id)
+    }
+
+    pub fn toggle_key_reference(&mut self, id: Identity) {
+        if self.is_key_reference(id) {
+            self.key_references.retain(|&k| k != id);
+        } else {
+            self.key_references.push(id);
+        }
+    }
+
+    pub fn get(&self, id: Identity) -> Option<&Reference> {
+        self.references.iter().find(|reference| reference.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: Identity) -> Option<&mut Reference> {
+        self.references
+            .iter_mut()
+            .find(|reference| reference.id == id)
+    }
+
+    // The index of the source in the bibliography, it's added unless
+    // the source of the same identifier is there already.
+    pub fn add(&mut self, reference: Reference) -> usize {
+        match self.position(reference.id) {
+            Some(i) => i,
+            None => {
+                self.references.push(reference);
+                self.references.len() - 1
+            }
+        }
+    }
+
+    // The sources are added as the key references, not listed
+    // in the Literature Survey unless they are there already.
+    pub fn add_key_references(&mut self, references: Vec<Reference>) {
+        for reference in references {
+            let i = self.add(Reference {
+                visible: false,
+                ..reference
+            });
+            let id = self.references[i].id;
+            if !self.is_key_reference(id) {
+                self.key_references.push(id);
+            }
+        }
+    }
+
+    // The bibliography of the lists of references kept apart in the earlier versions.
+    pub fn merge(literature: Vec<Reference>, key_references: Vec<Reference>) -> Self {
+        let mut bibliography = Self {
+            references: literature,
+            key_references: Vec::new(),
+        };
+        bibliography.merge_key_references(key_references);
+        bibliography
+    }
+
+    // The visible key references are selected from the bibliography,
+    // the sources not found there are added without being listed in
+    // the Literature Survey.
+    // Note: The key references were kept apart from the bibliography
+    //       in the earlier versions, so they are found by the contents.
+    pub fn merge_key_references(&mut self, key_references: Vec<Reference>) {
+        for reference in key_references {
+            let selected = reference.visible;
+            let i = match self
+                .position(reference.id)
+                .or_else(|| self.same_source(&reference))
+            {
+                Some(i) => i,
+                None => {
+                    self.references.push(Reference {
+                        visible: false,
+                        ..reference
+                    });
+                    self.references.len() - 1
+                }
+            };
+            let id = self.references[i].id;
+            if selected && !self.is_key_reference(id) {
+                self.key_references.push(id);
+            }
+        }
+    }
+
+    // The source is deleted also from the key references.
+    pub fn remove(&mut self, i: usize) {
+        let reference = self.references.remove(i);
+        self.key_references.retain(|&id| id != reference.id);
+    }
+
+    fn position(&self, id: Identity) -> Option<usize> {
+        self.references
+            .iter()
+            .position(|reference| reference.id == id)
+    }
+
+    // The same source has the same title, link and citation key.
+    fn same_source(&self, reference: &Reference) -> Option<usize> {
+        let title = reference.title.trim().to_lowercase();
+        let key = reference.citation_key();
+        self.references.iter().position(|other| {
+            other.title.trim().to_lowercase() == title
+                && other.hyperlink.trim() == reference.hyperlink.trim()
+                && other.citation_key() == key
+        })
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Default)]
 pub struct Reference {
     pub title: String,
     pub hyperlink: String,
     pub source_details: Notes,
     pub visible: bool,
+
+    // Bibliographic data for formatting the reference in a citation style.
+    pub metadata: ReferenceMetadata,
+
+    // What has been read and what the source contributes.
+    pub reading: Reading,
+
+    pub id: Identity,
 }
 
-impl Editor for References {
-    fn edit(&mut self, ui: &mut Ui, edit_section_titles: bool) {
+impl Reference {
+    // The citation key set for the reference, otherwise the one made of
+    // the family name of the first author (or the title) and the year.
+    pub fn citation_key(&self) -> String {
+        let key = self.metadata.key.trim();
+        if !key.is_empty() {
+            return key.to_string();
+        }
+
+        let name = match self.metadata.author_list().first() {
+            Some(author) => author.split(',').next().unwrap_or_default(),
+            None => self.title.split_whitespace().next().unwrap_or_default(),
+        };
+        name.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .chain(self.metadata.year.chars().filter(char::is_ascii_digit))
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq)]
+pub struct ReferenceMetadata {
+    pub kind: SourceKind,
+    pub authors: String, // "Family, Given; Family, Given"
+    pub year: String,
+    pub container: String, // Journal, book or proceedings title
+    pub volume: String,
+    pub issue: String,
+    pub pages: String,
+    pub publisher: String,
+    pub doi: String,
+
+    // The key for citing the source in notes, e.g. `[@smith2021]`.
+    pub key: String,
+
+    // The abstract as in the reference manager, it's not exported to the document.
+    pub abstract_text: String,
+}
+
+impl ReferenceMetadata {
+    // The names of authors as "Family, Given" (or the name of organisation).
+    pub fn author_list(&self) -> Vec<&str> {
+        self.authors
+            .split(';')
+            .map(|author| author.trim())
+            .filter(|author| !author.is_empty())
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq)]
+pub enum SourceKind {
+    #[default]
+    Article,
+    Book,
+    Chapter,
+    Conference,
+    Report,
+    Thesis,
+    Web,
+    Other,
+}
+
+impl SourceKind {
+    pub const ALL: [SourceKind; 8] = [
+        SourceKind::Article,
+        SourceKind::Book,
+        SourceKind::Chapter,
+        SourceKind::Conference,
+        SourceKind::Report,
+        SourceKind::Thesis,
+        SourceKind::Web,
+        SourceKind::Other,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SourceKind::Article => "Journal article",
+            SourceKind::Book => "Book",
+            SourceKind::Chapter => "Book chapter",
+            SourceKind::Conference => "Conference paper",
+            SourceKind::Report => "Report",
+            SourceKind::Thesis => "Thesis",
+            SourceKind::Web => "Web page",
+            SourceKind::Other => "Other",
+        }
+    }
+
+    // Sources published as a part of a journal, book or proceedings.
+    pub fn is_part(&self) -> bool {
+        matches!(
+            self,
+            SourceKind::Article | SourceKind::Chapter | SourceKind::Conference
+        )
+    }
+}
+
+// The notes on reading the source for the Literature Survey.
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq)]
+pub struct Reading {
+    pub status: ReadingStatus,
+    pub relevance: u8, // 0 (not rated) to 5
+    pub tags: String,  // "tag, tag"
+    // The local PDF file of the source.
+    pub file: String,
+    pub annotation: String,
+}
+
+impl Reading {
+    pub fn tag_list(&self) -> Vec<&str> {
+        self.tags
+            .split([',', ';'])
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReadingStatus {
+    #[default]
+    ToRead,
+    Reading,
+    Read,
+}
+
+impl ReadingStatus {
+    pub const ALL: [ReadingStatus; 3] = [
+        ReadingStatus::ToRead,
+        ReadingStatus::Reading,
+        ReadingStatus::Read,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReadingStatus::ToRead => "To read",
+            ReadingStatus::Reading => "Reading",
+            ReadingStatus::Read => "Read",
+        }
+    }
+}
+
+impl References {
+    pub fn edit(
+        &mut self,
+        ui: &mut Ui,
+        edit_section_titles: bool,
+        bibliography: &mut Bibliography,
+    ) {
         let mut title = self.title.clone();
         if title.is_empty() {
             title = Self::default().title;
@@ -63,8 +344,18 @@ impl Editor for References {
                     ",
                 );
 
-                if ui.button("+").on_hover_text("Add a reference").clicked() {
-                    self.references.push(Reference::default());
+                if ui
+                    .button("+")
+                    .on_hover_text(
+                        "Add a reference to the Literature Survey, \
+                        select it here when it's filled in",
+                    )
+                    .clicked()
+                {
+                    bibliography.references.push(Reference {
+                        visible: true,
+                        ..Default::default()
+                    });
                 }
             })
             .body(|ui| {
@@ -82,41 +373,60 @@ impl Editor for References {
                     ui.add(style_info_button()).on_hover_text(
                         "\
                         Offer 2-3 key references.\n\n\
-                        Add sources by clicking on the '+' ('Add a reference') button \
-                        in the section header.\
+                        Select them from the references of the Literature Survey. \
+                        The '+' button in the section header adds a new reference \
+                        there to fill in.\
                         ",
                     );
                 });
 
-                let mut source_to_delete: Option<usize> = None;
+                let mut source_to_select: Option<Identity> = None;
+                egui::ComboBox::from_id_source("key_reference_selection")
+                    .selected_text("Select from the bibliography")
+                    .show_ui(ui, |ui| {
+                        for reference in bibliography.references.iter() {
+                            let title = match reference.title.trim() {
+                                "" => "Unnamed reference",
+                                title => title,
+                            };
+                            if !bibliography.is_key_reference(reference.id)
+                                && ui.selectable_label(false, title).clicked()
+                            {
+                                source_to_select = Some(reference.id);
+                            }
+                        }
+                    });
+                if let Some(id) = source_to_select {
+                    bibliography.key_references.push(id);
+                }
+
+                let mut source_to_remove: Option<usize> = None;
                 let mut source_to_move: Option<usize> = None;
-                for (i, reference) in self.references.iter_mut().enumerate() {
-                    let id_src = format!("my_collapsing_header{}", i + 1);
+                let key_references = bibliography.key_references.clone();
+                for (n, key) in key_references.into_iter().enumerate() {
+                    let Some(reference) = bibliography.get_mut(key) else {
+                        continue;
+                    };
+                    let id_src = format!("my_collapsing_header{}", n + 1);
                     let id = ui.make_persistent_id(id_src);
 
                     ui.add_space(SPACE_INTERNAL_EDITOR);
                     CollapsingState::load_with_default_open(ui.ctx(), id, true)
                         .show_header(ui, |ui| {
-                            ui.checkbox(&mut reference.visible, "").on_hover_text(
-                                "\
-                                Check to show the reference to this source.\n\
-                                Uncheck to hide it in the document.\
-                                ",
-                            );
-
-                            TextEdit::singleline(&mut reference.title)
+                            let output = TextEdit::singleline(&mut reference.title)
                                 .hint_text("Reference title")
                                 .show(ui);
+                            search::scroll_to(ui, &output.response, &reference.title);
 
                             let icon_color = BIN_ICON_COLOR;
-                            if i > 0 {
+                            if n > 0 {
                                 // ⬆ Move up in the list
                                 if ui
                                     .add(style_move_button(icon_color))
                                     .on_hover_text("Move up in the list")
                                     .clicked()
                                 {
-                                    source_to_move = Some(i)
+                                    source_to_move = Some(n)
                                 }
                             }
 
@@ -125,31 +435,25 @@ impl Editor for References {
                                 .add(style_bin_button(icon_color))
                                 .on_hover_text(
                                     "\
-                                Click to delete this reference entirely. \n\
-                                ALERT: You cannot undo this action!\
+                                Click to remove this reference from the key references.\n\
+                                The source is kept in the bibliography.\
                                 ",
                                 )
                                 .clicked()
                             {
-                                source_to_delete = Some(i)
+                                source_to_remove = Some(n)
                             }
                         })
                         .body(|ui| {
-                            ui.label("Link to reference/source:");
-                            ui.vertical_centered_justified(|ui| {
-                                TextEdit::singleline(&mut reference.hyperlink)
-                                    .hint_text("https://links.example.com/source_example")
-                                    .show(ui);
-                            });
                             reference.edit(ui, edit_section_titles);
                         });
                 }
 
-                if let Some(i) = source_to_move {
-                    self.references.swap(i, i - 1);
+                if let Some(n) = source_to_move {
+                    bibliography.key_references.swap(n, n - 1);
                 }
-                if let Some(i) = source_to_delete {
-                    self.references.remove(i);
+                if let Some(n) = source_to_remove {
+                    bibliography.key_references.remove(n);
                 }
             });
     }
@@ -157,6 +461,50 @@ impl Editor for References {
 
 impl Editor for Reference {
     fn edit(&mut self, ui: &mut Ui, _edit_section_titles: bool) {
+        ui.label("Link to reference/source:");
+        ui.vertical_centered_justified(|ui| {
+            let output = TextEdit::singleline(&mut self.hyperlink)
+                .hint_text("https://links.example.com/source_example")
+                .show(ui);
+            search::scroll_to(ui, &output.response, &self.hyperlink);
+        });
+
+        // The group is expanded if it contains the match selected in the search.
+        let jump = search::jump(ui.ctx());
+        let reveal = |group: &str, texts: &[&String]| {
+            jump.as_ref()
+                .filter(|jump| jump.location.group == group && texts.contains(&&jump.text))
+                .map(|_| true)
+        };
+        let data = &self.metadata;
+        let key = self.citation_key();
+        egui::CollapsingHeader::new("Bibliographic data")
+            .open(reveal(
+                "Bibliographic data",
+                &[
+                    &data.authors,
+                    &data.year,
+                    &data.container,
+                    &data.volume,
+                    &data.issue,
+                    &data.pages,
+                    &data.publisher,
+                    &data.doi,
+                    &data.key,
+                ],
+            ))
+            .show(ui, |ui| {
+                self.metadata.edit(ui, &key);
+            });
+        egui::CollapsingHeader::new("Reading notes")
+            .open(reveal(
+                "Reading notes",
+                &[&self.reading.tags, &self.reading.annotation],
+            ))
+            .show(ui, |ui| {
+                self.reading.edit(ui, self.id);
+            });
+
         let mut note_to_delete: Option<usize> = None;
         let mut note_to_move: Option<usize> = None;
         for (i, item) in self.source_details.notes.iter_mut().enumerate() {
@@ -205,9 +553,10 @@ impl Editor for Reference {
                     }
                 });
                 ui.vertical_centered_justified(|ui| {
-                    TextEdit::multiline(&mut item.note)
+                    let output = TextEdit::multiline(&mut item.note)
                         .hint_text(&item.hint)
                         .show(ui);
+                    search::scroll_to(ui, &output.response, &item.note);
                 });
             });
         }
@@ -232,8 +581,184 @@ impl Editor for Reference {
     }
 }
 
-impl Renderer for References {
-    fn preview(&self, ui: &mut egui::Ui, _leading_space: Option<f32>) {
+impl ReferenceMetadata {
+    fn edit(&mut self, ui: &mut Ui, key: &str) {
+        egui::Grid::new("reference_metadata")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Type:");
+                egui::ComboBox::from_id_source("reference_kind")
+                    .selected_text(self.kind.name())
+                    .show_ui(ui, |ui| {
+                        for kind in SourceKind::ALL {
+                            ui.selectable_value(&mut self.kind, kind, kind.name());
+                        }
+                    });
+                ui.end_row();
+
+                for (label, hint, field) in [
+                    (
+                        "Authors:",
+                        "Family, Given; Family, Given",
+                        &mut self.authors,
+                    ),
+                    ("Year:", "2024", &mut self.year),
+                    (
+                        "Published in:",
+                        "Journal, book or proceedings",
+                        &mut self.container,
+                    ),
+                    ("Volume:", "", &mut self.volume),
+                    ("Issue:", "", &mut self.issue),
+                    ("Pages:", "45–67", &mut self.pages),
+                    ("Publisher:", "", &mut self.publisher),
+                    ("DOI:", "10.1000/xyz123", &mut self.doi),
+                    ("Citation key:", key, &mut self.key),
+                ] {
+                    ui.label(label);
+                    let output = TextEdit::singleline(field).hint_text(hint).show(ui);
+                    search::scroll_to(ui, &output.response, field);
+                    ui.end_row();
+                }
+            });
+
+        ui.label("Abstract:");
+        ui.vertical_centered_justified(|ui| {
+            let output = TextEdit::multiline(&mut self.abstract_text)
+                .hint_text("The abstract of the source")
+                .show(ui);
+            search::scroll_to(ui, &output.response, &self.abstract_text);
+        });
+    }
+}
+
+impl Reading {
+    fn edit(&mut self, ui: &mut Ui, reference: Identity) {
+        egui::Grid::new("reference_reading")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Status:");
+                egui::ComboBox::from_id_source("reading_status")
+                    .selected_text(self.status.name())
+                    .show_ui(ui, |ui| {
+                        for status in ReadingStatus::ALL {
+                            ui.selectable_value(&mut self.status, status, status.name());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Relevance:");
+                ui.add(egui::Slider::new(&mut self.relevance, 0..=5))
+                    .on_hover_text("0 – not rated, 5 – essential");
+                ui.end_row();
+
+                ui.label("Tags:");
+                let output = TextEdit::singleline(&mut self.tags)
+                    .hint_text("method, review")
+                    .show(ui);
+                search::scroll_to(ui, &output.response, &self.tags);
+                ui.end_row();
+
+                ui.label("PDF file:");
+                ui.horizontal(|ui| {
+                    TextEdit::singleline(&mut self.file)
+                        .hint_text("/path/to/paper.pdf")
+                        .show(ui);
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        use crate::workbook::notifications;
+
+                        if ui.button("…").on_hover_text("Choose the file").clicked() {
+                            pick_pdf_path(ui.ctx(), reference);
+                        }
+                        if ui
+                            .add_enabled(!self.file.trim().is_empty(), egui::Button::new("Open"))
+                            .on_hover_text("Open the file in the default PDF viewer")
+                            .clicked()
+                        {
+                            if let Err(e) = open_file(self.file.trim()) {
+                                notifications::notify(
+                                    ui.ctx(),
+                                    notifications::Notification::error(
+                                        "Open Error",
+                                        format!("The file cannot be opened: {}", e),
+                                    ),
+                                );
+                            }
+                        }
+                    }
+                });
+                ui.end_row();
+            });
+
+        ui.label("Annotation:");
+        ui.vertical_centered_justified(|ui| {
+            let output = TextEdit::multiline(&mut self.annotation)
+                .hint_text("What does the source contribute?")
+                .show(ui);
+            search::scroll_to(ui, &output.response, &self.annotation);
+        });
+    }
+}
+
+// The PDF file chosen for the reading notes of the reference,
+// sent back to the UI thread.
+pub type PickedPdf = (Identity, String);
+
+fn pdf_sender_id() -> Id {
+    Id::new("workbook_pdf_sender")
+}
+
+// The editor sends the chosen PDF files to the workbook by the sender.
+pub fn share_pdf_sender(ctx: &Context, sender: Sender<PickedPdf>) {
+    ctx.data_mut(|data| data.insert_temp(pdf_sender_id(), sender));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn pick_pdf_path(ctx: &Context, reference: Identity) {
+    use crate::workbook::app::execute;
+
+    let Some(sender) = ctx.data(|data| data.get_temp::<Sender<PickedPdf>>(pdf_sender_id())) else {
+        return;
+    };
+    let task = rfd::AsyncFileDialog::new()
+        .add_filter("PDF", &["pdf"])
+        .pick_file();
+    execute(async move {
+        let file = task.await;
+        if let Some(file) = file {
+            let _ = sender.send((reference, file.path().display().to_string()));
+        }
+    });
+}
+
+// Open the file in the default application of the system.
+#[cfg(not(target_arch = "wasm32"))]
+fn open_file(path: &str) -> std::io::Result<()> {
+    use std::process::Command;
+
+    if !std::path::Path::new(path).is_file() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "no such file",
+        ));
+    }
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = Command::new("xdg-open");
+
+    command.arg(path).spawn().map(|_| ())
+}
+
+impl References {
+    pub fn preview(&self, ui: &mut egui::Ui, citations: &Citations) {
         if self.visible {
             ui.add_space(SPACE_SECTIONS_PREVIEW);
             if self.title.is_empty() {
@@ -242,30 +767,124 @@ impl Renderer for References {
                 ui.heading(&self.title);
             }
 
-            self.references.iter().for_each(|reference| {
-                if reference.visible {
-                    ui.add_space(SPACE_INTERNAL_PREVIEW);
-                    ui.label(
-                        RichText::new(&reference.title)
-                            .size(SUBSECTION_FONT_SIZE)
-                            .color(SUBSECTION_FONT_COLOR),
-                    );
-                    if !&reference.hyperlink.is_empty() {
-                        ui.hyperlink(&reference.hyperlink);
-                    }
-                    reference.preview(ui, None);
-                }
-            });
+            preview_bibliography(ui, &citations.list(ReferenceList::KeyReferences), citations);
         }
     }
 }
 
+// The numbered references formatted in the citation style of project.
+pub fn preview_bibliography(
+    ui: &mut egui::Ui,
+    entries: &[(usize, &Reference)],
+    citations: &Citations,
+) {
+    for (number, reference) in entries {
+        preview_entry(ui, *number, reference, citations);
+        reference.preview(ui, None);
+    }
+}
+
+// The bibliography entry of the reference after its label.
+pub fn preview_entry(
+    ui: &mut egui::Ui,
+    number: usize,
+    reference: &Reference,
+    citations: &Citations,
+) {
+    let style = citations.style;
+    ui.add_space(SPACE_INTERNAL_PREVIEW);
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 0.0;
+        let label = style.label(number);
+        if !label.is_empty() {
+            ui.label(format!("{} ", label));
+        }
+        for fragment in style.format(reference) {
+            match fragment {
+                Fragment::Text(text) => ui.label(text),
+                Fragment::Emphasis(text) => ui.label(RichText::new(text).italics()),
+                Fragment::Link(url) => ui.hyperlink(url),
+            };
+        }
+        if citations.not_cited(reference) {
+            ui.label(
+                RichText::new(" (not cited)")
+                    .color(ui.visuals().warn_fg_color)
+                    .small(),
+            )
+            .on_hover_text("The reference is not cited in the notes");
+        }
+    });
+}
+
 impl Renderer for Reference {
     fn preview(&self, ui: &mut egui::Ui, _leading_space: Option<f32>) {
         self.source_details.notes.iter().for_each(|note| {
             if note.visible {
-                ui.label(&note.note);
+                preview_text(ui, &note.note);
             }
         });
+        // The annotated bibliography
+        if !self.reading.annotation.trim().is_empty() {
+            preview_text(ui, &self.reading.annotation);
+        }
+    }
+}
+
+impl Exporter for References {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.subheading(&Self::default().title);
+            } else {
+                document.subheading(&self.title);
+            }
+
+            export_bibliography(document, ReferenceList::KeyReferences);
+        }
+    }
+}
+
+// The visible references of the list formatted in the citation style of project.
+pub fn export_bibliography(document: &mut Document, list: ReferenceList) {
+    let style = document.citations.style;
+    let entries: Vec<(usize, Reference)> = document
+        .citations
+        .list(list)
+        .into_iter()
+        .map(|(number, reference)| (number, reference.clone()))
+        .collect();
+    for (number, reference) in entries {
+        document.entry(&style.label(number), &style.format(&reference));
+        reference.source_details.export(document);
+        document.paragraph(&reference.reading.annotation);
     }
 }
+
+// Test that the sources are told apart by the identifier, and the key
+// references of the earlier versions are found by the contents.
+#[test]
+fn bibliography_sources() {
+    let mut bibliography = Bibliography::default();
+    assert_eq!(bibliography.add(Reference::default()), 0);
+    assert_eq!(bibliography.add(Reference::default()), 1);
+    let same = bibliography.references[1].clone();
+    assert_eq!(bibliography.add(same), 1);
+
+    bibliography.add_key_references(vec![Reference {
+        title: "Paper".to_string(),
+        ..Default::default()
+    }]);
+    assert_eq!(bibliography.references.len(), 3);
+    assert!(bibliography.is_key_reference(bibliography.references[2].id));
+
+    let legacy = |title: &str| Reference {
+        title: title.to_string(),
+        visible: true,
+        ..Default::default()
+    };
+    bibliography.merge_key_references(vec![legacy(" paper "), legacy("Book")]);
+    assert_eq!(bibliography.references.len(), 4);
+    assert_eq!(bibliography.key_references.len(), 2);
+    assert!(!bibliography.references[3].visible);
+}
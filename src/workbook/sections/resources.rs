@@ -2,11 +2,14 @@ use egui::collapsing_header::CollapsingState;
 use egui::{RichText, TextEdit, Ui};
 use serde::{Deserialize, Serialize};
 
+use crate::workbook::chapter::Variety;
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE};
+use crate::workbook::exporter::{Document, Exporter};
 use crate::workbook::note::{Note, Notes};
 use crate::workbook::renderer::Renderer;
 use crate::workbook::renderer::{SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW};
+use crate::workbook::search;
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct Resources {
@@ -36,7 +39,11 @@ impl Editor for Resources {
         let id = ui.make_persistent_id("collapsing_header_resources");
 
         ui.add_space(SPACE_SECTIONS_EDITOR);
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::Resources
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
                 ui.toggle_value(
@@ -104,3 +111,18 @@ impl Renderer for Resources {
         }
     }
 }
+
+impl Exporter for Resources {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.heading(&Self::default().title);
+            } else {
+                document.heading(&self.title);
+            }
+
+            self.existing.export(document);
+            self.further.export(document);
+        }
+    }
+}
@@ -5,9 +5,11 @@ use serde::{Deserialize, Serialize};
 use crate::workbook::chapter::{Segment, Variety};
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE};
+use crate::workbook::exporter::{Document, Exporter};
 use crate::workbook::note::{Note, Notes};
 use crate::workbook::renderer::Renderer;
 use crate::workbook::renderer::{SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW};
+use crate::workbook::search;
 use crate::workbook::visuals::style_info_button;
 
 // SectionScope reflects the essence (character, nature) of the contents
@@ -86,7 +88,11 @@ impl Scope {
         let id = ui.make_persistent_id("collapsing_header_scope");
 
         ui.add_space(SPACE_SECTIONS_EDITOR);
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::Scope
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
                 ui.toggle_value(
@@ -242,3 +248,39 @@ impl Scope {
         }
     }
 }
+
+impl Exporter for Scope {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.heading(&Self::default().title);
+            } else {
+                document.heading(&self.title);
+            }
+
+            for chapter in &self.index_list {
+                if chapter.tier > document.resolution {
+                    continue;
+                }
+                match chapter.variety {
+                    Variety::SectionScope(SectionScope::SuggestedTasks) => {
+                        self.suggested_tasks.export(document);
+                    }
+                    Variety::SectionScope(SectionScope::Objectives) => {
+                        self.objectives.export(document);
+                    }
+                    Variety::SectionScope(SectionScope::Activities) => {
+                        self.activities.export(document);
+                    }
+                    Variety::SectionScope(SectionScope::WorkPlan) => {
+                        self.work_plan.export(document);
+                    }
+                    Variety::SectionScope(SectionScope::Tasks) => {
+                        self.tasks.export(document);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
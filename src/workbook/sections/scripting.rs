@@ -2,11 +2,14 @@ use egui::collapsing_header::CollapsingState;
 use egui::{RichText, TextEdit, Ui};
 use serde::{Deserialize, Serialize};
 
+use crate::workbook::chapter::Variety;
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE};
+use crate::workbook::exporter::{Document, Exporter};
 use crate::workbook::note::{Note, Notes};
 use crate::workbook::renderer::Renderer;
 use crate::workbook::renderer::{SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW};
+use crate::workbook::search;
 use crate::workbook::visuals::style_info_button;
 
 #[derive(Deserialize, Serialize, Clone, Default, PartialEq, Debug)]
@@ -45,7 +48,11 @@ impl Editor for Scripting {
         let id = ui.make_persistent_id("collapsing_header_scripting");
 
         ui.add_space(SPACE_SECTIONS_EDITOR);
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::Scripting
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
                 ui.toggle_value(
@@ -128,3 +135,28 @@ impl Renderer for Scripting {
         }
     }
 }
+
+impl Exporter for Scripting {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.heading(&Self::default().title);
+            } else {
+                document.heading(&self.title);
+            }
+
+            match self.answer {
+                ScriptingOption::Myself => {
+                    document.paragraph("I can write the Project Proposal myself.")
+                }
+                ScriptingOption::Jointly => document
+                    .paragraph("I offer to write the Project Proposal jointly with someone else."),
+                ScriptingOption::Other => {
+                    document.paragraph("I suggest another person for writing the Project Proposal.")
+                }
+            };
+
+            self.scripting.export(document);
+        }
+    }
+}
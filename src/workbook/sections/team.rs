@@ -7,11 +7,17 @@ use crate::workbook::editor::Editor;
 use crate::workbook::editor::{
     BIN_ICON_COLOR, SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE,
 };
+use crate::workbook::exporter::{Document, Exporter};
+use crate::workbook::identity::Identity;
 use crate::workbook::note::{Note, Notes};
 use crate::workbook::renderer::Renderer;
 use crate::workbook::renderer::{
     SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW, SUBSECTION_FONT_COLOR, SUBSECTION_FONT_SIZE,
 };
+use crate::workbook::search;
+use crate::workbook::sections::costs::{self, project_years, Cost};
+use crate::workbook::sections::cv::{self, CvEntry, CvKind, CvLength};
+use crate::workbook::sections::effort::{self, WorkPackage};
 use crate::workbook::visuals::{style_bin_button, style_info_button, style_move_button};
 
 // SectionTeam reflects the essence (character, nature) of the contents
@@ -21,6 +27,7 @@ use crate::workbook::visuals::{style_bin_button, style_info_button, style_move_b
 // which, being combined, make up the contents of the section.
 #[derive(Deserialize, Serialize, Clone, PartialEq)]
 pub enum SectionTeam {
+    Effort,
     IndustrialPartners,
     Participants,
     ProjectLeader,
@@ -46,6 +53,8 @@ pub struct Team {
     pub proponents: Notes,        // Suggestion for cooperation with non-participants of the project
 
     pub participants: Vec<Participant>, // Full information about participants, institutions, teams
+    pub cv_length: CvLength,            // The length of the CVs required by the funder
+    pub work_packages: Vec<WorkPackage>, // The person-months of the participants per work package
 
     pub visible: bool,
 }
@@ -75,6 +84,10 @@ impl Default for Team {
                     variety: Variety::SectionTeam(SectionTeam::Participants),
                     tier: 3,
                 },
+                Segment {
+                    variety: Variety::SectionTeam(SectionTeam::Effort),
+                    tier: 3,
+                },
             ],
             proposed_partners: Notes::default(),
             project_leader: Notes::default(),
@@ -82,6 +95,8 @@ impl Default for Team {
             proponents: Notes::default(),
 
             participants: Vec::new(),
+            cv_length: CvLength::default(),
+            work_packages: Vec::new(),
 
             visible: false,
         }
@@ -122,9 +137,14 @@ pub struct Participant {
     pub contribution: Notes,
     pub team: Notes,
     pub cv: Notes, // The short CV of the Participant (Partner's team leader)
+    #[serde(default)]
+    pub cv_entries: Vec<CvEntry>, // The structured CV with dates
     pub resources: Notes,
     pub budget: Notes,
+    #[serde(default)]
+    pub costs: Vec<Cost>, // The costs by category and year, added up in the Budget section
     pub visible: bool,
+    pub id: Identity,
 }
 
 impl Default for Participant {
@@ -172,15 +192,46 @@ impl Default for Participant {
             contribution: Notes::default(),
             team: Notes::default(),
             cv: Notes::default(), // The short CV of the Participant (Partner's team leader)
+            cv_entries: Vec::new(),
             resources: Notes::default(),
             budget: Notes::default(),
+            costs: Vec::new(),
             visible: false,
+            id: Identity::new(),
+        }
+    }
+}
+
+impl Participant {
+    // The name of the participant `i` in the tables, numbered if not entered.
+    pub fn title(&self, i: usize) -> String {
+        if self.name.trim().is_empty() {
+            format!("Participant {}", i + 1)
+        } else {
+            self.name.trim().to_string()
         }
     }
+
+    // The lists of notes in the order of the storage.
+    pub fn notes_mut(&mut self) -> [(&'static str, &mut Notes); 5] {
+        [
+            ("Contribution", &mut self.contribution),
+            ("Team", &mut self.team),
+            ("Short CV", &mut self.cv),
+            ("Resources", &mut self.resources),
+            ("Budget", &mut self.budget),
+        ]
+    }
 }
 
 impl Team {
-    pub fn edit(&mut self, ui: &mut Ui, edit_section_titles: bool, resolution: usize) {
+    pub fn edit(
+        &mut self,
+        ui: &mut Ui,
+        edit_section_titles: bool,
+        resolution: usize,
+        duration_years: f32,
+    ) {
         let mut title = self.title.clone();
         if title.is_empty() {
             title = Self::default().title;
@@ -188,7 +239,11 @@ impl Team {
         let id = ui.make_persistent_id("collapsing_header_team");
 
         ui.add_space(SPACE_SECTIONS_EDITOR);
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::Team
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
 
@@ -299,6 +354,23 @@ impl Team {
                                         self.participants.push(Participant::default());
                                     }
                                 });
+                                ui.horizontal(|ui| {
+                                    ui.label("CV length required by the funder:");
+                                    egui::ComboBox::from_id_source("team_cv_length")
+                                        .selected_text(self.cv_length.name())
+                                        .show_ui(ui, |ui| {
+                                            for length in CvLength::ALL {
+                                                ui.selectable_value(&mut self.cv_length, length, length.name());
+                                            }
+                                        });
+                                    ui.add(style_info_button())
+                                        .on_hover_text("\
+                                        The structured CVs of the participants are shown \
+                                        in full, with the latest entries of each kind, \
+                                        or as a short paragraph about the current position, \
+                                        the degree and the numbers of publications, grants and students.\
+                                        ");
+                                });
 
                                 // Edit participants in the project.
                                 let mut member_to_delete: Option<usize> = None;
@@ -312,7 +384,11 @@ impl Team {
                                     let id = ui.make_persistent_id(id_src);
 
                                     ui.add_space(SPACE_INTERNAL_EDITOR);
-                                    CollapsingState::load_with_default_open(ui.ctx(), id, true)
+                                    let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+                                    search::reveal(ui.ctx(), &mut state, |location| {
+                                        location.section == Variety::Team && location.item == Some(i)
+                                    });
+                                    state
                                         .show_header(ui, |ui| {
                                             ui.checkbox(&mut participant.visible, "")
                                                 .on_hover_text("\
@@ -320,9 +396,10 @@ impl Team {
                                                 by adding/removing a tick mark in this field\
                                                 ");
 
-                                            TextEdit::singleline(&mut participant.name)
+                                            let output = TextEdit::singleline(&mut participant.name)
                                                 .hint_text("The name of the leader of partner team")
                                                 .show(ui);
+                                            search::scroll_to(ui, &output.response, &participant.name);
 
                                             if i > 0 {
                                                 let icon_color = BIN_ICON_COLOR;
@@ -342,7 +419,7 @@ impl Team {
                                             }
                                         })
                                         .body(|ui| {
-                                            participant.edit(ui, edit_section_titles, resolution);
+                                            participant.edit(ui, edit_section_titles, resolution, project_years(duration_years));
                                         });
                                 }
 
@@ -353,6 +430,22 @@ impl Team {
                                     self.participants.remove(i);
                                 }
                             }
+                            Variety::SectionTeam(SectionTeam::Effort) => {
+                                // Person-months per participant and work package
+                                ui.add_space(SPACE_INTERNAL_EDITOR);
+                                ui.horizontal(|ui| {
+                                    ui.label("Effort of the participants in the work packages:");
+
+                                    ui.add(style_info_button())
+                                        .on_hover_text("\
+                                        Add the work packages of the project and estimate \
+                                        the person-months of each participant's team in them. \
+                                        The totals are checked against the project duration \
+                                        in the Timeline.\
+                                        ");
+                                });
+                                effort::edit(ui, &mut self.work_packages, &self.participants, duration_years);
+                            }
                             _ => {}
                         }
                     }
@@ -362,24 +455,29 @@ impl Team {
 }
 
 impl Participant {
-    fn edit(&mut self, ui: &mut Ui, edit_section_titles: bool, resolution: usize) {
+    fn edit(&mut self, ui: &mut Ui, edit_section_titles: bool, resolution: usize, years: usize) {
         let chapters = &self.index_list;
-        TextEdit::singleline(&mut self.role)
+        let output = TextEdit::singleline(&mut self.role)
             .hint_text("Role, e.g. Principal Investigator or Project Leader")
             .show(ui);
+        search::scroll_to(ui, &output.response, &self.role);
 
-        TextEdit::singleline(&mut self.affiliation)
+        let output = TextEdit::singleline(&mut self.affiliation)
             .hint_text("Affiliation")
             .show(ui);
-        TextEdit::singleline(&mut self.hyperlink)
+        search::scroll_to(ui, &output.response, &self.affiliation);
+        let output = TextEdit::singleline(&mut self.hyperlink)
             .hint_text("Web address")
             .show(ui);
-        TextEdit::singleline(&mut self.country)
+        search::scroll_to(ui, &output.response, &self.hyperlink);
+        let output = TextEdit::singleline(&mut self.country)
             .hint_text("Country")
             .show(ui);
-        TextEdit::singleline(&mut self.expertise)
+        search::scroll_to(ui, &output.response, &self.country);
+        let output = TextEdit::singleline(&mut self.expertise)
             .hint_text("Main expertise")
             .show(ui);
+        search::scroll_to(ui, &output.response, &self.expertise);
 
         for chapter in chapters {
             if chapter.tier > resolution {
@@ -411,6 +509,18 @@ impl Participant {
                         {
                             self.budget.notes.push(Note::new());
                         }
+                        ui.add_space(SPACE_INTERNAL_EDITOR);
+                        ui.horizontal(|ui| {
+                            ui.label("Costs by category and year:");
+                            ui.add(style_info_button()).on_hover_text(
+                                "The costs of the partner are added up \
+                                in the tables of the Budget section",
+                            );
+                        });
+                        costs::edit(ui, &mut self.costs, self.id, years);
+                        if ui.button("Add a cost").clicked() {
+                            self.costs.push(Cost::default());
+                        }
                     }
                     Variety::SectionTeam(SectionTeam::ModuleParticipant(
                         ModuleParticipant::Contribution,
@@ -452,6 +562,27 @@ impl Participant {
                         {
                             self.cv.notes.push(Note::new());
                         }
+                        ui.add_space(SPACE_INTERNAL_EDITOR);
+                        ui.horizontal(|ui| {
+                            ui.label("Structured CV:");
+                            ui.add(style_info_button()).on_hover_text(
+                                "The education, positions, selected publications, grants \
+                                and supervision with dates, rendered the same way \
+                                for all participants in the length required by the funder",
+                            );
+                        });
+                        cv::edit(ui, &mut self.cv_entries, self.id);
+                        if ui
+                            .button("Add an entry")
+                            .on_hover_text("Add an entry of the same kind as the last one")
+                            .clicked()
+                        {
+                            let kind = self
+                                .cv_entries
+                                .last()
+                                .map_or(CvKind::default(), |entry| entry.kind);
+                            self.cv_entries.push(CvEntry::new(kind));
+                        }
                     }
                     Variety::SectionTeam(SectionTeam::ModuleParticipant(
                         ModuleParticipant::Resources,
@@ -541,10 +672,13 @@ impl Team {
                             self.participants.iter().for_each(|partner| {
                                 if partner.visible {
                                     ui.add_space(SPACE_INTERNAL_PREVIEW);
-                                    partner.preview(ui, resolution);
+                                    partner.preview(ui, resolution, self.cv_length);
                                 }
                             });
                         }
+                        Variety::SectionTeam(SectionTeam::Effort) => {
+                            effort::preview(ui, &self.work_packages, &self.participants);
+                        }
                         _ => {}
                     }
                 }
@@ -554,7 +688,7 @@ impl Team {
 }
 
 impl Participant {
-    fn preview(&self, ui: &mut egui::Ui, resolution: usize) {
+    fn preview(&self, ui: &mut egui::Ui, resolution: usize, cv_length: CvLength) {
         let chapters = &self.index_list;
         ui.label(
             RichText::new(&self.name)
@@ -587,6 +721,10 @@ impl Participant {
                         ModuleParticipant::Budget,
                     )) => {
                         self.budget.preview(ui, Some(SPACE_INTERNAL_PREVIEW));
+                        if !self.costs.is_empty() {
+                            ui.add_space(SPACE_INTERNAL_PREVIEW);
+                            ui.label(costs::line(&self.costs));
+                        }
                     }
                     Variety::SectionTeam(SectionTeam::ModuleParticipant(
                         ModuleParticipant::Contribution,
@@ -595,6 +733,7 @@ impl Participant {
                     }
                     Variety::SectionTeam(SectionTeam::ModuleParticipant(ModuleParticipant::CV)) => {
                         self.cv.preview(ui, Some(SPACE_INTERNAL_PREVIEW));
+                        cv::preview(ui, &self.cv_entries, cv_length);
                     }
                     Variety::SectionTeam(SectionTeam::ModuleParticipant(
                         ModuleParticipant::Resources,
@@ -612,3 +751,89 @@ impl Participant {
         }
     }
 }
+
+impl Exporter for Team {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.heading(&Self::default().title);
+            } else {
+                document.heading(&self.title);
+            }
+
+            for chapter in &self.index_list {
+                if chapter.tier > document.resolution {
+                    continue;
+                }
+                match chapter.variety {
+                    Variety::SectionTeam(SectionTeam::ProposedPartners) => {
+                        self.proposed_partners.export(document);
+                    }
+                    Variety::SectionTeam(SectionTeam::ProjectLeader) => {
+                        self.project_leader.export(document);
+                    }
+                    Variety::SectionTeam(SectionTeam::IndustrialPartners) => {
+                        self.industrial_partners.export(document);
+                    }
+                    Variety::SectionTeam(SectionTeam::Proponents) => {
+                        self.proponents.export(document);
+                    }
+                    Variety::SectionTeam(SectionTeam::Participants) => {
+                        self.participants.iter().for_each(|partner| {
+                            if partner.visible {
+                                partner.export(document);
+                            }
+                        });
+                    }
+                    Variety::SectionTeam(SectionTeam::Effort) => {
+                        effort::export(document, &self.work_packages, &self.participants);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+impl Exporter for Participant {
+    fn export(&self, document: &mut Document) {
+        document.subheading(&self.name);
+        document.paragraph(&self.role);
+        document.paragraph(&self.affiliation);
+        document.link(&self.hyperlink);
+        document.paragraph(&self.country);
+        document.paragraph(&self.expertise);
+
+        for chapter in &self.index_list {
+            if chapter.tier > document.resolution {
+                continue;
+            }
+            match chapter.variety {
+                Variety::SectionTeam(SectionTeam::ModuleParticipant(ModuleParticipant::Budget)) => {
+                    self.budget.export(document);
+                    if !self.costs.is_empty() {
+                        document.paragraph(&costs::line(&self.costs));
+                    }
+                }
+                Variety::SectionTeam(SectionTeam::ModuleParticipant(
+                    ModuleParticipant::Contribution,
+                )) => {
+                    self.contribution.export(document);
+                }
+                Variety::SectionTeam(SectionTeam::ModuleParticipant(ModuleParticipant::CV)) => {
+                    self.cv.export(document);
+                    cv::export(document, &self.cv_entries, document.cv_length);
+                }
+                Variety::SectionTeam(SectionTeam::ModuleParticipant(
+                    ModuleParticipant::Resources,
+                )) => {
+                    self.resources.export(document);
+                }
+                Variety::SectionTeam(SectionTeam::ModuleParticipant(ModuleParticipant::Team)) => {
+                    self.team.export(document);
+                }
+                _ => {}
+            }
+        }
+    }
+}
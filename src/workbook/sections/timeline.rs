@@ -7,9 +7,11 @@ use serde::{Deserialize, Serialize};
 use crate::workbook::chapter::{Segment, Variety};
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{SPACE_INTERNAL_EDITOR, SPACE_SECTIONS_EDITOR, TITLE_FONT_SIZE};
+use crate::workbook::exporter::{Document, Exporter};
 use crate::workbook::note::{Note, Notes};
 use crate::workbook::renderer::Renderer;
 use crate::workbook::renderer::{SPACE_INTERNAL_PREVIEW, SPACE_SECTIONS_PREVIEW};
+use crate::workbook::search;
 use crate::workbook::visuals::style_info_button;
 
 // SectionTimeline reflects the essence (character, nature) of the contents
@@ -87,7 +89,11 @@ impl Timeline {
         let id = ui.make_persistent_id("collapsing_header_timeline");
 
         ui.add_space(SPACE_SECTIONS_EDITOR);
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::Timeline
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
 
@@ -246,3 +252,48 @@ impl Timeline {
         }
     }
 }
+
+impl Exporter for Timeline {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            if self.title.is_empty() {
+                document.heading(&Self::default().title);
+            } else {
+                document.heading(&self.title);
+            }
+
+            for chapter in &self.index_list {
+                if chapter.tier > document.resolution {
+                    continue;
+                }
+                match chapter.variety {
+                    Variety::SectionTimeline(SectionTimeline::ProjectTiming) => {
+                        document.paragraph(&format!(
+                            "Proposed project start: {:}-{:02}-{:02}",
+                            self.project_start.date.year(),
+                            self.project_start.date.month(),
+                            self.project_start.date.day()
+                        ));
+
+                        let duration = self.duration_years;
+                        if duration == 1. {
+                            document.paragraph(&format!(
+                                "Proposed project duration: {} year",
+                                duration
+                            ));
+                        } else {
+                            document.paragraph(&format!(
+                                "Proposed project duration: {} years",
+                                duration
+                            ));
+                        }
+                    }
+                    Variety::SectionTimeline(SectionTimeline::Milestones) => {
+                        self.milestones.export(document);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -2,11 +2,14 @@ use egui::collapsing_header::CollapsingState;
 use egui::{RichText, TextEdit, Ui};
 use serde::{Deserialize, Serialize};
 
+use crate::workbook::chapter::Variety;
 use crate::workbook::editor::Editor;
 use crate::workbook::editor::{SPACE_INTERNAL_EDITOR, TITLE_FONT_SIZE};
+use crate::workbook::exporter::{Document, Exporter};
 use crate::workbook::note::{Note, Notes};
 use crate::workbook::renderer::Renderer;
 
+use crate::workbook::search;
 use crate::workbook::visuals::style_info_button;
 
 #[derive(Deserialize, Serialize, Clone, Default)]
@@ -20,7 +23,11 @@ pub struct ProjectTitle {
 impl ProjectTitle {
     pub fn edit(&mut self, ui: &mut Ui) {
         let id = ui.make_persistent_id("collapsing_header_project_name");
-        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        search::reveal(ui.ctx(), &mut state, |location| {
+            location.section == Variety::WorkingName
+        });
+        state
             .show_header(ui, |ui| {
                 ui.checkbox(&mut self.visible, "");
 
@@ -43,9 +50,10 @@ impl ProjectTitle {
             .body(|ui| {
                 ui.label("Working name:");
                 ui.vertical_centered_justified(|ui| {
-                    TextEdit::singleline(&mut self.title)
+                    let output = TextEdit::singleline(&mut self.title)
                         .hint_text("Give this project a descriptive working name")
                         .show(ui);
+                    search::scroll_to(ui, &output.response, &self.title);
                 });
 
                 ui.add_space(SPACE_INTERNAL_EDITOR);
@@ -80,3 +88,12 @@ impl ProjectTitle {
         }
     }
 }
+
+impl Exporter for ProjectTitle {
+    fn export(&self, document: &mut Document) {
+        if self.visible {
+            document.title(&self.title);
+            self.options.export(document);
+        }
+    }
+}
@@ -0,0 +1,340 @@
+// Snapshots of the project with the time, the author and the purpose,
+// made on demand or automatically on saving and at an interval.
+// The automatic snapshots are deleted by the retention rules unless tagged.
+
+use chrono::{DateTime, Local};
+use egui::{Context, TextEdit, Ui};
+use serde::{Deserialize, Serialize};
+
+use super::app::Workbook;
+use super::compare::compare;
+use super::project::{Project, ProjectOwner};
+
+// The tags suggested for the snapshots.
+pub const SNAPSHOT_TAGS: [&str; 4] = ["sent to partners", "received", "submitted", "reviewed"];
+
+#[derive(Clone)]
+pub struct Snapshot {
+    // The name of the snapshot is the `record`.
+    pub project: Project,
+    pub created: DateTime<Local>,
+    pub author: ProjectOwner,
+    pub comment: String,
+    pub tag: String,
+    // Made on saving or at the interval, deleted by the retention rules unless tagged.
+    pub automatic: bool,
+}
+
+impl Snapshot {
+    // The copy of the project named according to the timestamp
+    // after the `label` if any, made by the `author`.
+    pub fn new(project: &Project, label: &str, author: ProjectOwner) -> Self {
+        let created = Local::now();
+        // Record the revision by naming it according to the timestamp.
+        let record = created.format("v%H%M%S %Y-%m-%d").to_string();
+        let mut proj = project.clone();
+        proj.record = if label.is_empty() {
+            record
+        } else {
+            format!("{} {}", label, record)
+        };
+        Snapshot {
+            project: proj,
+            created,
+            author,
+            comment: String::new(),
+            tag: String::new(),
+            automatic: false,
+        }
+    }
+
+    // The name of the snapshot as shown in the lists.
+    pub fn name(&self, i: usize) -> String {
+        match (self.project.record.is_empty(), self.tag.is_empty()) {
+            (true, _) => format!("Snapshot {}", i + 1),
+            (false, true) => self.project.record.clone(),
+            (false, false) => format!("{} [{}]", self.project.record, self.tag),
+        }
+    }
+
+    pub fn details(&self) -> String {
+        let mut details = format!(
+            "Created: {}\nAuthor: {}",
+            self.created.format("%Y-%m-%d %H:%M"),
+            self.author.signature()
+        );
+        if self.automatic {
+            details.push_str("\nMade automatically");
+        }
+        if !self.tag.is_empty() {
+            details.push_str(&format!("\nTag: {}", self.tag));
+        }
+        if !self.comment.is_empty() {
+            details.push_str(&format!("\n\n{}", self.comment));
+        }
+        details
+    }
+
+    pub fn edit_details(&mut self, ui: &mut Ui) {
+        ui.label(format!(
+            "Created {} by {}",
+            self.created.format("%Y-%m-%d %H:%M"),
+            self.author.signature()
+        ));
+        ui.horizontal(|ui| {
+            ui.label("Tag:");
+            TextEdit::singleline(&mut self.tag)
+                .hint_text("e.g. sent to partners")
+                .desired_width(140.)
+                .show(ui);
+        });
+        ui.horizontal_wrapped(|ui| {
+            for tag in SNAPSHOT_TAGS {
+                if ui.small_button(tag).clicked() {
+                    self.tag = tag.to_string();
+                }
+            }
+        });
+        TextEdit::multiline(&mut self.comment)
+            .hint_text("Comment")
+            .desired_rows(3)
+            .show(ui);
+        if self.automatic {
+            ui.label(
+                egui::RichText::new("Made automatically: tag it to keep it.")
+                    .small()
+                    .weak(),
+            );
+        }
+    }
+
+    // The automatic snapshot may be deleted by the retention rules.
+    fn is_disposable(&self) -> bool {
+        self.automatic && self.tag.trim().is_empty()
+    }
+}
+
+// The snapshots read from the files of the earlier versions.
+impl From<Project> for Snapshot {
+    fn from(val: Project) -> Self {
+        Snapshot {
+            created: Local::now(),
+            author: val.owner.clone(),
+            comment: String::new(),
+            tag: String::new(),
+            automatic: false,
+            project: val,
+        }
+    }
+}
+
+// The settings of the automatic snapshots and the retention rules.
+#[derive(Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct AutoSnapshots {
+    pub on_save: bool,
+    // Minutes, 0 for no snapshots at the interval.
+    pub interval: u32,
+    // The number of the latest automatic snapshots kept.
+    pub keep_latest: usize,
+    // The number of days the last automatic snapshot of each day is kept.
+    pub keep_daily: i64,
+}
+
+impl Default for AutoSnapshots {
+    fn default() -> Self {
+        Self {
+            on_save: false,
+            interval: 0,
+            keep_latest: 10,
+            keep_daily: 7,
+        }
+    }
+}
+
+impl AutoSnapshots {
+    pub fn edit(&mut self, ui: &mut Ui) {
+        ui.checkbox(&mut self.on_save, "On each save");
+        ui.horizontal(|ui| {
+            ui.label("Every");
+            ui.add(egui::DragValue::new(&mut self.interval).clamp_range(0..=240));
+            ui.label("minutes (0 for never)");
+        });
+        ui.separator();
+        ui.label("Keep the automatic snapshots:")
+            .on_hover_text("The snapshots made manually or tagged are always kept");
+        ui.horizontal(|ui| {
+            ui.label("the latest");
+            ui.add(egui::DragValue::new(&mut self.keep_latest).clamp_range(1..=100));
+        });
+        ui.horizontal(|ui| {
+            ui.label("the last of the day for");
+            ui.add(egui::DragValue::new(&mut self.keep_daily).clamp_range(0..=365));
+            ui.label("days");
+        });
+    }
+}
+
+// Delete the automatic snapshots which are not kept by the rules.
+pub fn prune(snapshots: &mut Vec<Snapshot>, rules: &AutoSnapshots, now: DateTime<Local>) {
+    let mut automatic: Vec<(usize, DateTime<Local>)> = snapshots
+        .iter()
+        .enumerate()
+        .filter(|(_, snapshot)| snapshot.is_disposable())
+        .map(|(i, snapshot)| (i, snapshot.created))
+        .collect();
+    // The latest first
+    automatic.sort_by_key(|&(_, created)| std::cmp::Reverse(created));
+
+    let mut kept = vec![true; snapshots.len()];
+    let mut days = Vec::new();
+    for (n, (i, created)) in automatic.into_iter().enumerate() {
+        let day = created.date_naive();
+        let daily = (now.date_naive() - day).num_days() < rules.keep_daily && !days.contains(&day);
+        if daily {
+            days.push(day);
+        }
+        kept[i] = n < rules.keep_latest || daily;
+    }
+    let mut i = 0;
+    snapshots.retain(|_| {
+        i += 1;
+        kept[i - 1]
+    });
+}
+
+// Make the automatic snapshot unless the texts are the same
+// as in the latest snapshot.
+pub fn auto_snapshot(
+    snapshots: &mut Vec<Snapshot>,
+    project: &Project,
+    rules: &AutoSnapshots,
+    label: &str,
+    author: ProjectOwner,
+) {
+    let latest = snapshots.iter().max_by_key(|snapshot| snapshot.created);
+    if latest.is_some_and(|latest| compare(&latest.project, project).is_empty()) {
+        return;
+    }
+    let mut snapshot = Snapshot::new(project, label, author);
+    snapshot.automatic = true;
+    snapshots.push(snapshot);
+    prune(snapshots, rules, Local::now());
+}
+
+impl Workbook {
+    // Make the snapshot of the current project, the automatic ones
+    // are deleted by the retention rules as after any new snapshot.
+    pub fn make_snapshot(&mut self, label: &str) {
+        let snapshot = Snapshot::new(&self.project, label, self.owner());
+        self.stored_projects.push(snapshot);
+        prune(
+            &mut self.stored_projects,
+            &self.auto_snapshots,
+            Local::now(),
+        );
+    }
+
+    // Make the automatic snapshot when the interval is over.
+    pub fn auto_snapshot_timer(&mut self, ctx: &Context) {
+        let interval = self.auto_snapshots.interval;
+        if interval == 0 {
+            return;
+        }
+        let interval = chrono::Duration::minutes(interval as i64);
+        let elapsed = Local::now() - self.last_auto_snapshot;
+        if elapsed >= interval {
+            let author = self.owner();
+            auto_snapshot(
+                &mut self.stored_projects,
+                &self.project,
+                &self.auto_snapshots,
+                "Auto",
+                author,
+            );
+            self.last_auto_snapshot = Local::now();
+        } else if let Ok(remaining) = (interval - elapsed).to_std() {
+            ctx.request_repaint_after(remaining);
+        }
+    }
+}
+
+// Test that the automatic snapshots are deleted by the retention rules.
+#[test]
+fn snapshot_retention() {
+    use chrono::Duration;
+
+    // Noon, so the hours before are in the same day.
+    let now = Local::now()
+        .date_naive()
+        .and_hms_opt(12, 0, 0)
+        .and_then(|noon| noon.and_local_timezone(Local).earliest())
+        .unwrap();
+    let snapshot = |hours: i64, automatic: bool, tag: &str| Snapshot {
+        created: now - Duration::hours(hours),
+        automatic,
+        tag: tag.to_string(),
+        ..Snapshot::new(
+            &Project::default(),
+            &hours.to_string(),
+            ProjectOwner::default(),
+        )
+    };
+    let mut snapshots = vec![
+        snapshot(24 * 30, false, ""),
+        snapshot(24 * 20, true, "sent to partners"),
+        snapshot(24 * 20 - 1, true, ""),
+        snapshot(24 * 3 + 2, true, ""),
+        snapshot(24 * 3 + 1, true, ""),
+        snapshot(3, true, ""),
+        snapshot(2, true, ""),
+        snapshot(1, true, ""),
+    ];
+    let rules = AutoSnapshots {
+        keep_latest: 2,
+        keep_daily: 7,
+        ..Default::default()
+    };
+    prune(&mut snapshots, &rules, now);
+    let kept: Vec<&str> = snapshots
+        .iter()
+        .map(|snapshot| snapshot.project.record.split(' ').next().unwrap())
+        .collect();
+    // The manual and tagged ones, the last of the day three days ago
+    // and the latest two.
+    assert_eq!(kept, ["720", "480", "73", "2", "1"]);
+
+    // The snapshot made manually is signed by the user, the rules are applied.
+    let mut workbook = Workbook::default();
+    workbook.settings.customer.name = "Jane Doe".to_string();
+    workbook.auto_snapshots.keep_latest = 1;
+    workbook.auto_snapshots.keep_daily = 0;
+    workbook.stored_projects = vec![snapshot(2, true, ""), snapshot(1, true, "")];
+    workbook.make_snapshot("");
+    assert_eq!(workbook.stored_projects.len(), 2);
+    assert!(workbook.stored_projects[0].automatic);
+    assert_eq!(workbook.stored_projects[1].author.name, "Jane Doe");
+}
+
+// Test that the settings of the automatic snapshots are kept with the preferences.
+#[test]
+fn auto_snapshots_preferences() {
+    let workbook = Workbook {
+        auto_snapshots: AutoSnapshots {
+            on_save: true,
+            interval: 15,
+            keep_latest: 3,
+            keep_daily: 30,
+        },
+        ..Default::default()
+    };
+    let stored = serde_json::to_string(&workbook.preferences()).unwrap();
+
+    let mut restored = Workbook::default();
+    restored.set_preferences(serde_json::from_str(&stored).unwrap());
+    assert!(restored.auto_snapshots == workbook.auto_snapshots);
+
+    // The preferences saved by the earlier versions have the default settings.
+    restored.set_preferences(serde_json::from_str("{}").unwrap());
+    assert!(restored.auto_snapshots == AutoSnapshots::default());
+}
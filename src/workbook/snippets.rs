@@ -0,0 +1,321 @@
+// The library of snippets, e.g. the description of the institution or
+// the data protection statement, kept with the preferences apart from
+// the workbooks. Any note is saved as a snippet, the snippets are inserted
+// into the lists of notes, the library is shared with the team as a file.
+
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+
+use egui::{Context, Id, RichText, TextEdit, Ui};
+use serde::{Deserialize, Serialize};
+
+use super::app::{execute, save_file, ImportState, PickedFile, Workbook};
+use super::note::{Note, Notes};
+use super::notifications::Notification;
+
+// The length of the name made of the text of the note.
+const NAME_LENGTH: usize = 40;
+
+#[derive(Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Snippet {
+    pub name: String,
+    pub text: String,
+}
+
+impl Snippet {
+    // The snippet named after the first words of the text.
+    pub fn new(text: &str) -> Self {
+        let line = text.lines().find(|line| !line.trim().is_empty());
+        let line = line.unwrap_or_default().trim();
+        let name: String = line.chars().take(NAME_LENGTH).collect();
+        let mut name = name.trim_end().to_string();
+        if name.len() < line.len() {
+            name.push('…');
+        }
+        Snippet {
+            name,
+            text: text.to_string(),
+        }
+    }
+
+    fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.name.to_lowercase().contains(&query)
+            || self.text.to_lowercase().contains(&query)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct Snippets {
+    pub snippets: Vec<Snippet>,
+}
+
+impl Snippets {
+    // Add the snippet unless the same text is in the library already,
+    // `true` if added.
+    pub fn add(&mut self, snippet: Snippet) -> bool {
+        if snippet.text.trim().is_empty() || self.snippets.iter().any(|s| s.text == snippet.text) {
+            return false;
+        }
+        self.snippets.push(snippet);
+        true
+    }
+
+    pub fn encode_json(&self) -> Vec<u8> {
+        serde_json::to_vec_pretty(self).unwrap_or_default()
+    }
+
+    // Add the snippets of the library shared as a file, the number of snippets added.
+    pub fn import_json(&mut self, contents: &[u8]) -> Result<usize, serde_json::Error> {
+        let library: Snippets = serde_json::from_slice(contents)?;
+        Ok(library
+            .snippets
+            .into_iter()
+            .filter(|snippet| self.add(snippet.clone()))
+            .count())
+    }
+
+    // The library is shared with the editors of notes in the current frame.
+    pub fn share(&self, ctx: &Context) {
+        let snippets = Arc::new(self.clone());
+        ctx.data_mut(|data| data.insert_temp(library_id(), snippets));
+    }
+
+    fn shared(ctx: &Context) -> Option<Arc<Snippets>> {
+        ctx.data(|data| data.get_temp(library_id()))
+    }
+}
+
+fn library_id() -> Id {
+    Id::new("workbook_snippets")
+}
+
+fn queue_id() -> Id {
+    Id::new("workbook_snippets_saved")
+}
+
+fn query_id() -> Id {
+    Id::new("workbook_snippets_query")
+}
+
+// Save the text of the note as a snippet, it is added to the library in the same frame.
+pub fn save(ctx: &Context, text: &str) {
+    ctx.data_mut(|data| {
+        data.get_temp_mut_or_default::<Vec<String>>(queue_id())
+            .push(text.to_string())
+    });
+}
+
+// The menu inserting a snippet of the library into the list of notes,
+// shown when the library is not empty.
+pub fn insert_menu(ui: &mut Ui, notes: &mut Notes) {
+    let Some(library) = Snippets::shared(ui.ctx()) else {
+        return;
+    };
+    if library.snippets.is_empty() {
+        return;
+    }
+    ui.menu_button(RichText::new("📋").color(egui::Color32::GRAY), |ui| {
+        let mut query: String = ui
+            .ctx()
+            .data(|data| data.get_temp(query_id()).unwrap_or_default());
+        let response = ui.add(
+            TextEdit::singleline(&mut query)
+                .hint_text("Search snippets")
+                .desired_width(240.),
+        );
+        if response.changed() {
+            ui.ctx()
+                .data_mut(|data| data.insert_temp(query_id(), query.clone()));
+        }
+        ui.separator();
+        egui::ScrollArea::vertical()
+            .max_height(320.)
+            .show(ui, |ui| {
+                for snippet in library.snippets.iter().filter(|s| s.matches(&query)) {
+                    if ui
+                        .button(&snippet.name)
+                        .on_hover_text(&snippet.text)
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        notes.notes.push(Note {
+                            note: snippet.text.clone(),
+                            ..Note::new()
+                        });
+                    }
+                }
+            });
+    })
+    .response
+    .on_hover_text("Insert a snippet from the library");
+}
+
+fn pick_snippets_file(sender: Sender<PickedFile>) {
+    let task = rfd::AsyncFileDialog::new()
+        .add_filter("Snippet Library", &["json"])
+        .add_filter("All Files", &["*"])
+        .pick_file();
+    execute(async move {
+        let file = task.await;
+        if let Some(file) = file {
+            let contents = file.read().await;
+            let _ = sender.send((ImportState::Snippets, contents));
+        }
+    });
+}
+
+impl Workbook {
+    pub fn import_snippets(&mut self) {
+        match self.snippets.import_json(&self.file_buffer) {
+            Ok(added) => self.notifications.push(Notification::success(
+                "Snippets Imported",
+                format!("{} snippets added to the library", added),
+            )),
+            Err(e) => self.notifications.push(Notification::error(
+                "Import Error",
+                format!("The snippet library cannot be read: {}", e),
+            )),
+        }
+    }
+
+    pub fn show_snippets(&mut self, ctx: &Context) {
+        // The notes saved as snippets in the editor.
+        let saved = ctx.data_mut(|data| data.remove_temp::<Vec<String>>(queue_id()));
+        for text in saved.unwrap_or_default() {
+            let snippet = Snippet::new(&text);
+            let name = snippet.name.clone();
+            if self.snippets.add(snippet) {
+                self.notifications
+                    .push(Notification::success("Saved as Snippet", name));
+            } else {
+                self.notifications.push(Notification::info(
+                    "Snippet Not Saved",
+                    "The same text is in the library already.",
+                ));
+            }
+        }
+
+        let mut open = self.windows.snippets_open;
+        let filter = &mut self.windows.snippets_filter;
+        let snippets = &mut self.snippets.snippets;
+        let mut snippet_to_delete = None;
+        let mut import = false;
+        egui::Window::new("Snippet Library")
+            .open(&mut open)
+            .default_width(420.)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(
+                        "Save any note as a snippet with 📌, \
+                        insert the snippets into the lists of notes with 📋.",
+                    )
+                    .small()
+                    .weak(),
+                );
+                ui.add(
+                    TextEdit::singleline(filter)
+                        .hint_text("Search")
+                        .desired_width(f32::INFINITY),
+                );
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .max_height(420.)
+                    .show(ui, |ui| {
+                        if snippets.is_empty() {
+                            ui.label("…no snippets");
+                        }
+                        for (i, snippet) in snippets.iter_mut().enumerate() {
+                            if !snippet.matches(filter) {
+                                continue;
+                            }
+                            ui.horizontal(|ui| {
+                                ui.add(TextEdit::singleline(&mut snippet.name).desired_width(300.));
+                                if ui
+                                    .button("🗑")
+                                    .on_hover_text("Delete the snippet from the library")
+                                    .clicked()
+                                {
+                                    snippet_to_delete = Some(i);
+                                }
+                            });
+                            ui.add(
+                                TextEdit::multiline(&mut snippet.text)
+                                    .desired_rows(2)
+                                    .desired_width(f32::INFINITY),
+                            );
+                            ui.add_space(6.);
+                        }
+                    });
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Export…")
+                        .on_hover_text("Save the library to share it with the team")
+                        .clicked()
+                    {
+                        let library = Snippets {
+                            snippets: snippets.clone(),
+                        };
+                        save_file(
+                            self.notifications.sender(),
+                            library.encode_json(),
+                            "Snippets.json".to_string(),
+                            ("Snippet Library", "json"),
+                        );
+                    }
+                    if ui
+                        .button("Import…")
+                        .on_hover_text(
+                            "Add the snippets of a library shared by the team, \
+                            the same ones are not repeated",
+                        )
+                        .clicked()
+                    {
+                        import = true;
+                    }
+                });
+            });
+
+        if let Some(i) = snippet_to_delete {
+            snippets.remove(i);
+        }
+        if import {
+            pick_snippets_file(self.file_channel.0.clone());
+        }
+        self.windows.snippets_open = open;
+    }
+}
+
+// Test that the notes are saved as snippets once and the shared library is added.
+#[test]
+fn snippet_library() {
+    let snippet = Snippet::new(
+        "\nThe Institute of Marine Research is the largest research institute in the field.",
+    );
+    assert_eq!(snippet.name, "The Institute of Marine Research is the…");
+    assert!(snippet.matches("marine"));
+    assert!(!snippet.matches("privacy"));
+
+    let mut library = Snippets::default();
+    assert!(library.add(snippet.clone()));
+    assert!(!library.add(snippet));
+    assert!(!library.add(Snippet::new("  ")));
+
+    let shared = Snippets {
+        snippets: vec![
+            library.snippets[0].clone(),
+            Snippet::new("The data are processed according to the GDPR."),
+        ],
+    };
+    assert_eq!(library.import_json(&shared.encode_json()).unwrap(), 1);
+    assert_eq!(library.snippets.len(), 2);
+    assert!(library.import_json(b"Snippets").is_err());
+}
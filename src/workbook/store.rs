@@ -1,3 +1,3 @@
 pub mod open;
 pub mod save;
-pub mod v_b0004;
+pub mod v_b0005;
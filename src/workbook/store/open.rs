@@ -1,8 +1,11 @@
 use chrono::{DateTime, Local, NaiveDate, TimeZone};
 
-use super::v_b0004::{Parent, Rank, YearMonthDay};
-use crate::workbook::sections::{funding::Programme, references::Reference, team::Participant};
-use crate::workbook::store::v_b0004::Store;
+use super::v_b0005::{Parent, Rank, YearMonthDay};
+use crate::workbook::identity::Identity;
+use crate::workbook::sections::{
+    costs::MAX_YEARS, funding::Programme, references::Reference, team::Participant,
+};
+use crate::workbook::store::v_b0005::Store;
 use crate::workbook::{project::Project, sections::timeline::Date};
 
 impl From<YearMonthDay> for Date {
@@ -34,6 +37,8 @@ impl From<Store> for Project {
         let mut ref_source = Reference::default();
         let mut funding_option = Programme::default();
         let mut participant = Participant::default();
+        // The key references of the format B0004.
+        let mut key_references: Vec<Reference> = Vec::new();
         project.owner = val.owner;
         project.record = val.record;
         project.resolution = val.resolution;
@@ -118,10 +123,8 @@ impl From<Store> for Project {
                 Some(Parent {
                     address: 0,
                     variety: Rank::Top,
-                }) => {
-                    if chunk.address == 0 {
-                        project.working_name.options = chunk.body.notes.clone();
-                    }
+                }) if chunk.address == 0 => {
+                    project.working_name.options = chunk.body.notes.clone();
                 }
                 // Idea - Subsections
                 Some(Parent {
@@ -164,7 +167,8 @@ impl From<Store> for Project {
                     let citation = chunk;
                     ref_source.title = citation.title.clone();
                     ref_source.visible = citation.visible;
-                    project.idea.references.references.push(ref_source.clone());
+                    ref_source.id = Identity::new();
+                    key_references.push(ref_source.clone());
                 }
                 // IdeaReferencesCitation
                 // Introduced a special `Rank::IdeaReferencesCitation`
@@ -180,12 +184,11 @@ impl From<Store> for Project {
                     match chunk.address {
                         // IdeaReferencesCitationHyperlink
                         0 => {
-                            project.idea.references.references[source_counter].hyperlink =
-                                citation.body.phrase.clone();
+                            key_references[source_counter].hyperlink = citation.body.phrase.clone();
                         }
                         // IdeaReferencesCitationDetails
                         1 => {
-                            project.idea.references.references[source_counter].source_details =
+                            key_references[source_counter].source_details =
                                 citation.body.notes.clone();
                         }
                         _ => {} // _ => unimplemented!("Reference has 2 subsections")
@@ -396,19 +399,15 @@ impl From<Store> for Project {
                 Some(Parent {
                     address: 7,
                     variety: Rank::Top,
-                }) => {
-                    if chunk.address == 0 {
-                        project.prelim_results.prelim_results = chunk.body.notes.clone();
-                    }
+                }) if chunk.address == 0 => {
+                    project.prelim_results.prelim_results = chunk.body.notes.clone();
                 }
                 // Methodology
                 Some(Parent {
                     address: 8,
                     variety: Rank::Top,
-                }) => {
-                    if chunk.address == 0 {
-                        project.methodology.methodology = chunk.body.notes.clone();
-                    }
+                }) if chunk.address == 0 => {
+                    project.methodology.methodology = chunk.body.notes.clone();
                 }
                 // Outcomes - Subsections
                 Some(Parent {
@@ -458,7 +457,8 @@ impl From<Store> for Project {
                     let citation = chunk;
                     ref_source.title = citation.title.clone();
                     ref_source.visible = citation.visible;
-                    project.literature.references.push(ref_source.clone());
+                    ref_source.id = Identity::new();
+                    project.bibliography.references.push(ref_source.clone());
                 }
                 // LiteratureSourcesCitation
                 // Introduced a special `Rank::LiteratureSourcesCitation`
@@ -474,12 +474,12 @@ impl From<Store> for Project {
                     match chunk.address {
                         // LiteratureCitation - Hyperlink
                         0 => {
-                            project.literature.references[bib_counter].hyperlink =
+                            project.bibliography.references[bib_counter].hyperlink =
                                 citation.body.phrase.clone();
                         }
                         // LiteratureCitation - Details
                         1 => {
-                            project.literature.references[bib_counter].source_details =
+                            project.bibliography.references[bib_counter].source_details =
                                 citation.body.notes.clone();
                         }
                         _ => {} // _ => unimplemented!()
@@ -559,6 +559,57 @@ impl From<Store> for Project {
                 _ => {} // _ => todo!()
             }
         }
+        let extensions = val.extensions;
+        project.citation_style = extensions.citation_style;
+        let bibliography = &mut project.bibliography;
+        for (reference, extension) in bibliography
+            .references
+            .iter_mut()
+            .zip(extensions.references)
+        {
+            reference.id = extension.id;
+            reference.metadata = extension.metadata;
+            reference.reading = extension.reading;
+        }
+        bibliography.key_references = extensions.key_references;
+        // The key references of the format B0004 are found in the bibliography
+        // (or added to it) by the contents.
+        bibliography.merge_key_references(key_references);
+        for (programme, id) in project
+            .funding
+            .funding_options
+            .iter_mut()
+            .zip(extensions.programmes)
+        {
+            programme.id = id;
+        }
+        for (participant, extension) in project
+            .team
+            .participants
+            .iter_mut()
+            .zip(extensions.participants)
+        {
+            participant.id = extension.id;
+            participant.cv_entries = extension.cv_entries;
+            participant.costs = extension.costs;
+            for cost in participant.costs.iter_mut() {
+                cost.year = cost.year.clamp(1, MAX_YEARS);
+            }
+        }
+        project.team.cv_length = extensions.cv_length;
+        project.team.work_packages = extensions.work_packages;
+        // The notes keep their identifiers unless the lists have changed
+        // (e.g. in the earlier versions of the app), the copies get new ones.
+        let mut note_identities = extensions.note_identities.into_iter();
+        project.visit_notes(&mut |notes| {
+            let ids = note_identities.next().unwrap_or_default();
+            if ids.len() == notes.notes.len() {
+                for (note, id) in notes.notes.iter_mut().zip(ids) {
+                    note.id = id;
+                }
+            }
+        });
+        project.identify();
         project
     }
 }
@@ -566,16 +617,27 @@ impl From<Store> for Project {
 // Test the correctness of the storage format conversion into the `Project`.
 #[test]
 fn conversion_from_store() {
-    use super::v_b0004::{Body, Chunk};
+    use super::v_b0005::{Body, Chunk, Extensions, ParticipantExtension, ReferenceExtension};
+    use crate::workbook::identity::Identity;
     use crate::workbook::note::{Note, Notes};
-    use crate::workbook::sections::scripting::ScriptingOption;
-    use crate::workbook::sections::timeline::Date;
+    use crate::workbook::reference_formats::citation_style::CitationStyle;
+    use crate::workbook::sections::{
+        costs::{Cost, CostCategory},
+        cv::{CvEntry, CvKind, CvLength},
+        effort::{Effort, WorkPackage},
+        references::{Reading, ReadingStatus, ReferenceMetadata},
+        scripting::ScriptingOption,
+        timeline::Date,
+    };
 
+    let reference = Identity::new();
+    let partner = Identity::new();
+    let package = Identity::new();
     let test = &Store {
-        format: "B0004".to_string(),
         owner: crate::workbook::project::ProjectOwner {
             email: "abc@email.tst".to_string(),
             account: "abc123".to_string(),
+            name: "Abc Def".to_string(),
         },
         record: "test_record".to_string(),
         resolution: 3,
@@ -929,7 +991,7 @@ fn conversion_from_store() {
                 body: Body {
                     date: Date {
                         day: 31,
-                        month: 03,
+                        month: 3,
                         year: 2025,
                         ..Default::default() // *** TESTING ONLY!!! ***
                     }
@@ -955,7 +1017,7 @@ fn conversion_from_store() {
                 ..Default::default()
             },
             /* TODO: Funding Option 2 - Details: annotation, hyperlink and deadline
-            */
+             */
             // 20
             Chunk {
                 // Scope
@@ -2349,14 +2411,71 @@ fn conversion_from_store() {
                 ..Default::default()
             },
         ],
+        extensions: Extensions {
+            citation_style: CitationStyle::Vancouver,
+            references: vec![
+                ReferenceExtension::default(),
+                ReferenceExtension {
+                    id: reference,
+                    metadata: ReferenceMetadata {
+                        authors: "Smith, John; Doe, Ann".to_string(),
+                        key: "smith2021".to_string(),
+                        ..Default::default()
+                    },
+                    reading: Reading {
+                        status: ReadingStatus::Reading,
+                        ..Default::default()
+                    },
+                },
+            ],
+            participants: vec![
+                ParticipantExtension {
+                    id: partner,
+                    cv_entries: vec![CvEntry {
+                        kind: CvKind::Education,
+                        from: "2011".to_string(),
+                        to: "2015".to_string(),
+                        title: "PhD in Oceanography".to_string(),
+                        place: "University of Oslo".to_string(),
+                        visible: true,
+                    }],
+                    costs: vec![
+                        Cost {
+                            category: CostCategory::Personnel,
+                            year: 3,
+                            amount: 42000.,
+                            description: "PhD student 50%".to_string(),
+                        },
+                        Cost {
+                            category: CostCategory::Materials,
+                            year: usize::MAX,
+                            amount: 16_777_217.,
+                            description: String::new(),
+                        },
+                    ],
+                },
+                ParticipantExtension::default(),
+            ],
+            cv_length: CvLength::OnePage,
+            work_packages: vec![WorkPackage {
+                title: "Field work".to_string(),
+                effort: vec![Effort {
+                    participant: partner,
+                    months: 12.5,
+                }],
+                id: package,
+            }],
+            ..Default::default()
+        },
         ..Default::default()
     };
 
     // Chapter counter.
     let mut nn: usize;
-    let mut parsed = crate::workbook::app::Workbook::default();
-
-    parsed.project = (*test).clone().into();
+    let parsed = crate::workbook::app::Workbook {
+        project: (*test).clone().into(),
+        ..Default::default()
+    };
 
     // nn = 0;
 
@@ -2532,15 +2651,18 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[8].visible,
-        parsed.project.idea.references.references[0].visible
+        parsed
+            .project
+            .bibliography
+            .is_key_reference(parsed.project.bibliography.references[2].id)
     );
     assert_eq!(
         test.chunks[8].title,
-        parsed.project.idea.references.references[0].title
+        parsed.project.bibliography.references[2].title
     );
     assert_eq!(
         "Key source 1".to_string(),
-        parsed.project.idea.references.references[0].title
+        parsed.project.bibliography.references[2].title
     );
 
     assert_eq!(test.chunks[9].address, 0);
@@ -2553,11 +2675,11 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[9].body.phrase,
-        parsed.project.idea.references.references[0].hyperlink
+        parsed.project.bibliography.references[2].hyperlink
     );
     assert_eq!(
         "link source 1".to_string(),
-        parsed.project.idea.references.references[0].hyperlink
+        parsed.project.bibliography.references[2].hyperlink
     );
 
     assert_eq!(test.chunks[10].address, 1);
@@ -2570,21 +2692,21 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[10].body.notes.notes[0].visible,
-        parsed.project.idea.references.references[0]
+        parsed.project.bibliography.references[2]
             .source_details
             .notes[0]
             .visible
     );
     assert_eq!(
         test.chunks[10].body.notes.notes[0].note,
-        parsed.project.idea.references.references[0]
+        parsed.project.bibliography.references[2]
             .source_details
             .notes[0]
             .note
     );
     assert_eq!(
         "Source 1 details".to_string(),
-        parsed.project.idea.references.references[0]
+        parsed.project.bibliography.references[2]
             .source_details
             .notes[0]
             .note
@@ -2600,15 +2722,18 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[11].visible,
-        parsed.project.idea.references.references[1].visible
+        parsed
+            .project
+            .bibliography
+            .is_key_reference(parsed.project.bibliography.references[3].id)
     );
     assert_eq!(
         test.chunks[11].title,
-        parsed.project.idea.references.references[1].title
+        parsed.project.bibliography.references[3].title
     );
     assert_eq!(
         "Key source 2".to_string(),
-        parsed.project.idea.references.references[1].title
+        parsed.project.bibliography.references[3].title
     );
 
     assert_eq!(test.chunks[12].address, 0);
@@ -2621,11 +2746,11 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[12].body.phrase,
-        parsed.project.idea.references.references[1].hyperlink
+        parsed.project.bibliography.references[3].hyperlink
     );
     assert_eq!(
         "link source 2".to_string(),
-        parsed.project.idea.references.references[1].hyperlink
+        parsed.project.bibliography.references[3].hyperlink
     );
 
     assert_eq!(test.chunks[13].address, 1);
@@ -2638,21 +2763,21 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[13].body.notes.notes[0].visible,
-        parsed.project.idea.references.references[1]
+        parsed.project.bibliography.references[3]
             .source_details
             .notes[0]
             .visible
     );
     assert_eq!(
         test.chunks[13].body.notes.notes[0].note,
-        parsed.project.idea.references.references[1]
+        parsed.project.bibliography.references[3]
             .source_details
             .notes[0]
             .note
     );
     assert_eq!(
         "Source 2 details".to_string(),
-        parsed.project.idea.references.references[1]
+        parsed.project.bibliography.references[3]
             .source_details
             .notes[0]
             .note
@@ -2766,7 +2891,7 @@ fn conversion_from_store() {
 
     /*
     // TODO: Funding Option 2 - Details: annotation, hyperlink and deadline
-    */
+     */
 
     assert_eq!(test.chunks[20].address, 3);
     assert_eq!(test.chunks[20].parent, None);
@@ -3825,15 +3950,15 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[nn].visible,
-        parsed.project.literature.references[0].visible
+        parsed.project.bibliography.references[0].visible
     );
     assert_eq!(
         test.chunks[nn].title,
-        parsed.project.literature.references[0].title
+        parsed.project.bibliography.references[0].title
     );
     assert_eq!(
         "Literature source 1".to_string(),
-        parsed.project.literature.references[0].title
+        parsed.project.bibliography.references[0].title
     );
 
     nn += 1;
@@ -3848,11 +3973,11 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[nn].body.phrase,
-        parsed.project.literature.references[0].hyperlink
+        parsed.project.bibliography.references[0].hyperlink
     );
     assert_eq!(
         "Literature link source 1".to_string(),
-        parsed.project.literature.references[0].hyperlink
+        parsed.project.bibliography.references[0].hyperlink
     );
 
     nn += 1;
@@ -3867,15 +3992,24 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[nn].body.notes.notes[0].visible,
-        parsed.project.literature.references[0].source_details.notes[0].visible
+        parsed.project.bibliography.references[0]
+            .source_details
+            .notes[0]
+            .visible
     );
     assert_eq!(
         test.chunks[nn].body.notes.notes[0].note,
-        parsed.project.literature.references[0].source_details.notes[0].note
+        parsed.project.bibliography.references[0]
+            .source_details
+            .notes[0]
+            .note
     );
     assert_eq!(
         "Literature Source 1 details".to_string(),
-        parsed.project.literature.references[0].source_details.notes[0].note
+        parsed.project.bibliography.references[0]
+            .source_details
+            .notes[0]
+            .note
     );
 
     nn += 1;
@@ -3890,15 +4024,15 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[nn].visible,
-        parsed.project.literature.references[1].visible
+        parsed.project.bibliography.references[1].visible
     );
     assert_eq!(
         test.chunks[nn].title,
-        parsed.project.literature.references[1].title
+        parsed.project.bibliography.references[1].title
     );
     assert_eq!(
         "Literature source 2".to_string(),
-        parsed.project.literature.references[1].title
+        parsed.project.bibliography.references[1].title
     );
 
     nn += 1;
@@ -3913,11 +4047,11 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[nn].body.phrase,
-        parsed.project.literature.references[1].hyperlink
+        parsed.project.bibliography.references[1].hyperlink
     );
     assert_eq!(
         "Literature link source 2".to_string(),
-        parsed.project.literature.references[1].hyperlink
+        parsed.project.bibliography.references[1].hyperlink
     );
 
     nn += 1;
@@ -3932,15 +4066,24 @@ fn conversion_from_store() {
     );
     assert_eq!(
         test.chunks[nn].body.notes.notes[0].visible,
-        parsed.project.literature.references[1].source_details.notes[0].visible
+        parsed.project.bibliography.references[1]
+            .source_details
+            .notes[0]
+            .visible
     );
     assert_eq!(
         test.chunks[nn].body.notes.notes[0].note,
-        parsed.project.literature.references[1].source_details.notes[0].note
+        parsed.project.bibliography.references[1]
+            .source_details
+            .notes[0]
+            .note
     );
     assert_eq!(
         "Literature Source 2 details".to_string(),
-        parsed.project.literature.references[1].source_details.notes[0].note
+        parsed.project.bibliography.references[1]
+            .source_details
+            .notes[0]
+            .note
     );
 
     nn += 1;
@@ -4263,4 +4406,58 @@ fn conversion_from_store() {
         "Attachments Other note 1".to_string(),
         parsed.project.attachments.other.notes[0].note
     );
+
+    nn += 1;
+
+    assert_eq!(test.chunks.len(), nn);
+
+    // Extensions
+    assert_eq!(parsed.project.citation_style, CitationStyle::Vancouver);
+
+    let references = &parsed.project.bibliography.references;
+    assert_eq!(references[1].id, reference);
+    assert_eq!(
+        references[1].metadata.authors,
+        "Smith, John; Doe, Ann".to_string()
+    );
+    assert_eq!(references[0].metadata.authors, String::new());
+    assert_eq!(references[1].citation_key(), "smith2021".to_string());
+    assert_eq!(references[1].reading.status, ReadingStatus::Reading);
+
+    let participants = &parsed.project.team.participants;
+    assert_eq!(participants[0].id, partner);
+    let entry = &participants[0].cv_entries[0];
+    assert_eq!(entry.kind, CvKind::Education);
+    assert_eq!(
+        entry.line(),
+        "2011–2015: PhD in Oceanography, University of Oslo"
+    );
+    assert!(participants[1].cv_entries.is_empty());
+
+    let cost = &participants[0].costs[0];
+    assert_eq!(cost.category, CostCategory::Personnel);
+    assert_eq!(cost.year, 3);
+    assert_eq!(cost.amount, 42000.);
+    assert_eq!(cost.description, "PhD student 50%".to_string());
+    let cost = &participants[0].costs[1];
+    assert_eq!(cost.year, MAX_YEARS);
+    assert_eq!(cost.amount, 16_777_217.);
+    assert!(participants[1].costs.is_empty());
+
+    assert_eq!(parsed.project.team.cv_length, CvLength::OnePage);
+
+    let work_package = &parsed.project.team.work_packages[0];
+    assert_eq!(work_package.id, package);
+    assert_eq!(work_package.title, "Field work".to_string());
+    assert_eq!(work_package.months(partner), 12.5);
+
+    // The key references are added to the bibliography,
+    // but not listed in the Literature Survey.
+    assert_eq!(parsed.project.bibliography.references.len(), 4);
+    let references = &parsed.project.bibliography.references;
+    assert_eq!(
+        parsed.project.bibliography.key_references,
+        vec![references[2].id, references[3].id]
+    );
+    assert!(!parsed.project.bibliography.references[2].visible);
 }
@@ -9,9 +9,12 @@ use crate::workbook::sections::{
     timeline::{Date, SectionTimeline},
 };
 
-use super::v_b0004::{Association, Body, Chunk, Parent, Rank, Store, YearMonthDay};
+use super::v_b0005::{
+    Association, Body, Chunk, Extensions, Parent, ParticipantExtension, Rank, ReferenceExtension,
+    Store, YearMonthDay,
+};
 
-pub const DATA_FORMAT_VERSION: &str = super::v_b0004::DATA_FORMAT_VERSION;
+pub const DATA_FORMAT_VERSION: &str = super::v_b0005::DATA_FORMAT_VERSION;
 
 trait Stock {
     fn stock(&self) -> Self;
@@ -28,7 +31,7 @@ impl From<Date> for YearMonthDay {
 }
 
 impl From<Project> for Store {
-    fn from(val: Project) -> Self {
+    fn from(mut val: Project) -> Self {
         // Use the association array, not the `&self.index_list`,
         // so that the order of sections be relatively stable
         // and the mapping doesn't depend on the order in which
@@ -37,9 +40,9 @@ impl From<Project> for Store {
         let mut storage = Store {
             format: DATA_FORMAT_VERSION.to_string(),
             owner: val.owner.clone(),
-            record: val.record,
+            record: val.record.clone(),
             resolution: val.resolution,
-            chunks: Vec::new(),
+            ..Default::default()
         };
 
         let empty_elem = Chunk::default();
@@ -275,78 +278,8 @@ impl From<Project> for Store {
                                     element.title = empty_elem.title.clone();
                                     element.visible = empty_elem.visible;
                                 }
-                                // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-                                // Subsections
-                                // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-                                let references = &val.idea.references.references;
-                                for (k, ref_source) in references.iter().enumerate() {
-                                    // Set new field values.
-                                    element.visible = ref_source.visible;
-                                    // Same visibility, as above, for all segments.
-                                    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-                                    // Title
-                                    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-                                    // IdeaReferencesCitation = IdeaReferencesCitationName
-                                    element.address = k;
-                                    // This parent is for the title segment only.
-                                    element.parent = Some(
-                                        // IdeaReferences
-                                        // Variant::IdeaReferences,
-                                        Parent {
-                                            address: j,
-                                            variety: Rank::IdeaReferences,
-                                        },
-                                    );
-                                    element.title = ref_source.title.clone();
-                                    storage.chunks.push(element.clone());
-                                    // Reset Naïve field values to "empty".
-                                    {
-                                        element.address = empty_elem.address;
-                                        element.body = empty_elem.body.clone();
-                                        element.parent = empty_elem.parent.clone();
-                                        element.title = empty_elem.title.clone();
-                                        // element.visible = empty_elem.visible;
-                                    }
-                                    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-                                    // Segments
-                                    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-                                    // Set new field values.
-                                    // Same parent for all segments below.
-                                    element.parent = Some(
-                                        // IdeaReferencesCitation
-                                        // Variant::IdeaReferencesCitation,
-                                        Parent {
-                                            address: k,
-                                            variety: Rank::IdeaReferencesCitation,
-                                        },
-                                    );
-                                    // IdeaReferencesCitationHyperlink
-                                    // Set new field values.
-                                    element.address = 0;
-                                    element.body.phrase = ref_source.hyperlink.clone();
-                                    storage.chunks.push(element.clone());
-                                    // Reset Naïve field values to "empty".
-                                    {
-                                        element.address = empty_elem.address;
-                                        element.body = empty_elem.body.clone();
-                                        // element.parent = empty_elem.parent.clone();
-                                        element.title = empty_elem.title.clone();
-                                        // element.visible = empty_elem.visible;
-                                    }
-                                    // IdeaReferencesCitationDetails
-                                    // Set new field values.
-                                    element.address = 1;
-                                    element.body.notes = ref_source.source_details.stock();
-                                    storage.chunks.push(element.clone());
-                                    // Reset all Naïve field values to "empty".
-                                    {
-                                        element.address = empty_elem.address;
-                                        element.body = empty_elem.body.clone();
-                                        element.parent = empty_elem.parent.clone();
-                                        element.title = empty_elem.title.clone();
-                                        element.visible = empty_elem.visible;
-                                    }
-                                }
+                                // The key references are kept in the bibliography,
+                                // see `Extensions::key_references`.
                             }
                             // _ => {}
                             _ => unreachable!(),
@@ -952,7 +885,7 @@ impl From<Project> for Store {
                     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
                     // Literature References
                     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-                    let bibliography = &val.literature.references;
+                    let bibliography = &val.bibliography.references;
                     for (j, bib_source) in bibliography.iter().enumerate() {
                         // Set new field values.
                         element.visible = bib_source.visible;
@@ -1256,6 +1189,59 @@ impl From<Project> for Store {
                 _ => {}
             }
         }
+
+        // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Extensions
+        // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        let mut note_identities = Vec::new();
+        val.visit_notes(&mut |notes| {
+            note_identities.push(notes.stock().notes.iter().map(|note| note.id).collect());
+        });
+        let participants = &val.team.participants;
+        storage.extensions = Extensions {
+            citation_style: val.citation_style,
+            references: val
+                .bibliography
+                .references
+                .iter()
+                .map(|reference| ReferenceExtension {
+                    id: reference.id,
+                    metadata: reference.metadata.clone(),
+                    reading: reference.reading.clone(),
+                })
+                .collect(),
+            key_references: val.bibliography.key_references.clone(),
+            programmes: val
+                .funding
+                .funding_options
+                .iter()
+                .map(|programme| programme.id)
+                .collect(),
+            participants: participants
+                .iter()
+                .map(|partner| ParticipantExtension {
+                    id: partner.id,
+                    cv_entries: partner.cv_entries.clone(),
+                    costs: partner.costs.clone(),
+                })
+                .collect(),
+            cv_length: val.team.cv_length,
+            // The effort of the participants not in the project is dropped.
+            work_packages: val
+                .team
+                .work_packages
+                .iter()
+                .map(|package| {
+                    let mut package = package.clone();
+                    package
+                        .effort
+                        .retain(|effort| participants.iter().any(|p| p.id == effort.participant));
+                    package
+                })
+                .collect(),
+            note_identities,
+        };
+
         storage
     }
 }
@@ -1278,18 +1264,26 @@ impl Stock for Notes {
 fn conversion_into_store() {
     use chrono::Datelike;
 
+    use crate::workbook::identity::Identity;
     use crate::workbook::note::Note;
     use crate::workbook::project::ProjectOwner;
+    use crate::workbook::reference_formats::citation_style::CitationStyle;
     use crate::workbook::sections::{
         attachments::Attachments,
         budget::Budget,
+        costs::{Cost, CostCategory},
+        cv::{CvEntry, CvKind, CvLength},
+        effort::WorkPackage,
         funding::{FundingOptions, Programme},
         idea::Idea,
         literature::Literature,
         methodology::Methodology,
         outcomes::Outcomes,
         prelim_results::PrelimResults,
-        references::{Reference, References},
+        references::{
+            Bibliography, Reading, ReadingStatus, Reference, ReferenceMetadata, References,
+            SourceKind,
+        },
         resources::Resources,
         scope::Scope,
         scripting::{Scripting, ScriptingOption},
@@ -1302,9 +1296,11 @@ fn conversion_into_store() {
     test.owner = ProjectOwner {
         email: "abc@email.tst".to_string(),
         account: "abc123".to_string(),
+        name: "Abc Def".to_string(),
     };
     test.record = "test_record".to_string();
     test.resolution = 3;
+    test.citation_style = CitationStyle::Ieee;
 
     test.working_name = ProjectTitle {
         title: "Test project name".to_string(),
@@ -1338,32 +1334,6 @@ fn conversion_into_store() {
         description: Notes::default(),
         references: References {
             title: "Key References".to_string(),
-            references: vec![
-                Reference {
-                    title: "Key source 1".to_string(),
-                    hyperlink: "link source 1".to_string(),
-                    source_details: Notes {
-                        notes: vec![Note {
-                            note: "Source 1 details".to_string(),
-                            visible: true,
-                            ..Default::default()
-                        }],
-                    },
-                    visible: true,
-                },
-                Reference {
-                    title: "Key source 2".to_string(),
-                    hyperlink: "link source 2".to_string(),
-                    source_details: Notes {
-                        notes: vec![Note {
-                            note: "Source 2 details".to_string(),
-                            visible: true,
-                            ..Default::default()
-                        }],
-                    },
-                    visible: true,
-                },
-            ],
             visible: true,
         },
         visible: true,
@@ -1387,11 +1357,12 @@ fn conversion_into_store() {
                     ..Default::default()
                 },
                 visible: true,
+                ..Default::default()
             },
             Programme {
                 title: "Funding Option 2".to_string(),
                 /* TODO: Funding Option 2 - Details: annotation, hyperlink and deadline
-                */
+                 */
                 ..Default::default()
             },
         ],
@@ -1542,6 +1513,18 @@ fn conversion_into_store() {
                         ..Default::default()
                     }],
                 },
+                cv_entries: vec![CvEntry {
+                    from: "2019-09".to_string(),
+                    title: "Partner 1 position".to_string(),
+                    place: "Partner 1\nplace".to_string(),
+                    ..CvEntry::new(CvKind::Position)
+                }],
+                costs: vec![Cost {
+                    category: CostCategory::Materials,
+                    year: 2,
+                    amount: 3500.,
+                    description: "Partner 1\ntravel".to_string(),
+                }],
                 visible: true,
                 ..Default::default()
             },
@@ -1591,9 +1574,17 @@ fn conversion_into_store() {
                 ..Default::default()
             },
         ],
+        cv_length: CvLength::ShortBio,
+        work_packages: vec![WorkPackage {
+            title: "Work package 1".to_string(),
+            ..Default::default()
+        }],
         visible: true,
         ..Default::default()
     };
+    let partner = test.team.participants[1].id;
+    test.team.work_packages[0].set_months(partner, 12.5);
+    test.team.work_packages[0].set_months(Identity::new(), 3.);
 
     test.prelim_results = PrelimResults {
         title: "Preliminary Results".to_string(),
@@ -1655,6 +1646,11 @@ fn conversion_into_store() {
                 ..Default::default()
             }],
         },
+        visible: true,
+    };
+
+    // The key sources are listed in the Literature Survey, too.
+    test.bibliography = Bibliography {
         references: vec![
             Reference {
                 title: "Literature source 1".to_string(),
@@ -1667,6 +1663,13 @@ fn conversion_into_store() {
                     }],
                 },
                 visible: true,
+                reading: Reading {
+                    status: ReadingStatus::Read,
+                    relevance: 4,
+                    annotation: "Literature Source 1 annotation".to_string(),
+                    ..Default::default()
+                },
+                ..Default::default()
             },
             Reference {
                 title: "Literature source 2".to_string(),
@@ -1679,11 +1682,47 @@ fn conversion_into_store() {
                     }],
                 },
                 visible: true,
+                ..Default::default()
+            },
+            Reference {
+                title: "Key source 1".to_string(),
+                hyperlink: "link source 1".to_string(),
+                source_details: Notes {
+                    notes: vec![Note {
+                        note: "Source 1 details".to_string(),
+                        visible: true,
+                        ..Default::default()
+                    }],
+                },
+                visible: true,
+                metadata: ReferenceMetadata {
+                    kind: SourceKind::Book,
+                    authors: "Smith, John; Doe, Ann".to_string(),
+                    year: "2021".to_string(),
+                    doi: "10.1000/xyz123".to_string(),
+                    key: "smith2021".to_string(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Reference {
+                title: "Key source 2".to_string(),
+                hyperlink: "link source 2".to_string(),
+                source_details: Notes {
+                    notes: vec![Note {
+                        note: "Source 2 details".to_string(),
+                        visible: true,
+                        ..Default::default()
+                    }],
+                },
+                visible: true,
+                ..Default::default()
             },
         ],
-        visible: true,
-        ..Default::default()
+        key_references: Vec::new(),
     };
+    let references = &test.bibliography.references;
+    test.bibliography.key_references = vec![references[2].id, references[3].id];
 
     test.resources = Resources {
         title: "Resources".to_string(),
@@ -1702,7 +1741,6 @@ fn conversion_into_store() {
             }],
         },
         visible: true,
-        ..Default::default()
     };
 
     test.budget = Budget {
@@ -1777,7 +1815,6 @@ fn conversion_into_store() {
             }],
         },
         visible: true,
-        ..Default::default()
     };
 
     // Chapter counter.
@@ -1926,479 +1963,367 @@ fn conversion_into_store() {
     assert_eq!(stored.chunks[7].title, test.idea.references.title);
     assert_eq!(stored.chunks[7].title, "Key References".to_string());
 
-    // IdeaReferencesCitation = IdeaReferencesCitationName
-    assert_eq!(stored.chunks[8].address, 0);
-    assert_eq!(
-        stored.chunks[8].parent,
-        Some(Parent {
-            address: 4,
-            variety: Rank::IdeaReferences
-        })
-    );
-    assert_eq!(
-        stored.chunks[8].visible,
-        test.idea.references.references[0].visible
-    );
-    assert_eq!(
-        stored.chunks[8].title,
-        test.idea.references.references[0].title
-    );
-    assert_eq!(stored.chunks[8].title, "Key source 1".to_string());
-
-    // IdeaReferencesCitationHyperlink
-    assert_eq!(stored.chunks[9].address, 0);
-    assert_eq!(
-        stored.chunks[9].parent,
-        Some(Parent {
-            address: 0,
-            variety: Rank::IdeaReferencesCitation
-        })
-    );
-    assert_eq!(
-        stored.chunks[9].body.phrase,
-        test.idea.references.references[0].hyperlink
-    );
-    assert_eq!(stored.chunks[9].body.phrase, "link source 1".to_string());
-
-    // IdeaReferencesCitationDetails
-    assert_eq!(stored.chunks[10].address, 1);
-    assert_eq!(
-        stored.chunks[10].parent,
-        Some(Parent {
-            address: 0,
-            variety: Rank::IdeaReferencesCitation
-        })
-    );
-    assert_eq!(
-        stored.chunks[10].body.notes.notes[0].visible,
-        test.idea.references.references[0].source_details.notes[0].visible
-    );
-    assert_eq!(
-        stored.chunks[10].body.notes.notes[0].note,
-        test.idea.references.references[0].source_details.notes[0].note
-    );
-    assert_eq!(
-        stored.chunks[10].body.notes.notes[0].note,
-        "Source 1 details".to_string()
-    );
-
-    // IdeaReferencesCitation = IdeaReferencesCitationName
-    assert_eq!(stored.chunks[11].address, 1);
-    assert_eq!(
-        stored.chunks[11].parent,
-        Some(Parent {
-            address: 4,
-            variety: Rank::IdeaReferences
-        })
-    );
-    assert_eq!(
-        stored.chunks[11].visible,
-        test.idea.references.references[1].visible
-    );
-    assert_eq!(
-        stored.chunks[11].title,
-        test.idea.references.references[1].title
-    );
-    assert_eq!(stored.chunks[11].title, "Key source 2".to_string());
-
-    // IdeaReferencesCitationHyperlink
-    assert_eq!(stored.chunks[12].address, 0);
-    assert_eq!(
-        stored.chunks[12].parent,
-        Some(Parent {
-            address: 1,
-            variety: Rank::IdeaReferencesCitation
-        })
-    );
-    assert_eq!(
-        stored.chunks[12].body.phrase,
-        test.idea.references.references[1].hyperlink
-    );
-    assert_eq!(stored.chunks[12].body.phrase, "link source 2".to_string());
-
-    // IdeaReferencesCitationDetails
-    assert_eq!(stored.chunks[13].address, 1);
-    assert_eq!(
-        stored.chunks[13].parent,
-        Some(Parent {
-            address: 1,
-            variety: Rank::IdeaReferencesCitation
-        })
-    );
-    assert_eq!(
-        stored.chunks[13].body.notes.notes[0].visible,
-        test.idea.references.references[1].source_details.notes[0].visible
-    );
-    assert_eq!(
-        stored.chunks[13].body.notes.notes[0].note,
-        test.idea.references.references[1].source_details.notes[0].note
-    );
-    assert_eq!(
-        stored.chunks[13].body.notes.notes[0].note,
-        "Source 2 details".to_string()
-    );
-
     // Funding
-    assert_eq!(stored.chunks[14].address, 2);
-    assert_eq!(stored.chunks[14].parent, None);
-    assert_eq!(stored.chunks[14].visible, test.funding.visible);
-    assert_eq!(stored.chunks[14].title, test.funding.title);
-    assert_eq!(stored.chunks[14].title, "Funding Options".to_string());
+    assert_eq!(stored.chunks[8].address, 2);
+    assert_eq!(stored.chunks[8].parent, None);
+    assert_eq!(stored.chunks[8].visible, test.funding.visible);
+    assert_eq!(stored.chunks[8].title, test.funding.title);
+    assert_eq!(stored.chunks[8].title, "Funding Options".to_string());
 
     // FundingOptions
 
     // FundingProgram = FundingProgramName
-    assert_eq!(stored.chunks[15].address, 0);
+    assert_eq!(stored.chunks[9].address, 0);
     assert_eq!(
-        stored.chunks[15].parent,
+        stored.chunks[9].parent,
         Some(Parent {
             address: 2,
             variety: Rank::Top
         })
     );
     assert_eq!(
-        stored.chunks[15].visible,
+        stored.chunks[9].visible,
         test.funding.funding_options[0].visible
     );
     assert_eq!(
-        stored.chunks[15].title,
+        stored.chunks[9].title,
         test.funding.funding_options[0].title
     );
-    assert_eq!(stored.chunks[15].title, "Funding Option 1".to_string());
+    assert_eq!(stored.chunks[9].title, "Funding Option 1".to_string());
 
     // Reset Naïve field values to "empty"
-    assert_eq!(stored.chunks[15].body.phrase, "".to_string());
-    assert_eq!(stored.chunks[15].body.notes.notes.len(), 0);
+    assert_eq!(stored.chunks[9].body.phrase, "".to_string());
+    assert_eq!(stored.chunks[9].body.notes.notes.len(), 0);
 
     // FundingProgramAnnotation
-    assert_eq!(stored.chunks[16].address, 0);
+    assert_eq!(stored.chunks[10].address, 0);
     assert_eq!(
-        stored.chunks[16].parent,
+        stored.chunks[10].parent,
         Some(Parent {
             address: 0,
             variety: Rank::FundingProgram
         })
     );
     assert_eq!(
-        stored.chunks[16].body.notes.notes[0].visible,
+        stored.chunks[10].body.notes.notes[0].visible,
         test.funding.funding_options[0].annotation.notes[0].visible
     );
     assert_eq!(
-        stored.chunks[16].body.notes.notes[0].note,
+        stored.chunks[10].body.notes.notes[0].note,
         test.funding.funding_options[0].annotation.notes[0].note
     );
     assert_eq!(
-        stored.chunks[16].body.notes.notes[0].note,
+        stored.chunks[10].body.notes.notes[0].note,
         "Funding Option 1 annotation".to_string()
     );
 
     // Reset Naïve field values to "empty"
-    assert_eq!(stored.chunks[16].title, "".to_string());
-    assert_eq!(stored.chunks[16].body.phrase, "".to_string());
+    assert_eq!(stored.chunks[10].title, "".to_string());
+    assert_eq!(stored.chunks[10].body.phrase, "".to_string());
 
     // FundingProgramHyperlink
-    assert_eq!(stored.chunks[17].address, 1);
+    assert_eq!(stored.chunks[11].address, 1);
     assert_eq!(
-        stored.chunks[17].parent,
+        stored.chunks[11].parent,
         Some(Parent {
             address: 0,
             variety: Rank::FundingProgram
         })
     );
     assert_eq!(
-        stored.chunks[17].body.phrase,
+        stored.chunks[11].body.phrase,
         test.funding.funding_options[0].hyperlink
     );
     assert_eq!(
-        stored.chunks[17].body.phrase,
+        stored.chunks[11].body.phrase,
         "link funding option 1".to_string()
     );
 
     // FundingProgramDeadline
-    assert_eq!(stored.chunks[18].address, 2);
+    assert_eq!(stored.chunks[12].address, 2);
     assert_eq!(
-        stored.chunks[18].parent,
+        stored.chunks[12].parent,
         Some(Parent {
             address: 0,
             variety: Rank::FundingProgram
         })
     );
     assert_eq!(
-        stored.chunks[18].body.date.year,
+        stored.chunks[12].body.date.year,
         test.funding.funding_options[0].deadline.year
     ); // default deadline
     assert_eq!(
-        stored.chunks[18].body.date.month,
+        stored.chunks[12].body.date.month,
         test.funding.funding_options[0].deadline.month
     ); // default deadline
     assert_eq!(
-        stored.chunks[18].body.date.day,
+        stored.chunks[12].body.date.day,
         test.funding.funding_options[0].deadline.day
     ); // default deadline
 
     // FundingProgram = FundingProgramName
-    assert_eq!(stored.chunks[19].address, 1);
+    assert_eq!(stored.chunks[13].address, 1);
     assert_eq!(
-        stored.chunks[19].parent,
+        stored.chunks[13].parent,
         Some(Parent {
             address: 2,
             variety: Rank::Top
         })
     );
     assert_eq!(
-        stored.chunks[19].visible,
+        stored.chunks[13].visible,
         test.funding.funding_options[1].visible
     );
     assert_eq!(
-        stored.chunks[19].title,
+        stored.chunks[13].title,
         test.funding.funding_options[1].title
     );
-    assert_eq!(stored.chunks[19].title, "Funding Option 2".to_string());
+    assert_eq!(stored.chunks[13].title, "Funding Option 2".to_string());
 
     // Reset Naïve field values to "empty"
-    assert_eq!(stored.chunks[19].body.phrase, "".to_string());
-    assert_eq!(stored.chunks[19].body.notes.notes.len(), 0);
+    assert_eq!(stored.chunks[13].body.phrase, "".to_string());
+    assert_eq!(stored.chunks[13].body.notes.notes.len(), 0);
 
     // FundingProgramAnnotation
-    assert_eq!(stored.chunks[20].address, 0);
+    assert_eq!(stored.chunks[14].address, 0);
     assert_eq!(
-        stored.chunks[20].parent,
+        stored.chunks[14].parent,
         Some(Parent {
             address: 1,
             variety: Rank::FundingProgram
         })
     );
     assert_eq!(
-        stored.chunks[20].body.notes.notes.len(),
+        stored.chunks[14].body.notes.notes.len(),
         test.funding.funding_options[1].annotation.notes.len()
     ); // empty field
-    assert_eq!(stored.chunks[20].body.notes.notes.len(), 0); // empty field
+    assert_eq!(stored.chunks[14].body.notes.notes.len(), 0); // empty field
 
     // FundingProgramHyperlink
-    assert_eq!(stored.chunks[21].address, 1);
+    assert_eq!(stored.chunks[15].address, 1);
     assert_eq!(
-        stored.chunks[21].parent,
+        stored.chunks[15].parent,
         Some(Parent {
             address: 1,
             variety: Rank::FundingProgram
         })
     );
     assert_eq!(
-        stored.chunks[21].body.phrase,
+        stored.chunks[15].body.phrase,
         test.funding.funding_options[1].hyperlink
     );
-    assert_eq!(stored.chunks[21].body.phrase, "".to_string()); // empty field
+    assert_eq!(stored.chunks[15].body.phrase, "".to_string()); // empty field
 
     // FundingProgramDeadline
-    assert_eq!(stored.chunks[22].address, 2);
+    assert_eq!(stored.chunks[16].address, 2);
     assert_eq!(
-        stored.chunks[22].parent,
+        stored.chunks[16].parent,
         Some(Parent {
             address: 1,
             variety: Rank::FundingProgram
         })
     );
     assert_eq!(
-        stored.chunks[22].body.date.year,
+        stored.chunks[16].body.date.year,
         test.funding.funding_options[0].deadline.year
     ); // default deadline
     assert_eq!(
-        stored.chunks[22].body.date.month,
+        stored.chunks[16].body.date.month,
         test.funding.funding_options[0].deadline.month
     ); // default deadline
     assert_eq!(
-        stored.chunks[22].body.date.day,
+        stored.chunks[16].body.date.day,
         test.funding.funding_options[0].deadline.day
     ); // default deadline
 
     // Reset Naïve field values to "empty"
-    assert_eq!(stored.chunks[22].body.phrase, "".to_string());
-    assert_eq!(stored.chunks[22].body.notes.notes.len(), 0);
+    assert_eq!(stored.chunks[16].body.phrase, "".to_string());
+    assert_eq!(stored.chunks[16].body.notes.notes.len(), 0);
 
     // Scope
-    assert_eq!(stored.chunks[23].address, 3);
-    assert_eq!(stored.chunks[23].parent, None);
-    assert_eq!(stored.chunks[23].visible, test.scope.visible);
-    assert_eq!(stored.chunks[23].title, test.scope.title);
+    assert_eq!(stored.chunks[17].address, 3);
+    assert_eq!(stored.chunks[17].parent, None);
+    assert_eq!(stored.chunks[17].visible, test.scope.visible);
+    assert_eq!(stored.chunks[17].title, test.scope.title);
     assert_eq!(
-        stored.chunks[23].title,
+        stored.chunks[17].title,
         "Project Scope: Objectives and Planned Activities".to_string()
     );
 
     // ScopeSuggestedTasks
     assert_eq!(
-        stored.chunks[24].body.notes.notes.len(),
+        stored.chunks[18].body.notes.notes.len(),
         test.scope.suggested_tasks.notes.len()
     );
     assert_eq!(
-        stored.chunks[24].body.notes.notes[0].note,
+        stored.chunks[18].body.notes.notes[0].note,
         test.scope.suggested_tasks.notes[0].note
     );
     assert_eq!(
-        stored.chunks[24].body.notes.notes[0].note,
+        stored.chunks[18].body.notes.notes[0].note,
         "Suggested Tasks note 1".to_string()
     );
 
     // ScopeObjectives
     assert_eq!(
-        stored.chunks[25].body.notes.notes.len(),
+        stored.chunks[19].body.notes.notes.len(),
         test.scope.objectives.notes.len()
     );
     assert_eq!(
-        stored.chunks[25].body.notes.notes[0].visible,
+        stored.chunks[19].body.notes.notes[0].visible,
         test.scope.objectives.notes[0].visible
     );
     assert_eq!(
-        stored.chunks[25].body.notes.notes[0].note,
+        stored.chunks[19].body.notes.notes[0].note,
         test.scope.objectives.notes[0].note
     );
     assert_eq!(
-        stored.chunks[25].body.notes.notes[0].note,
+        stored.chunks[19].body.notes.notes[0].note,
         "Objectives note 1".to_string()
     );
 
     // ScopeActivities
     assert_eq!(
-        stored.chunks[26].body.notes.notes.len(),
+        stored.chunks[20].body.notes.notes.len(),
         test.scope.activities.notes.len()
     );
     assert_eq!(
-        stored.chunks[26].body.notes.notes[0].visible,
+        stored.chunks[20].body.notes.notes[0].visible,
         test.scope.activities.notes[0].visible
     );
     assert_eq!(
-        stored.chunks[26].body.notes.notes[0].note,
+        stored.chunks[20].body.notes.notes[0].note,
         test.scope.activities.notes[0].note
     );
     assert_eq!(
-        stored.chunks[26].body.notes.notes[0].note,
+        stored.chunks[20].body.notes.notes[0].note,
         "Activities note 1".to_string()
     );
 
     // ScopeWorkPlan
     assert_eq!(
-        stored.chunks[27].body.notes.notes.len(),
+        stored.chunks[21].body.notes.notes.len(),
         test.scope.work_plan.notes.len()
     );
     assert_eq!(
-        stored.chunks[27].body.notes.notes[0].visible,
+        stored.chunks[21].body.notes.notes[0].visible,
         test.scope.work_plan.notes[0].visible
     );
     assert_eq!(
-        stored.chunks[27].body.notes.notes[0].note,
+        stored.chunks[21].body.notes.notes[0].note,
         test.scope.work_plan.notes[0].note
     );
     assert_eq!(
-        stored.chunks[27].body.notes.notes[0].note,
+        stored.chunks[21].body.notes.notes[0].note,
         "Work Plan note 1".to_string()
     );
 
     // ScopeTasks
     assert_eq!(
-        stored.chunks[28].body.notes.notes.len(),
+        stored.chunks[22].body.notes.notes.len(),
         test.scope.tasks.notes.len()
     );
     assert_eq!(
-        stored.chunks[28].body.notes.notes[0].visible,
+        stored.chunks[22].body.notes.notes[0].visible,
         test.scope.tasks.notes[0].visible
     );
     assert_eq!(
-        stored.chunks[28].body.notes.notes[0].note,
+        stored.chunks[22].body.notes.notes[0].note,
         test.scope.tasks.notes[0].note
     );
     assert_eq!(
-        stored.chunks[28].body.notes.notes[0].note,
+        stored.chunks[22].body.notes.notes[0].note,
         "Tasks note 1".to_string()
     );
 
     // Timeline
-    assert_eq!(stored.chunks[29].address, 4);
-    assert_eq!(stored.chunks[29].parent, None);
-    assert_eq!(stored.chunks[29].visible, test.timeline.visible);
-    assert_eq!(stored.chunks[29].title, test.timeline.title);
-    assert_eq!(stored.chunks[29].title, "Timeline".to_string());
+    assert_eq!(stored.chunks[23].address, 4);
+    assert_eq!(stored.chunks[23].parent, None);
+    assert_eq!(stored.chunks[23].visible, test.timeline.visible);
+    assert_eq!(stored.chunks[23].title, test.timeline.title);
+    assert_eq!(stored.chunks[23].title, "Timeline".to_string());
 
     // TimelineProjectTiming - ProjectStart
     assert_eq!(
-        stored.chunks[30].body.date.year,
+        stored.chunks[24].body.date.year,
         test.timeline.project_start.date.year()
     );
     assert_eq!(
-        stored.chunks[30].body.date.month,
+        stored.chunks[24].body.date.month,
         test.timeline.project_start.date.month()
     );
     assert_eq!(
-        stored.chunks[30].body.date.day,
+        stored.chunks[24].body.date.day,
         test.timeline.project_start.date.day()
     );
     assert_eq!(
-        stored.chunks[30].body.date.year,
+        stored.chunks[24].body.date.year,
         test.timeline.project_start.year
     );
     assert_eq!(
-        stored.chunks[30].body.date.month,
+        stored.chunks[24].body.date.month,
         test.timeline.project_start.month
     );
     assert_eq!(
-        stored.chunks[30].body.date.day,
+        stored.chunks[24].body.date.day,
         test.timeline.project_start.day
     );
 
     // TimelineProjectTiming - ProjectDuration
-    assert_eq!(stored.chunks[31].body.numeral, test.timeline.duration_years);
-    assert_eq!(stored.chunks[31].body.numeral, 4.5);
+    assert_eq!(stored.chunks[25].body.numeral, test.timeline.duration_years);
+    assert_eq!(stored.chunks[25].body.numeral, 4.5);
 
     // TimelineMilestones
     assert_eq!(
-        stored.chunks[32].body.notes.notes.len(),
+        stored.chunks[26].body.notes.notes.len(),
         test.timeline.milestones.notes.len()
     );
     assert_eq!(
-        stored.chunks[32].body.notes.notes[0].visible,
+        stored.chunks[26].body.notes.notes[0].visible,
         test.timeline.milestones.notes[0].visible
     );
     assert_eq!(
-        stored.chunks[32].body.notes.notes[0].note,
+        stored.chunks[26].body.notes.notes[0].note,
         test.timeline.milestones.notes[0].note
     );
     assert_eq!(
-        stored.chunks[32].body.notes.notes[0].note,
+        stored.chunks[26].body.notes.notes[0].note,
         "Milestones note 1".to_string()
     );
 
     // Scripting
-    assert_eq!(stored.chunks[33].address, 5);
-    assert_eq!(stored.chunks[33].parent, None);
-    assert_eq!(stored.chunks[33].visible, test.scripting.visible);
-    assert_eq!(stored.chunks[33].title, test.scripting.title);
+    assert_eq!(stored.chunks[27].address, 5);
+    assert_eq!(stored.chunks[27].parent, None);
+    assert_eq!(stored.chunks[27].visible, test.scripting.visible);
+    assert_eq!(stored.chunks[27].title, test.scripting.title);
     assert_eq!(
-        stored.chunks[33].title,
+        stored.chunks[27].title,
         "Who Will Write The Project Proposal".to_string()
     );
 
     // Scripting - ScriptingOption
-    assert_eq!(stored.chunks[34].body.option, test.scripting.answer);
-    assert_eq!(stored.chunks[34].body.option, ScriptingOption::Jointly);
+    assert_eq!(stored.chunks[28].body.option, test.scripting.answer);
+    assert_eq!(stored.chunks[28].body.option, ScriptingOption::Jointly);
 
     // Scripting - ScriptingNotes
     assert_eq!(
-        stored.chunks[35].body.notes.notes.len(),
+        stored.chunks[29].body.notes.notes.len(),
         test.scripting.scripting.notes.len()
     );
     assert_eq!(
-        stored.chunks[35].body.notes.notes[0].visible,
+        stored.chunks[29].body.notes.notes[0].visible,
         test.scripting.scripting.notes[0].visible
     );
     assert_eq!(
-        stored.chunks[35].body.notes.notes[0].note,
+        stored.chunks[29].body.notes.notes[0].note,
         test.scripting.scripting.notes[0].note
     );
     assert_eq!(
-        stored.chunks[35].body.notes.notes[0].note,
+        stored.chunks[29].body.notes.notes[0].note,
         "Scripting Option note 1".to_string()
     );
 
-    nn = 35;
+    nn = 29;
     nn += 1;
 
     // Team
@@ -3245,9 +3170,12 @@ fn conversion_into_store() {
     );
     assert_eq!(
         stored.chunks[nn].visible,
-        test.literature.references[0].visible
+        test.bibliography.references[0].visible
+    );
+    assert_eq!(
+        stored.chunks[nn].title,
+        test.bibliography.references[0].title
     );
-    assert_eq!(stored.chunks[nn].title, test.literature.references[0].title);
     assert_eq!(stored.chunks[nn].title, "Literature source 1".to_string());
 
     nn += 1;
@@ -3263,11 +3191,11 @@ fn conversion_into_store() {
     );
     assert_eq!(
         stored.chunks[nn].visible,
-        test.literature.references[0].visible
+        test.bibliography.references[0].visible
     );
     assert_eq!(
         stored.chunks[nn].body.phrase,
-        test.literature.references[0].hyperlink
+        test.bibliography.references[0].hyperlink
     );
     assert_eq!(
         stored.chunks[nn].body.phrase,
@@ -3287,15 +3215,15 @@ fn conversion_into_store() {
     );
     assert_eq!(
         stored.chunks[nn].body.notes.notes.len(),
-        test.literature.references[0].source_details.notes.len()
+        test.bibliography.references[0].source_details.notes.len()
     );
     assert_eq!(
         stored.chunks[nn].body.notes.notes[0].visible,
-        test.literature.references[0].source_details.notes[0].visible
+        test.bibliography.references[0].source_details.notes[0].visible
     );
     assert_eq!(
         stored.chunks[nn].body.notes.notes[0].note,
-        test.literature.references[0].source_details.notes[0].note
+        test.bibliography.references[0].source_details.notes[0].note
     );
     assert_eq!(
         stored.chunks[nn].body.notes.notes[0].note,
@@ -3316,9 +3244,12 @@ fn conversion_into_store() {
     );
     assert_eq!(
         stored.chunks[nn].visible,
-        test.literature.references[1].visible
+        test.bibliography.references[1].visible
+    );
+    assert_eq!(
+        stored.chunks[nn].title,
+        test.bibliography.references[1].title
     );
-    assert_eq!(stored.chunks[nn].title, test.literature.references[1].title);
     assert_eq!(stored.chunks[nn].title, "Literature source 2".to_string());
 
     nn += 1;
@@ -3334,11 +3265,11 @@ fn conversion_into_store() {
     );
     assert_eq!(
         stored.chunks[nn].visible,
-        test.literature.references[1].visible
+        test.bibliography.references[1].visible
     );
     assert_eq!(
         stored.chunks[nn].body.phrase,
-        test.literature.references[1].hyperlink
+        test.bibliography.references[1].hyperlink
     );
     assert_eq!(
         stored.chunks[nn].body.phrase,
@@ -3358,15 +3289,15 @@ fn conversion_into_store() {
     );
     assert_eq!(
         stored.chunks[nn].body.notes.notes.len(),
-        test.literature.references[1].source_details.notes.len()
+        test.bibliography.references[1].source_details.notes.len()
     );
     assert_eq!(
         stored.chunks[nn].body.notes.notes[0].visible,
-        test.literature.references[1].source_details.notes[0].visible
+        test.bibliography.references[1].source_details.notes[0].visible
     );
     assert_eq!(
         stored.chunks[nn].body.notes.notes[0].note,
-        test.literature.references[1].source_details.notes[0].note
+        test.bibliography.references[1].source_details.notes[0].note
     );
     assert_eq!(
         stored.chunks[nn].body.notes.notes[0].note,
@@ -3375,6 +3306,12 @@ fn conversion_into_store() {
 
     nn += 1;
 
+    // The key sources follow in the bibliography.
+    assert_eq!(stored.chunks[nn].title, "Key source 1".to_string());
+    assert_eq!(stored.chunks[nn + 3].title, "Key source 2".to_string());
+
+    nn += 6;
+
     // Resources
     assert_eq!(stored.chunks[nn].address, 11);
     assert_eq!(stored.chunks[nn].parent, None);
@@ -3707,4 +3644,72 @@ fn conversion_into_store() {
         stored.chunks[nn].body.notes.notes[0].note,
         "Attachments Other note 1".to_string()
     );
+
+    nn += 1;
+
+    assert_eq!(stored.chunks.len(), nn);
+
+    // Extensions
+    let extensions = &stored.extensions;
+    assert_eq!(extensions.citation_style, CitationStyle::Ieee);
+
+    let references = &extensions.references;
+    assert_eq!(references.len(), 4);
+    assert_eq!(references[0].id, test.bibliography.references[0].id);
+    assert_eq!(references[0].reading.status, ReadingStatus::Read);
+    assert_eq!(references[0].reading.relevance, 4);
+    assert_eq!(
+        references[0].reading.annotation,
+        "Literature Source 1 annotation".to_string()
+    );
+    assert_eq!(references[2].metadata.kind, SourceKind::Book);
+    assert_eq!(
+        references[2].metadata.authors,
+        "Smith, John; Doe, Ann".to_string()
+    );
+    assert_eq!(references[2].metadata.key, "smith2021".to_string());
+    assert_eq!(
+        extensions.key_references,
+        vec![
+            test.bibliography.references[2].id,
+            test.bibliography.references[3].id
+        ]
+    );
+
+    assert_eq!(
+        extensions.programmes,
+        vec![
+            test.funding.funding_options[0].id,
+            test.funding.funding_options[1].id
+        ]
+    );
+
+    let participants = &extensions.participants;
+    assert_eq!(participants.len(), 2);
+    assert_eq!(participants[0].id, test.team.participants[0].id);
+    assert_eq!(
+        participants[0].cv_entries,
+        test.team.participants[0].cv_entries
+    );
+    assert_eq!(
+        participants[0].cv_entries[0].place,
+        "Partner 1\nplace".to_string()
+    );
+    assert_eq!(participants[0].costs, test.team.participants[0].costs);
+    assert_eq!(participants[0].costs[0].amount, 3500.);
+    assert!(participants[1].costs.is_empty());
+
+    assert_eq!(extensions.cv_length, CvLength::ShortBio);
+    // The effort of the participants not in the project is dropped.
+    let package = &extensions.work_packages[0];
+    assert_eq!(package.id, test.team.work_packages[0].id);
+    assert_eq!(package.title, "Work package 1".to_string());
+    assert_eq!(package.effort.len(), 1);
+    assert_eq!(package.months(partner), 12.5);
+
+    // The note identities in the order of `Project::visit_notes()`.
+    assert_eq!(
+        extensions.note_identities[1],
+        vec![test.idea.problem.notes[0].id]
+    );
 }
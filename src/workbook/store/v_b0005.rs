@@ -2,12 +2,18 @@ use serde::{Deserialize, Serialize};
 
 use crate::workbook::{
     chapter::Variety,
+    identity::Identity,
     note::Notes,
     project::ProjectOwner,
+    reference_formats::citation_style::CitationStyle,
     sections::{
         budget::SectionBudget,
+        costs::Cost,
+        cv::{CvEntry, CvLength},
+        effort::WorkPackage,
         idea::SectionIdea,
         outcomes::SectionOutcomes,
+        references::{Reading, ReferenceMetadata},
         scope::SectionScope,
         scripting::ScriptingOption,
         team::{ModuleParticipant, SectionTeam},
@@ -15,7 +21,13 @@ use crate::workbook::{
     },
 };
 
-pub const DATA_FORMAT_VERSION: &str = "B0004"; // "B" for Bincode
+pub const DATA_FORMAT_VERSION: &str = "B0005"; // "B" for Bincode
+
+// The format B0004 has the owner after the format version. The versions
+// of the app reading it find an invalid length of the owner's email here,
+// so they report the format instead of opening the workbook and saving it
+// without the extensions they don't know.
+pub const FORMAT_GUARD: u64 = u64::MAX;
 
 // The Association struct establishes the order of sections (subsections)
 // for correct mapping.
@@ -119,26 +131,64 @@ impl Default for Association {
 #[derive(Deserialize, Serialize, Clone)]
 pub struct Store {
     pub format: String, // Data format version
+    pub guard: u64,     // See `FORMAT_GUARD`
     pub owner: ProjectOwner,
     pub record: String,
     pub resolution: usize,
 
     // The list of chapters, ordered by the app developer.
     pub chunks: Vec<Chunk>,
+
+    // The data introduced after the format B0004.
+    pub extensions: Extensions,
 }
 
 impl Default for Store {
     fn default() -> Self {
         Self {
             format: DATA_FORMAT_VERSION.to_string(),
+            guard: FORMAT_GUARD,
             owner: ProjectOwner::default(),
             record: String::new(),
             resolution: 1,
             chunks: Vec::new(),
+            extensions: Extensions::default(),
         }
     }
 }
 
+// The data of the items kept in the chunks and of the new settings,
+// stored as it is rather than mapped onto the chunks.
+// Note: The lists are in the order of the respective chunks.
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct Extensions {
+    pub citation_style: CitationStyle,
+    // The references of the bibliography, i.e. of the Literature Sources.
+    pub references: Vec<ReferenceExtension>,
+    pub key_references: Vec<Identity>,
+    pub programmes: Vec<Identity>,
+    pub participants: Vec<ParticipantExtension>,
+    pub cv_length: CvLength,
+    pub work_packages: Vec<WorkPackage>,
+    // The identifiers of the notes, one list per list of notes
+    // in the order of `Project::visit_notes()`.
+    pub note_identities: Vec<Vec<Identity>>,
+}
+
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct ReferenceExtension {
+    pub id: Identity,
+    pub metadata: ReferenceMetadata,
+    pub reading: Reading,
+}
+
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct ParticipantExtension {
+    pub id: Identity,
+    pub cv_entries: Vec<CvEntry>,
+    pub costs: Vec<Cost>,
+}
+
 #[derive(Deserialize, Serialize, Default, Clone)]
 pub struct Chunk {
     // Relative address on the respective level of the tree (`Rank`).
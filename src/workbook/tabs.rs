@@ -0,0 +1,449 @@
+// The workbooks open in the tabs, each with its own file, snapshots and changes,
+// and copying the notes, participants and references from one to another.
+// The active workbook is kept in the fields of the `Workbook` as before,
+// the others are put aside in the `tabs`.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use egui::{Context, RichText};
+
+use super::app::Workbook;
+use super::file::{file_name, Unsaved, WorkbookFile};
+use super::identity::Identity;
+use super::note::Note;
+use super::notifications::Notification;
+use super::project::{Project, ProjectOwner};
+use super::sections::funding::Programme;
+use super::sections::references::Reference;
+use super::sections::team::Participant;
+use super::snapshots::Snapshot;
+
+pub struct Tab {
+    pub id: Identity,
+    pub project: Project,
+    pub stored_projects: Vec<Snapshot>,
+    pub file: Option<WorkbookFile>,
+    // The fingerprint of the project saved or opened.
+    pub saved: u64,
+    pub modified: bool,
+}
+
+impl Tab {
+    pub fn new(owner: ProjectOwner) -> Self {
+        let project = Project::new(owner);
+        Tab {
+            id: Identity::new(),
+            saved: project.fingerprint(),
+            project,
+            stored_projects: Vec::new(),
+            file: None,
+            modified: false,
+        }
+    }
+}
+
+impl Workbook {
+    // Put the active workbook aside.
+    fn take_tab(&mut self) -> Tab {
+        Tab {
+            id: self.tab,
+            project: std::mem::take(&mut self.project),
+            stored_projects: std::mem::take(&mut self.stored_projects),
+            file: self.file.take(),
+            saved: self.saved,
+            modified: self.modified,
+        }
+    }
+
+    fn put_tab(&mut self, tab: Tab) {
+        self.tab = tab.id;
+        self.project = tab.project;
+        self.stored_projects = tab.stored_projects;
+        self.file = tab.file;
+        self.saved = tab.saved;
+        self.update_modified();
+
+        // The windows showing the snapshots or changing the project of another tab.
+        self.windows.compare_open = false;
+        self.windows.restore_open = false;
+        self.windows.merge_open = false;
+        self.windows.search.forget_undo();
+    }
+
+    pub fn switch_tab(&mut self, i: usize) {
+        if i == self.active_tab {
+            return;
+        }
+        let Some(tab) = self.tabs.get_mut(i).and_then(Option::take) else {
+            return;
+        };
+        let active = self.take_tab();
+        self.tabs[self.active_tab] = Some(active);
+        self.put_tab(tab);
+        self.active_tab = i;
+    }
+
+    pub fn new_tab(&mut self) {
+        self.tabs.push(Some(Tab::new(self.owner())));
+        self.switch_tab(self.tabs.len() - 1);
+    }
+
+    // Close the active tab, the last one is left blank.
+    pub fn close_tab(&mut self) {
+        if self.tabs.len() == 1 {
+            self.put_tab(Tab::new(self.owner()));
+            return;
+        }
+        self.tabs.remove(self.active_tab);
+        self.active_tab = self.active_tab.min(self.tabs.len() - 1);
+        if let Some(tab) = self.tabs[self.active_tab].take() {
+            self.put_tab(tab);
+        }
+    }
+
+    // Nothing is opened or written in the tab, it is used for the workbook opened.
+    pub fn is_blank(&self) -> bool {
+        self.file.is_none() && !self.modified
+    }
+
+    // The tab with the changes not saved, the active one first.
+    pub fn modified_tab(&self) -> Option<usize> {
+        if self.modified {
+            return Some(self.active_tab);
+        }
+        self.tabs
+            .iter()
+            .position(|tab| tab.as_ref().is_some_and(|tab| tab.modified))
+    }
+
+    // The tab of the workbook file open already.
+    pub fn file_tab(&self, path: &Path) -> Option<usize> {
+        let is_path = |file: &Option<WorkbookFile>| {
+            file.as_ref()
+                .and_then(|file| file.path.as_deref())
+                .is_some_and(|other| other == path)
+        };
+        if is_path(&self.file) {
+            return Some(self.active_tab);
+        }
+        self.tabs
+            .iter()
+            .position(|tab| tab.as_ref().is_some_and(|tab| is_path(&tab.file)))
+    }
+
+    pub fn other_tab_mut(&mut self, id: Identity) -> Option<&mut Tab> {
+        self.tabs.iter_mut().flatten().find(|tab| tab.id == id)
+    }
+
+    pub fn select_tab(&mut self, id: Identity) {
+        let i = self
+            .tabs
+            .iter()
+            .position(|tab| tab.as_ref().is_some_and(|tab| tab.id == id));
+        if let Some(i) = i {
+            self.switch_tab(i);
+        }
+    }
+
+    // The tabs are shown when more than one workbook is open.
+    pub fn show_tabs(&mut self, ctx: &Context) {
+        if self.tabs.len() < 2 {
+            return;
+        }
+        let mut selected = None;
+        let mut close = None;
+        egui::TopBottomPanel::top("workbook_tabs").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().item_spacing.x = 3.;
+                for (i, tab) in self.tabs.iter().enumerate() {
+                    let (name, modified) = match tab {
+                        Some(tab) => (file_name(tab.file.as_ref()), tab.modified),
+                        None => (self.file_name(), self.modified),
+                    };
+                    let name = if modified {
+                        format!("• {}", name)
+                    } else {
+                        name
+                    };
+                    if ui.selectable_label(i == self.active_tab, name).clicked() {
+                        selected = Some(i);
+                    }
+                    if ui.small_button("×").on_hover_text("Close").clicked() {
+                        close = Some(i);
+                    }
+                    ui.separator();
+                }
+            });
+        });
+        if let Some(i) = close {
+            self.switch_tab(i);
+            self.request(Unsaved::CloseTab, ctx);
+        } else if let Some(i) = selected {
+            self.switch_tab(i);
+        }
+    }
+}
+
+// The notes, programmes, participants and references copied from a workbook,
+// the notes with the name of their list.
+#[derive(Clone, Default)]
+pub struct Clipboard {
+    pub notes: Vec<(&'static str, Note)>,
+    pub programmes: Vec<Programme>,
+    pub participants: Vec<Participant>,
+    pub references: Vec<Reference>,
+}
+
+impl Clipboard {
+    pub fn copy(project: &Project, chosen: &HashSet<Identity>) -> Self {
+        let mut clipboard = Clipboard::default();
+        project.visit_section_notes(&mut |name, notes| {
+            for note in notes.notes.iter() {
+                if chosen.contains(&note.id) {
+                    clipboard.notes.push((name, note.clone()));
+                }
+            }
+        });
+        clipboard.programmes = project
+            .funding
+            .funding_options
+            .iter()
+            .filter(|programme| chosen.contains(&programme.id))
+            .cloned()
+            .collect();
+        clipboard.participants = project
+            .team
+            .participants
+            .iter()
+            .filter(|participant| chosen.contains(&participant.id))
+            .cloned()
+            .collect();
+        clipboard.references = project
+            .bibliography
+            .references
+            .iter()
+            .filter(|reference| chosen.contains(&reference.id))
+            .cloned()
+            .collect();
+        clipboard
+    }
+
+    pub fn len(&self) -> usize {
+        self.notes.len() + self.programmes.len() + self.participants.len() + self.references.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Append the items to the project as new ones, the notes to the lists
+    // of the same name. The references keep their identifiers, so the ones
+    // in the bibliography already are not repeated.
+    pub fn paste(&self, project: &mut Project) {
+        project.visit_section_notes_mut(&mut |name, notes| {
+            for (list, note) in self.notes.iter() {
+                if *list == name {
+                    notes.notes.push(Note {
+                        id: Identity::new(),
+                        ..note.clone()
+                    });
+                }
+            }
+        });
+        for programme in self.programmes.iter() {
+            let mut programme = programme.clone();
+            programme.id = Identity::new();
+            for note in programme.annotation.notes.iter_mut() {
+                note.id = Identity::new();
+            }
+            project.funding.funding_options.push(programme);
+        }
+        for participant in self.participants.iter() {
+            let mut participant = participant.clone();
+            participant.renew_identities();
+            project.team.participants.push(participant);
+        }
+        for reference in self.references.iter() {
+            let mut reference = reference.clone();
+            for note in reference.source_details.notes.iter_mut() {
+                note.id = Identity::new();
+            }
+            project.bibliography.add(reference);
+        }
+        project.identify();
+    }
+}
+
+// The state of the "Copy" window.
+#[derive(Default)]
+pub struct Copying {
+    pub chosen: HashSet<Identity>,
+}
+
+// The checkbox of the item to copy or import.
+pub fn choose(ui: &mut egui::Ui, chosen: &mut HashSet<Identity>, id: Identity, text: &str) {
+    ui.horizontal(|ui| {
+        let mut selected = chosen.contains(&id);
+        if ui.checkbox(&mut selected, "").changed() {
+            if selected {
+                chosen.insert(id);
+            } else {
+                chosen.remove(&id);
+            }
+        }
+        ui.add(egui::Label::new(text).truncate(true));
+    });
+}
+
+impl Workbook {
+    pub fn show_copy(&mut self, ctx: &Context) {
+        if !self.windows.copy_open {
+            return;
+        }
+        let mut copying = std::mem::take(&mut self.windows.copy);
+        let mut open = self.windows.copy_open;
+        let mut copy = false;
+        egui::Window::new("Copy")
+            .open(&mut open)
+            .default_width(420.)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(format!(
+                        "Choose the items of “{}” to paste into another workbook.",
+                        self.file_name()
+                    ))
+                    .small()
+                    .weak(),
+                );
+                ui.separator();
+
+                let project = &self.project;
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .max_height(420.)
+                    .show(ui, |ui| {
+                        egui::CollapsingHeader::new("Notes")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                project.visit_section_notes(&mut |name, notes| {
+                                    if notes.notes.is_empty() {
+                                        return;
+                                    }
+                                    ui.label(RichText::new(name).strong());
+                                    for note in notes.notes.iter() {
+                                        choose(ui, &mut copying.chosen, note.id, &note.note);
+                                    }
+                                    ui.add_space(6.);
+                                });
+                            });
+                        egui::CollapsingHeader::new("Programmes")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for programme in project.funding.funding_options.iter() {
+                                    choose(ui, &mut copying.chosen, programme.id, &programme.title);
+                                }
+                            });
+                        egui::CollapsingHeader::new("Participants")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for participant in project.team.participants.iter() {
+                                    choose(
+                                        ui,
+                                        &mut copying.chosen,
+                                        participant.id,
+                                        &participant.name,
+                                    );
+                                }
+                            });
+                        egui::CollapsingHeader::new("References")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for reference in project.bibliography.references.iter() {
+                                    choose(ui, &mut copying.chosen, reference.id, &reference.title);
+                                }
+                            });
+                    });
+                ui.separator();
+
+                if ui
+                    .add_enabled(!copying.chosen.is_empty(), egui::Button::new("Copy"))
+                    .on_hover_text("Paste them with Edit › Paste in the workbook of another tab")
+                    .clicked()
+                {
+                    copy = true;
+                }
+            });
+
+        if copy {
+            self.clipboard = Clipboard::copy(&self.project, &copying.chosen);
+            self.notifications.push(Notification::info(
+                "Copied",
+                format!("{} items of “{}”", self.clipboard.len(), self.file_name()),
+            ));
+            copying = Copying::default();
+            open = false;
+        }
+        self.windows.copy_open = open;
+        self.windows.copy = copying;
+    }
+}
+
+// Test that each tab keeps its project, and the items copied
+// from one are pasted into another as new ones.
+#[test]
+fn workbook_tabs() {
+    let mut workbook = Workbook::default();
+    workbook.settings.customer.name = "Jane Doe".to_string();
+    workbook.project.idea.problem.notes.push(Note {
+        note: "Problem".to_string(),
+        ..Note::new()
+    });
+    workbook.project.team.participants.push(Participant {
+        name: "Jane Doe".to_string(),
+        ..Default::default()
+    });
+    workbook
+        .project
+        .bibliography
+        .references
+        .push(Reference::default());
+    workbook.modified = true;
+    assert!(!workbook.is_blank());
+
+    let chosen = HashSet::from([
+        workbook.project.idea.problem.notes[0].id,
+        workbook.project.team.participants[0].id,
+        workbook.project.bibliography.references[0].id,
+    ]);
+    workbook.clipboard = Clipboard::copy(&workbook.project, &chosen);
+    assert_eq!(workbook.clipboard.len(), 3);
+
+    workbook.new_tab();
+    assert_eq!(workbook.tabs.len(), 2);
+    assert_eq!(workbook.active_tab, 1);
+    assert!(workbook.is_blank());
+    assert_eq!(workbook.project.owner.name, "Jane Doe");
+    assert_eq!(workbook.modified_tab(), Some(0));
+
+    workbook.clipboard.paste(&mut workbook.project);
+    workbook.clipboard.paste(&mut workbook.project);
+    let problem = &workbook.project.idea.problem.notes;
+    assert_eq!(problem.len(), 2);
+    assert_eq!(problem[0].note, "Problem");
+    assert!(!chosen.contains(&problem[0].id));
+    assert_ne!(problem[0].id, problem[1].id);
+    assert_eq!(workbook.project.team.participants.len(), 2);
+    assert_eq!(workbook.project.bibliography.references.len(), 1);
+
+    workbook.switch_tab(0);
+    assert!(workbook.modified);
+    assert_eq!(workbook.project.idea.problem.notes.len(), 1);
+    workbook.close_tab();
+    assert_eq!(workbook.tabs.len(), 1);
+    assert_eq!(workbook.project.idea.problem.notes.len(), 2);
+    workbook.close_tab();
+    assert!(workbook.is_blank());
+    assert!(workbook.project.idea.problem.notes.is_empty());
+}
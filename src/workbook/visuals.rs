@@ -16,6 +16,14 @@ pub fn style_bin_button(color: Color32) -> Button<'static> {
     .frame(false)
 }
 
+pub fn style_snippet_button(color: Color32) -> Button<'static> {
+    Button::new(
+        egui::RichText::new("\u{1F4CC}") // 📌 PUSHPIN
+            .color(color),
+    )
+    .frame(false)
+}
+
 pub fn style_move_button(color: Color32) -> Button<'static> {
     Button::new(egui::RichText::new("⬆").color(color))
 }